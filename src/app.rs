@@ -2,13 +2,21 @@ use anyhow::{Context, Result};
 use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use git2::{DiffOptions, Repository, Status, StatusOptions};
 use ratatui::widgets::ListState;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::thread::{self, JoinHandle};
-use std::time::Instant;
-
-use crate::config::RepoConfig;
+use std::time::{Duration, Instant};
+
+use crate::changelog::{self, Fragment};
+use crate::config::{
+    ClipboardBackend, Config, Confirmations, DateFormat, FileSort, LogOrder, Profile, RepoConfig,
+    StartTab, StartupConfig, profile_for_remote,
+};
+use crate::date;
+use crate::diff_viewer::{self, DiffHunk, DiffPager, ModeChange, TextIssue};
+use crate::graph::{GraphBuilder, GraphRow};
+use crate::i18n;
 use crate::version::{self, VersionFile};
 
 // ============================================================================
@@ -16,6 +24,15 @@ use crate::version::{self, VersionFile};
 // ============================================================================
 pub const HEAD_LABEL: &str = "[HEAD]";
 
+/// Commits loaded into the Log tab at a time
+const LOG_BATCH: usize = 100;
+
+/// Subject prefix of save-point commits made with `W` (undone with `U`)
+const WIP_PREFIX: &str = "WIP: ";
+
+/// Changed lines shown by the quick look (`K`) before it cuts off
+const QUICK_LOOK_LINES: usize = 20;
+
 pub fn remote_label(branch: &str) -> String {
     format!("[{branch}]")
 }
@@ -27,6 +44,9 @@ pub fn remote_label(branch: &str) -> String {
 /// Braille spinner characters for smooth animation
 pub const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
+/// Commit message lines shown at once; longer messages scroll with the cursor
+pub const MAX_MESSAGE_LINES: usize = 6;
+
 /// Processing state for async operations
 #[derive(Clone, PartialEq, Debug)]
 pub enum Processing {
@@ -35,6 +55,9 @@ pub enum Processing {
     Pulling,
     Committing,
     PushingTags,
+    Releasing,
+    Generating,
+    Fetching,
 }
 
 impl Processing {
@@ -45,6 +68,23 @@ impl Processing {
             Processing::Pulling => "Pulling...",
             Processing::Committing => "Committing...",
             Processing::PushingTags => "Pushing tags...",
+            Processing::Releasing => "Pushing release...",
+            Processing::Generating => "Generating message...",
+            Processing::Fetching => "Fetching...",
+        }
+    }
+
+    /// Short name used in the queue display ("queued: Pull")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Processing::None => "",
+            Processing::Pushing => "Push",
+            Processing::Pulling => "Pull",
+            Processing::Committing => "Commit",
+            Processing::PushingTags => "Push tags",
+            Processing::Releasing => "Release",
+            Processing::Generating => "Suggest",
+            Processing::Fetching => "Fetch",
         }
     }
 
@@ -53,11 +93,181 @@ impl Processing {
     }
 }
 
+/// One-key follow-up offered after a common failure
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RecoveryAction {
+    PullRebaseThenPush,
+    ForceWithLease,
+    CommitNoVerify,
+    RollbackRelease,
+}
+
+impl RecoveryAction {
+    pub fn key(self) -> char {
+        match self {
+            Self::PullRebaseThenPush => 'r',
+            Self::ForceWithLease => 'f',
+            Self::CommitNoVerify => 'n',
+            Self::RollbackRelease => 'u',
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PullRebaseThenPush => "pull --rebase, then push",
+            Self::ForceWithLease => "push --force-with-lease",
+            Self::CommitNoVerify => "retry commit with --no-verify",
+            Self::RollbackRelease => "roll back tag and bump commit",
+        }
+    }
+}
+
+/// Repository state that makes git operations fail or behave unexpectedly,
+/// shown as a banner under the tabs
+#[derive(Clone, Debug, PartialEq)]
+pub enum HealthIssue {
+    /// `.git/index.lock` exists: another git process, or one that crashed.
+    /// Holds the lock's age in seconds when its mtime is readable
+    IndexLock(Option<u64>),
+    /// A merge, rebase, cherry-pick, revert or bisect was left unfinished
+    Unfinished(&'static str, &'static str),
+    /// `.git` can't be written (read-only filesystem or permissions)
+    ReadOnly,
+    /// No `git` on PATH: everything that runs the CLI is off, browsing still works
+    NoGitCli,
+}
+
+impl HealthIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::IndexLock(age) => format!(
+                "index.lock exists{}: another git process is running, or one crashed (! to remove)",
+                age.map(|s| format!(" ({})", format_age(s)))
+                    .unwrap_or_default()
+            ),
+            Self::Unfinished(what, abort) => {
+                format!(
+                    "{} in progress: finish it, or run git {} --abort",
+                    what, abort
+                )
+            }
+            Self::ReadOnly => ".git is read-only: changes can't be saved".to_string(),
+            Self::NoGitCli => "git not found on PATH: commits, push/pull/fetch, stage all, \
+                               staging folders and other actions that run git are disabled \
+                               (single files can still be staged)"
+                .to_string(),
+        }
+    }
+
+    /// Lock and read-only problems make every write fail
+    pub fn blocks_writes(&self) -> bool {
+        matches!(self, Self::IndexLock(_) | Self::ReadOnly)
+    }
+}
+
+/// Whether the `git` command can be run at all (most operations shell out to it)
+pub fn git_cli_available() -> bool {
+    std::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{}s old", seconds),
+        60..3600 => format!("{} min old", seconds / 60),
+        _ => format!("{} h old", seconds / 3600),
+    }
+}
+
+/// Follow-ups for a failed operation, based on its git output
+pub fn suggest_recovery(
+    operation: &Processing,
+    output: &str,
+    has_commit_hooks: bool,
+) -> Vec<RecoveryAction> {
+    match operation {
+        Processing::Pushing
+            if output.contains("non-fast-forward") || output.contains("fetch first") =>
+        {
+            vec![
+                RecoveryAction::PullRebaseThenPush,
+                RecoveryAction::ForceWithLease,
+            ]
+        }
+        // Hooks print their own output, so rule out git's own refusals instead
+        Processing::Committing
+            if has_commit_hooks
+                && !output.contains("nothing to commit")
+                && !output.contains("no changes added")
+                && !output.contains("Please tell me who you are") =>
+        {
+            vec![RecoveryAction::CommitNoVerify]
+        }
+        // Rebasing would strand the tag on the old commit, so only undo
+        Processing::Releasing => vec![RecoveryAction::RollbackRelease],
+        _ => Vec::new(),
+    }
+}
+
+/// What a commit records: the index, or working tree content (Ctrl+A, Ctrl+O / Ctrl+F, `W`)
+#[derive(Clone, Debug, Default, PartialEq)]
+enum CommitScope {
+    #[default]
+    Index,
+    /// `git commit --all`: every tracked change; git stages them as it commits
+    Tracked,
+    /// `git commit -- <paths>`: only these paths, whatever else is staged
+    Paths(Vec<String>),
+    /// `W`: everything, untracked files included, staged just before committing
+    /// with this message
+    Wip(String),
+}
+
+/// Everything `git commit` needs, kept for the --no-verify retry
+#[derive(Clone)]
+struct CommitAttempt {
+    message: String,
+    amend: bool,
+    allow_empty: bool,
+    scope: CommitScope,
+    /// Author and committer date (unix seconds) instead of now
+    date: Option<i64>,
+    /// `--signoff`: append `Signed-off-by:` with the committer identity
+    signoff: bool,
+}
+
+/// What a half-finished release created, so it can be undone
+#[derive(Clone, Debug)]
+pub struct ReleaseRollback {
+    pub tag_name: String,
+    /// HEAD before the bump commit
+    pub previous: git2::Oid,
+    pub bump_commit: git2::Oid,
+}
+
+/// What one file's diff changes: line counts, file mode and binary sizes
+#[derive(Default)]
+struct DiffSummary {
+    stats: Option<(usize, usize)>,
+    mode_change: Option<ModeChange>,
+    binary_size: Option<(u64, u64)>,
+}
+
+/// A background operation waiting for the running one to finish
+pub struct QueuedOperation {
+    pub state: Processing,
+    run: Box<dyn FnOnce() -> GitResult + Send>,
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Debug)]
 pub enum Tab {
     #[default]
     Files,
     Log,
+    Stash,
+    Branches,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -78,6 +288,8 @@ pub enum InputMode {
     UncommittedWarning,
     DiscardConfirm,
     DeleteTagConfirm,
+    TagDetail,
+    TagsView,
     DiffConfirm,
     WorktreeTypeSelect,
     WorktreeNewBranch,
@@ -85,6 +297,52 @@ pub enum InputMode {
     WorktreeRemoveConfirm,
     CherryPickInput,
     BranchSelect,
+    HunkView,
+    StashInput,
+    StashDropConfirm,
+    BranchNameInput,
+    BranchDeleteConfirm,
+    GitignoreInput,
+    MoveInput,
+    QuickLook,
+    Review,
+    CommitWarning,
+    OutputView,
+    RecoveryPrompt,
+    ChangelogInput,
+    AmendPreview,
+    CommitReview,
+    CommitTypeSelect,
+    CommitScopeInput,
+    CoAuthorSelect,
+    CommitSizeWarning,
+    CommitDateInput,
+    CommitFileSelect,
+    ProfileSelect,
+    UndoCommitConfirm,
+    CheckoutConfirm,
+    RemoveLockConfirm,
+    IncomingView,
+    CompareView,
+    ReflogView,
+    ReflogResetConfirm,
+    LogSearch,
+    CommitDetail,
+    CommitFileDiff,
+}
+
+/// One file of the commit that amending HEAD would produce
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmendFile {
+    pub path: String,
+    /// Status letter (A/M/D/R/T) of the change
+    pub status: char,
+    /// Already part of HEAD
+    pub in_head: bool,
+    /// Staged now, so amending folds it in
+    pub staged: bool,
+    /// Lines added/removed by the amended commit (None for binary files)
+    pub stats: Option<(usize, usize)>,
 }
 
 /// Pending version update information
@@ -94,6 +352,27 @@ pub struct PendingVersionUpdate {
     pub new_version: String,
     pub files: Vec<VersionFile>,
     pub commit_id: String,
+    /// Changelog fragments to fold into the changelog (and delete) in the release commit
+    pub fragments: Vec<Fragment>,
+    /// Push the bump commit and tag at the end of the pipeline
+    pub push: bool,
+}
+
+/// `a` in the Log tab: what a tag records beyond its name
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagDetail {
+    pub name: String,
+    pub target_id: git2::Oid,
+    /// Short id and subject of the tagged commit
+    pub target: String,
+    /// "Name <email>"; None for lightweight tags
+    pub tagger: Option<String>,
+    /// Tagging date, or the commit date of a lightweight tag
+    pub date: String,
+    pub timestamp: i64,
+    /// First line of the annotation
+    pub annotation: Option<String>,
+    pub pushed: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -108,6 +387,32 @@ pub struct FileEntry {
     pub status: FileStatus,
     pub staged: bool,
     pub diff_stats: Option<(usize, usize)>,
+    /// Line ending / encoding problems detected in the staged content
+    pub text_issues: Vec<TextIssue>,
+    /// Registered with `git add -N`: tracked in the index, content not yet staged
+    pub intent_to_add: bool,
+    /// Index flag that hides local changes from status
+    pub hidden: Option<HiddenFlag>,
+    /// (old, new) file mode when the change flips it, e.g. 100644 → 100755
+    pub mode_change: Option<ModeChange>,
+    /// (old, new) size in bytes when git treats the file as binary
+    pub binary_size: Option<(u64, u64)>,
+    /// Link target when the file is a symlink
+    pub symlink_target: Option<String>,
+    /// Untracked directory that is a git repository of its own
+    pub nested_repo: bool,
+    /// Lockfile / generated output (`linguist-generated` or `[generated] paths`)
+    pub generated: bool,
+    /// Conflict regions (`<<<<<<<` markers) still in a conflicted text file;
+    /// None when git left no markers (modify/delete, binary) or nothing conflicts
+    pub conflicts: Option<usize>,
+}
+
+/// `git update-index` flags that make git ignore local changes to a tracked file
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HiddenFlag {
+    SkipWorktree,
+    AssumeUnchanged,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -116,12 +421,54 @@ pub enum FileStatus {
     Modified,
     Deleted,
     Untracked,
+    /// Unmerged after a merge, revert or cherry-pick stopped
+    Conflicted,
+}
+
+/// `f` in the Files tab: list only one kind of change
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Untracked,
+    Modified,
+    Deleted,
+}
+
+impl StatusFilter {
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Untracked,
+            Self::Untracked => Self::Modified,
+            Self::Modified => Self::Deleted,
+            Self::Deleted => Self::All,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Untracked => "untracked",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    fn matches(self, status: FileStatus) -> bool {
+        match self {
+            Self::All => true,
+            Self::Untracked => status == FileStatus::Untracked,
+            Self::Modified => status == FileStatus::Modified,
+            Self::Deleted => status == FileStatus::Deleted,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PendingDiscardAction {
     RestoreTracked,
     TrashUntracked,
+    DeleteUntracked,
 }
 
 impl PendingDiscardAction {
@@ -129,6 +476,7 @@ impl PendingDiscardAction {
         match self {
             Self::RestoreTracked => " Discard Changes ",
             Self::TrashUntracked => " Move to Trash ",
+            Self::DeleteUntracked => " Delete File ",
         }
     }
 
@@ -136,13 +484,14 @@ impl PendingDiscardAction {
         match self {
             Self::RestoreTracked => "Discard changes to:",
             Self::TrashUntracked => "Move to trash:",
+            Self::DeleteUntracked => "Delete from disk:",
         }
     }
 
     pub fn confirm_warning(self) -> &'static str {
         match self {
-            Self::RestoreTracked => "This cannot be undone!",
-            Self::TrashUntracked => "You can restore it from the trash.",
+            Self::RestoreTracked | Self::DeleteUntracked => "This cannot be undone!",
+            Self::TrashUntracked => "Kept in the system trash, or .git/siori/trash",
         }
     }
 
@@ -150,6 +499,7 @@ impl PendingDiscardAction {
         match self {
             Self::RestoreTracked => "discard",
             Self::TrashUntracked => "trash",
+            Self::DeleteUntracked => "delete",
         }
     }
 }
@@ -165,8 +515,15 @@ impl PendingDiscard {
         if file.staged {
             return Err("Unstage file first (Space)");
         }
+        if file.intent_to_add {
+            return Err("Undo intent-to-add first (N)");
+        }
+        if file.hidden.is_some() {
+            return Err("Unhide file first (H)");
+        }
         let action = match file.status {
             FileStatus::Untracked => PendingDiscardAction::TrashUntracked,
+            FileStatus::Conflicted => return Err("Resolve the conflict, then stage it (Space)"),
             FileStatus::Added | FileStatus::Modified | FileStatus::Deleted => {
                 PendingDiscardAction::RestoreTracked
             }
@@ -178,10 +535,144 @@ impl PendingDiscard {
     }
 }
 
+/// A single unstaged hunk pending discard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingHunkDiscard {
+    pub path: String,
+    pub index: usize,
+    pub header: String,
+    /// The hunk came from a diff shown anyway (see `DiffView::force_text`)
+    pub force_text: bool,
+}
+
 #[derive(Clone, Debug)]
 pub enum PendingDiscardTarget {
     Single(PendingDiscard),
     All(Vec<PendingDiscard>),
+    Hunk(PendingHunkDiscard),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitProblemKind {
+    ConflictMarker,
+    TrailingWhitespaceOnly,
+}
+
+impl CommitProblemKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ConflictMarker => "conflict marker",
+            Self::TrailingWhitespaceOnly => "trailing whitespace only",
+        }
+    }
+}
+
+/// A suspicious staged hunk found right before committing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitProblem {
+    pub path: String,
+    pub hunk_index: usize,
+    pub kind: CommitProblemKind,
+}
+
+/// In-app diff view of a single file, split into hunks
+#[derive(Clone, Debug)]
+pub struct DiffView {
+    pub path: String,
+    pub staged: bool,
+    pub hunks: Vec<DiffHunk>,
+    pub selected: usize,
+    pub issues: Vec<TextIssue>,
+    pub mode_change: Option<ModeChange>,
+    pub binary_size: Option<(u64, u64)>,
+    pub symlink_target: Option<String>,
+    /// `-diff` or `diff=<driver>` from .gitattributes
+    pub diff_attribute: Option<String>,
+    /// Hunks hidden because of `diff_attribute` until "show anyway"
+    pub collapsed: bool,
+    /// Line mode (V): (anchor, cursor) line indexes in the selected hunk
+    pub line_selection: Option<(usize, usize)>,
+}
+
+impl DiffView {
+    /// Hunks were loaded with "show anyway", so hunk actions must diff the same way
+    pub fn force_text(&self) -> bool {
+        self.diff_attribute.is_some() && !self.collapsed
+    }
+}
+
+/// `K` in the Files tab: the first changed lines of the selected file, over the list
+#[derive(Clone, Debug)]
+pub struct QuickLook {
+    pub path: String,
+    pub staged: bool,
+    /// Hunk headers and their `+`/`-` lines, up to QUICK_LOOK_LINES changed lines
+    pub lines: Vec<String>,
+    /// Changed lines in the whole diff; more than shown when it was cut off
+    pub changed: usize,
+}
+
+/// `B` in the Files tab: the worktree as it was when the review started,
+/// and what has been decided about the changes made since
+#[derive(Clone, Debug)]
+pub struct ReviewSession {
+    /// Unix time the session started
+    pub started: i64,
+    /// The worktree at the start as a tree: the index plus every file that differed from it
+    baseline: git2::Oid,
+    /// Approved paths and their worktree hash then; saving again makes them pending again
+    approved: HashMap<String, Option<git2::Oid>>,
+    /// Rejected paths and a tree holding the rejected version (None: it didn't exist), for `u`
+    rejected: Vec<(String, Option<git2::Oid>)>,
+    /// Files changed since the start, by path
+    pub files: Vec<ReviewFile>,
+    pub selected: usize,
+    /// Hunk headers and lines of the selected file's change since the start
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+impl ReviewSession {
+    pub fn pending(&self) -> usize {
+        self.files.iter().filter(|f| !f.approved).count()
+    }
+}
+
+/// A file in the review session
+#[derive(Clone, Debug)]
+pub struct ReviewFile {
+    pub path: String,
+    /// Added, Modified or Deleted since the session started
+    pub status: FileStatus,
+    pub approved: bool,
+    /// Hash of the worktree file as it is now (None: missing), for approvals
+    current: Option<git2::Oid>,
+}
+
+/// A local branch in the Branches tab
+#[derive(Clone, Debug)]
+pub struct BranchEntry {
+    pub name: String,
+    pub is_head: bool,
+    /// Tracked remote branch, e.g. "origin/main"
+    pub upstream: Option<String>,
+    /// (ahead, behind) its upstream
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Tip commit: short id, subject and relative time
+    pub id: String,
+    pub subject: String,
+    pub time: String,
+    /// Reachable from its upstream (HEAD when it has none), so `git branch -d` takes it
+    pub merged: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct StashEntry {
+    pub index: usize,
+    pub message: String,
+    pub id: git2::Oid,
+    /// (files changed, insertions, deletions)
+    pub diff_stats: Option<(usize, usize, usize)>,
 }
 
 #[derive(Clone)]
@@ -189,63 +680,291 @@ pub struct CommitEntry {
     pub id: String,
     pub full_id: git2::Oid,
     pub message: String,
+    pub author: String,
     pub time: String,
+    /// "2024-05-03 14:22" in the committer's time zone
+    pub date: String,
     pub is_head: bool,
+    pub local_branches: Vec<String>,
     pub remote_branches: Vec<String>,
     pub tags: Vec<TagInfo>,
+    /// On the current branch but not on its upstream
+    pub unpushed: bool,
+    /// More than one for merge commits
+    pub parents: Vec<git2::Oid>,
+    /// Lanes drawn beside the commit in the Log tab
+    pub graph: GraphRow,
+}
+
+impl CommitEntry {
+    pub fn is_merge(&self) -> bool {
+        self.parents.len() > 1
+    }
+
+    /// `/` in the Log tab: subject or author contains `query` (any case), or the hash starts with it
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.message.to_lowercase().contains(&query)
+            || self.author.to_lowercase().contains(&query)
+            || self.full_id.to_string().starts_with(&query)
+    }
+}
+
+/// Everything the Log tab's detail pane shows for one commit
+#[derive(Clone, Debug)]
+pub struct CommitDetail {
+    pub id: String,
+    pub full_id: git2::Oid,
+    /// Full message, subject and body
+    pub message: String,
+    pub author: String,
+    pub author_date: String,
+    pub committer: String,
+    pub commit_date: String,
+    /// Short ids of the parents
+    pub parents: Vec<String>,
+    /// Every remote branch and tag pointing here, however the log trims them
+    pub refs: Vec<String>,
+    /// Changes against `diff_parent`
+    pub files: Vec<CommitDetailFile>,
+    /// Parent (1-based) the files are compared with; None for a merge's
+    /// combined diff (`git show --cc`), cycled with `p`
+    pub diff_parent: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitDetailFile {
+    pub path: String,
+    /// Status letter (A/M/D/R/T) of the change
+    pub status: char,
+    /// Lines added/removed (None for binary files)
+    pub stats: Option<(usize, usize)>,
+}
+
+/// What pulling would bring in: commits on the upstream that HEAD lacks
+#[derive(Clone, Debug)]
+pub struct Incoming {
+    pub upstream: String,
+    /// (short id, subject, author, relative time), newest first
+    pub commits: Vec<(String, String, String, String)>,
+    /// Changes since the merge base, as `git diff HEAD...upstream`
+    pub files: Vec<CommitDetailFile>,
+}
+
+/// `d` in the Log tab: what changed from the older of the marked and
+/// selected commits to the newer, as `git diff from..to`
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub from: git2::Oid,
+    pub to: git2::Oid,
+    pub files: Vec<CommitDetailFile>,
+}
+
+impl Comparison {
+    /// "abc1234..def5678"
+    pub fn range(&self) -> String {
+        format!("{:.7}..{:.7}", self.from, self.to)
+    }
+}
+
+/// One move of HEAD, newest first, as `git reflog` lists it
+#[derive(Clone, Debug)]
+pub struct ReflogEntry {
+    /// `HEAD@{n}`
+    pub selector: String,
+    pub id: git2::Oid,
+    /// What moved HEAD, e.g. "rebase (finish): returning to refs/heads/main"
+    pub message: String,
+    pub time: String,
 }
 
 /// Result from background git operations
 pub type GitResult = std::result::Result<String, String>;
 
+/// Files changed, insertions, deletions
+pub type DiffStats = (usize, usize, usize);
+
 /// Run a git command in the specified repository directory
 fn run_git(
     repo_path: &std::path::Path,
     args: &[&str],
     success_msg: &str,
     error_prefix: &str,
+) -> GitResult {
+    run_git_with_env(repo_path, args, &[], success_msg, error_prefix)
+}
+
+/// Run `command` through the shell with the staged diff on stdin; Ok holds its output
+fn run_suggest_command(repo_path: &std::path::Path, command: &str) -> GitResult {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let diff = Command::new("git")
+        .current_dir(repo_path)
+        .args(["diff", "--cached", "--no-color"])
+        .output()
+        .map_err(|e| format!("Failed to read staged diff: {e}"))?;
+    let mut child = Command::new("sh")
+        .current_dir(repo_path)
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run `{}`: {e}", command))?;
+    // Write from another thread so a command that answers early can't deadlock us
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = thread::spawn(move || {
+        let _ = stdin.write_all(&diff.stdout);
+    });
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run `{}`: {e}", command))?;
+    let _ = writer.join();
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(with_details(format!("`{}` failed", command), stderr.trim()));
+    }
+    if stdout.is_empty() {
+        return Err(format!("`{}` printed no message", command));
+    }
+    Ok(stdout)
+}
+
+/// `run_git` with extra environment variables (e.g. GIT_COMMITTER_DATE)
+fn run_git_with_env(
+    repo_path: &std::path::Path,
+    args: &[&str],
+    envs: &[(&str, &str)],
+    success_msg: &str,
+    error_prefix: &str,
 ) -> GitResult {
     match std::process::Command::new("git")
         .current_dir(repo_path)
         .args(args)
+        .envs(envs.iter().copied())
         .output()
     {
         Ok(o) => {
             let stderr = String::from_utf8_lossy(&o.stderr);
             let stdout = String::from_utf8_lossy(&o.stdout);
+            // Full output goes after the summary line for the output viewer
+            let output_text = format!("{}{}", stdout, stderr);
+            let output_text = output_text.trim();
 
             if o.status.success() {
                 // Check if git actually did something
-                let output_text = format!("{}{}", stdout, stderr);
                 if output_text.contains("nothing to commit")
                     || output_text.contains("no changes added")
                 {
-                    return Err(format!("{}: {}", error_prefix, output_text.trim()));
+                    return Err(with_details(
+                        format!("{}: {}", error_prefix, error_summary_line(output_text)),
+                        output_text,
+                    ));
                 }
-                Ok(success_msg.to_string())
+                Ok(with_details(success_msg.to_string(), output_text))
             } else {
-                Err(format!(
-                    "{}: {}",
-                    error_prefix,
-                    if stderr.trim().is_empty() {
-                        stdout.trim()
-                    } else {
-                        stderr.trim()
-                    }
+                Err(with_details(
+                    format!("{}: {}", error_prefix, error_summary_line(output_text)),
+                    output_text,
                 ))
             }
         }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(format!("{}: git not found on PATH", error_prefix))
+        }
         Err(e) => Err(format!("{}: {}", error_prefix, e)),
     }
 }
 
+/// Full output of the last background operation
+pub struct OperationOutput {
+    pub title: &'static str,
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// "now", or a zero offset like "0 seconds ago": dates that really resolve to now
+fn means_now(text: &str) -> bool {
+    let text = text.trim().to_lowercase();
+    if text.is_empty() || text == "now" {
+        return true;
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    matches!(words.as_slice(), [count, _, "ago"] if count.chars().all(|c| c == '0'))
+}
+
+/// Unix time for a date in any format git accepts, via `git rev-parse --since`
+fn resolve_git_date(repo_path: &Path, text: &str) -> Option<i64> {
+    let output = std::process::Command::new("git")
+        .current_dir(repo_path)
+        .args(["rev-parse", &format!("--since={}", text)])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .strip_prefix("--max-age=")?
+        .parse()
+        .ok()
+}
+
+/// Append raw git output below a one-line summary
+fn with_details(summary: String, details: &str) -> String {
+    if details.is_empty() {
+        summary
+    } else {
+        format!("{}\n{}", summary, details)
+    }
+}
+
+/// Operation left half-done in the repository: (name, git subcommand to abort it)
+pub fn unfinished_operation(state: git2::RepositoryState) -> Option<(&'static str, &'static str)> {
+    use git2::RepositoryState as S;
+    match state {
+        S::Clean => None,
+        S::Merge => Some(("Merge", "merge")),
+        S::Revert | S::RevertSequence => Some(("Revert", "revert")),
+        S::CherryPick | S::CherryPickSequence => Some(("Cherry-pick", "cherry-pick")),
+        S::Bisect => Some(("Bisect", "bisect")),
+        S::Rebase | S::RebaseInteractive | S::RebaseMerge => Some(("Rebase", "rebase")),
+        S::ApplyMailbox | S::ApplyMailboxOrRebase => Some(("git am", "am")),
+    }
+}
+
+/// First line of an operation message (the rest is raw git output)
+pub fn summary_line(message: &str) -> &str {
+    message.lines().next().unwrap_or("")
+}
+
+/// Pick the line that explains a failure: `! [rejected]`, `error:` or `fatal:`,
+/// falling back to the last line of output
+pub fn error_summary_line(output: &str) -> &str {
+    let mut lines = output.lines().map(str::trim).filter(|l| !l.is_empty());
+    lines
+        .clone()
+        .find(|l| l.starts_with('!') || l.starts_with("error:") || l.starts_with("fatal:"))
+        .or_else(|| lines.next_back())
+        .unwrap_or("")
+}
+
 pub struct App {
     pub tab: Tab,
     pub running: bool,
     pub input_mode: InputMode,
     pub commit_message: String,
-    pub cursor_pos: usize, // Cursor position in commit_message (byte index)
-    pub is_amending: bool, // true when editing existing commit message
+    pub cursor_pos: usize,     // Cursor position in commit_message (byte index)
+    pub is_amending: bool,     // true when editing existing commit message
+    allow_empty: bool,         // set by Ctrl+Enter for `git commit --allow-empty`
+    commit_scope: CommitScope, // Ctrl+A / Ctrl+O / W: until the commit starts or is cancelled
+    pub editor_requested: bool, // main loop suspends the TUI and runs the commit editor
+    // `[pager] command` split into program and arguments (None: built-in viewer)
+    pub pager: Option<Vec<String>>,
+    // Text the main loop pipes into `pager`, with the TUI suspended
+    pub pager_requested: Option<String>,
     pub remote_url: String,
     pub tag_input: String,
     pub editing_tag: Option<String>,
@@ -254,16 +973,50 @@ pub struct App {
     pub commits: Vec<CommitEntry>,
     pub files_state: ListState,
     pub commits_state: ListState,
+    // `/` in the Log tab: the query being typed, then the one n/N step through
+    pub log_search_input: String,
+    pub log_search: Option<String>,
+    // Space in the Log tab: the other end of a range for C (cherry-pick)
+    pub log_mark: Option<git2::Oid>,
+    // `A` in the Log tab: walk every local and remote branch, not just HEAD
+    pub log_all_branches: bool,
     pub branch_name: String,
+    // Short id HEAD points at when it is detached
+    pub detached_at: Option<String>,
+    // Remote branch the current one tracks ("origin/main" when none is configured)
+    pub upstream_name: String,
     pub ahead_behind: Option<(usize, usize)>,
+    // When .git/FETCH_HEAD was last written (unix seconds), i.e. how old ↑/↓ is
+    pub last_fetch: Option<i64>,
+    pub fetch_stale_minutes: u64,
+    // Lock files, unfinished operations and read-only .git (banner under the tabs)
+    pub health: Vec<HealthIssue>,
+    // `git` could be run when the app started
+    git_cli: bool,
     pub message: Option<(String, bool)>,
     pub repo: Repository,
     pub repo_path: PathBuf,
     pub available_repos: Vec<PathBuf>,
     pub repo_select_state: ListState,
+    // `^` went up from this nested repository; `^` again comes back
+    nested_return: Option<PathBuf>,
     // Processing state
     pub processing: Processing,
     pub spinner_frame: usize,
+    // Error from the last background operation (shown in the status chip until next key)
+    pub failed_operation: Option<String>,
+    // Operations started while another one was running (run in order)
+    pub queued_operations: VecDeque<QueuedOperation>,
+    // Completion messages of the current batch of queued operations
+    pub operation_results: Vec<(String, bool)>,
+    // Full stdout/stderr of the last operation (output viewer)
+    pub last_output: Option<OperationOutput>,
+    pub output_scroll: usize,
+    // Follow-ups offered for the last failure (recovery prompt)
+    pub recovery_actions: Vec<RecoveryAction>,
+    // Last commit attempt (for --no-verify retry)
+    last_commit_attempt: Option<CommitAttempt>,
+    release_rollback: Option<ReleaseRollback>,
     processing_rx: Option<mpsc::Receiver<GitResult>>,
     #[allow(dead_code)]
     processing_handle: Option<JoinHandle<()>>,
@@ -271,17 +1024,76 @@ pub struct App {
     status_fingerprint: Option<u64>,
     // Repository-specific config
     pub repo_config: RepoConfig,
+    // Files tab ordering (config.toml `ui.file_sort`)
+    pub file_sort: FileSort,
+    // `f` in the Files tab: list one kind of change only (not saved)
+    pub status_filter: StatusFilter,
+    // Log tab commit dates (config.toml `ui.date_format`, cycled with `D`)
+    pub date_format: DateFormat,
+    // Whether siori captures the mouse (config.toml `ui.mouse`, toggled with `M`);
+    // off leaves text selection to the terminal
+    pub mouse_capture: bool,
+    // config.toml `clipboard.backend`
+    clipboard_backend: ClipboardBackend,
+    // Static progress indicator instead of the spinner (config.toml `ui.reduced_motion`)
+    pub reduced_motion: bool,
+    // Terminal speaks the kitty keyboard protocol, so modified Enter keys are distinguishable
+    pub keyboard_enhanced: bool,
+    // Files marked with `v` for batch stage/unstage/discard, keyed by (path, staged)
+    pub marked_files: HashSet<(String, bool)>,
     // Pending version update (for confirmation dialog)
     pub pending_version_update: Option<PendingVersionUpdate>,
     // Pending discard action (for confirmation dialog)
     pub pending_discard: Option<PendingDiscardTarget>,
+    // Hunk view of the selected file
+    pub diff_view: Option<DiffView>,
+    // Quick look of the selected file (None when the cursor isn't on a file)
+    pub quick_look: Option<QuickLook>,
+    // Review session (`B`), kept across refreshes until ended
+    pub review: Option<ReviewSession>,
     // Pending delete tag (name, was_pushed)
     pub pending_delete_tag: Option<(String, bool)>,
+    // `a` in the Log tab: the selected commit's tags
+    pub tag_details: Vec<TagDetail>,
+    // `g` in the Log tab: every tag, newest version (or date) first
+    pub tags_view: Vec<TagDetail>,
+    pub tags_view_state: ListState,
+    pub tags_by_date: bool,
+    // `u` in the Log tab: (short id + subject of HEAD, already on origin)
+    pub pending_undo_commit: Option<(String, bool)>,
+    // `c` in the Log tab: (full id, short id + subject) to check out detached
+    pub pending_checkout: Option<(String, String)>,
+    // Branch a detached HEAD came from, offered by the checkout dialog
+    pub checkout_return: Option<String>,
+    // Enter / `l` in the Log tab: the selected commit in full, its files navigable into diffs
+    pub commit_detail: Option<CommitDetail>,
+    pub commit_detail_state: ListState,
+    pub commit_file_diff: Option<DiffPager>,
+    // Enter on the ↓ row above the Log (or `i`): incoming commits and diffstat from the upstream
+    pub incoming: Option<Incoming>,
+    pub incoming_state: ListState,
+    // Log cursor is on the ↓ row rather than a commit (no commit selected meanwhile)
+    incoming_row_selected: bool,
+    // `d` in the Log tab: marked commit vs selected commit
+    pub comparison: Option<Comparison>,
+    pub comparison_state: ListState,
+    // `L` in the Log tab: HEAD's reflog
+    pub reflog: Vec<ReflogEntry>,
+    pub reflog_state: ListState,
+    // (full id, selector + short id + message) to reset the current branch to
+    pub pending_reflog_reset: Option<(String, String)>,
     // Pending diff command (for copy confirmation)
     pub pending_diff_command: Option<String>,
     // Remote tags cache (to avoid frequent ls-remote calls)
     remote_tags_cache: HashSet<String>,
     remote_tags_last_fetch: Option<Instant>,
+    // Commits the Log tab walks; grows by LOG_BATCH when scrolling past the end
+    log_limit: usize,
+    log_has_more: bool,
+    // config.toml `ui.log_order`
+    log_order: LogOrder,
+    // config.toml `ui.confirmations`
+    confirmations: Confirmations,
     // Worktree state
     pub available_worktrees: Vec<WorktreeInfo>,
     pub worktree_type_new: bool,
@@ -298,14 +1110,152 @@ pub struct App {
     pub branch_select_op: BranchSelectOp,
     pub branch_list: Vec<String>,
     pub branch_select_state: ListState,
+    // Per-commit `N files, +x -y` for the Log tab, filled in by a background thread
+    pub commit_stats: HashMap<git2::Oid, DiffStats>,
+    commit_stats_pending: HashSet<git2::Oid>,
+    // None when the commit couldn't be diffed (not retried until the log reloads)
+    commit_stats_tx: mpsc::Sender<(git2::Oid, Option<DiffStats>)>,
+    commit_stats_rx: mpsc::Receiver<(git2::Oid, Option<DiffStats>)>,
+    // Stash state
+    pub stashes: Vec<StashEntry>,
+    pub stash_state: ListState,
+    pub stash_message_input: String,
+    // Branches tab; `renaming_branch` is set while the name input renames instead of creating
+    pub branches: Vec<BranchEntry>,
+    pub branches_state: ListState,
+    pub branch_name_input: String,
+    pub renaming_branch: Option<String>,
+    // .gitignore pattern being added
+    pub gitignore_input: String,
+    // `n` in the Files tab: tracked paths to `git mv`, and where to
+    pub move_sources: Vec<String>,
+    pub move_input: String,
+    // Changelog fragment being typed ("type: text")
+    pub changelog_input: String,
+    // Files of the amended commit, shown before editing the message
+    pub amend_preview: Vec<AmendFile>,
+    // Problems found in staged hunks before committing
+    pub commit_problems: Vec<CommitProblem>,
+    pub commit_problems_state: ListState,
+    // Staged diff shown for a final review before committing ([commit] review)
+    review_before_commit: bool,
+    // config.toml `commit.all`: Enter commits like `git commit -a`
+    commit_all_tracked: bool,
+    // [commit] subject_max when block_long_subject is on
+    subject_hard_limit: Option<usize>,
+    // [commit] body_width when wrap_body is on
+    wrap_body_width: Option<usize>,
+    pub commit_review: Vec<String>,
+    pub commit_review_scroll: usize,
+    // Conventional commit prefix picker ([conventional] in .siori.toml)
+    pub commit_type_state: ListState,
+    pub commit_scope_input: String,
+    // Ctrl+D in Insert: date for the next commit as typed and as resolved by git
+    pub commit_date_input: String,
+    pub commit_date: Option<(String, i64)>,
+    // Ctrl+S in Insert, for the next commit; back to `[commit] signoff` in .siori.toml after it
+    pub signoff: bool,
+    // `Q` records keys until pressed again; `@` replays them
+    macro_recording: Option<Vec<(KeyCode, KeyModifiers)>>,
+    recorded_macro: Vec<(KeyCode, KeyModifiers)>,
+    replaying_macro: bool,
+    // Generated files are collapsed out of the Files list until `g`
+    pub show_generated: bool,
+    // Summary row of +/− per top-level directory under CHANGES (config.toml `ui.directory_stats`)
+    pub directory_stats: bool,
+    // Co-authored-by picker: configured collaborators, then recent authors
+    co_authors_config: Vec<String>,
+    // Ctrl+G in Insert: config.toml `commit.suggest_command`
+    suggest_command: Option<String>,
+    // The message when Ctrl+G ran; a suggestion only replaces it unchanged
+    suggest_base: String,
+    // A suggestion that arrived after the message was edited, waiting for Ctrl+G
+    pending_suggestion: Option<String>,
+    pub co_author_candidates: Vec<String>,
+    pub co_author_marked: HashSet<usize>,
+    pub co_author_state: ListState,
+    // Ctrl+F picker: staged paths (with "also has unstaged changes") to commit on their own
+    pub commit_file_choices: Vec<(String, bool)>,
+    pub commit_file_marked: HashSet<usize>,
+    pub commit_file_state: ListState,
+    // Identity switcher (`[[profiles]]` in config.toml)
+    pub profiles: Vec<Profile>,
+    pub profile_state: ListState,
 }
 
 impl App {
     pub fn new() -> Result<Self> {
+        let mut app = Self::open()?;
+        app.check_health(true);
+        app.refresh()?;
+        app.apply_startup(&Config::load().startup);
+        Ok(app)
+    }
+
+    /// `[startup]` in config.toml: first tab, commit box focus, repository switcher
+    fn apply_startup(&mut self, startup: &StartupConfig) {
+        self.tab = match startup.tab {
+            StartTab::Files => Tab::Files,
+            StartTab::Log => Tab::Log,
+            StartTab::Stash => Tab::Stash,
+            StartTab::Branches => Tab::Branches,
+        };
+        if startup.repo_switcher && self.available_repos.len() > 1 {
+            self.open_repo_select();
+        } else if startup.commit_focus && self.tab == Tab::Files {
+            self.open_commit_input();
+        }
+    }
+
+    /// Time each startup phase separately (`siori --profile-startup`)
+    pub fn profile_startup() -> Result<(Self, Vec<(&'static str, Duration)>)> {
+        let mut phases = Vec::new();
+        let start = Instant::now();
+        let mut app = Self::open()?;
+        phases.push(("repo discovery", start.elapsed()));
+
+        let start = Instant::now();
+        app.refresh_status_internal(false)?;
+        phases.push(("status", start.elapsed()));
+
+        // Diff stats are computed per file inside the status pass
+        let start = Instant::now();
+        app.refresh_status_internal(true)?;
+        let with_stats = start.elapsed();
+        let status = phases[1].1;
+        phases.push(("diff stats", with_stats.saturating_sub(status)));
+
+        let start = Instant::now();
+        app.refresh_branch_info()?;
+        phases.push(("branch info", start.elapsed()));
+
+        let start = Instant::now();
+        app.refresh_log_local()?;
+        phases.push(("log", start.elapsed()));
+
+        let start = Instant::now();
+        if let Some(tags) = Self::fetch_remote_tags(&app.repo_path) {
+            app.remote_tags_cache = tags;
+            app.remote_tags_last_fetch = Some(Instant::now());
+        }
+        phases.push(("ls-remote", start.elapsed()));
+
+        let start = Instant::now();
+        app.refresh_stashes();
+        phases.push(("stashes", start.elapsed()));
+        Ok((app, phases))
+    }
+
+    /// Open the repository without loading status, log or stashes
+    fn open() -> Result<Self> {
+        Self::open_in(std::env::current_dir().unwrap_or_default())
+    }
+
+    /// Open the repository at (or above) `current_dir`, as if siori started there
+    fn open_in(current_dir: PathBuf) -> Result<Self> {
         // Prioritize .git in current directory to handle nested repositories correctly
         // This ensures that when working in a subdirectory with its own .git,
         // we use that repository instead of a parent repository
-        let current_dir = std::env::current_dir().unwrap_or_default();
         let git_dir = current_dir.join(".git");
 
         let repo = if git_dir.exists() {
@@ -313,20 +1263,28 @@ impl App {
             Repository::open(&current_dir).context("Failed to open git repository")?
         } else {
             // Fall back to discovering parent repositories
-            Repository::discover(".").context("Not a git repository")?
+            Repository::discover(&current_dir).context("Not a git repository")?
         };
         let repo_path = repo.workdir().unwrap_or(repo.path()).to_path_buf();
-        let base_dir = std::env::current_dir().unwrap_or_default();
+        let base_dir = current_dir;
         let available_repos = detect_repos(&base_dir);
         let repo_config = RepoConfig::load(&repo_path);
+        let signoff = repo_config.commit.signoff;
+        let config = Config::load();
+        let (commit_stats_tx, commit_stats_rx) = mpsc::channel();
 
-        let mut app = Self {
+        Ok(Self {
             tab: Tab::default(),
             running: true,
             input_mode: InputMode::default(),
             commit_message: String::new(),
             cursor_pos: 0,
             is_amending: false,
+            allow_empty: false,
+            commit_scope: CommitScope::Index,
+            editor_requested: false,
+            pager: config.pager.argv(),
+            pager_requested: None,
             remote_url: String::new(),
             tag_input: String::new(),
             editing_tag: None,
@@ -335,26 +1293,80 @@ impl App {
             commits: Vec::new(),
             files_state: ListState::default(),
             commits_state: ListState::default(),
+            log_search_input: String::new(),
+            log_search: None,
+            log_mark: None,
+            log_all_branches: false,
             branch_name: String::new(),
+            detached_at: None,
+            upstream_name: String::new(),
             ahead_behind: None,
+            last_fetch: None,
+            fetch_stale_minutes: config.ui.fetch_stale_minutes,
+            health: Vec::new(),
+            git_cli: git_cli_available(),
             message: None,
             repo,
             worktree_target_repo: repo_path.clone(),
             repo_path,
             available_repos,
+            nested_return: None,
             repo_select_state: ListState::default(),
             processing: Processing::None,
             spinner_frame: 0,
+            failed_operation: None,
+            queued_operations: VecDeque::new(),
+            operation_results: Vec::new(),
+            last_output: None,
+            output_scroll: 0,
+            recovery_actions: Vec::new(),
+            last_commit_attempt: None,
+            release_rollback: None,
             processing_rx: None,
             processing_handle: None,
             status_fingerprint: None,
             repo_config,
+            file_sort: config.ui.file_sort,
+            status_filter: StatusFilter::All,
+            date_format: config.ui.date_format,
+            directory_stats: config.ui.directory_stats,
+            mouse_capture: config.ui.mouse,
+            clipboard_backend: config.clipboard.backend,
+            // A spinner is just noise to a screen reader
+            reduced_motion: config.ui.reduced_motion || config.ui.screen_reader,
+            keyboard_enhanced: false,
+            marked_files: HashSet::new(),
             pending_version_update: None,
             pending_discard: None,
+            diff_view: None,
+            quick_look: None,
+            review: None,
             pending_delete_tag: None,
+            tag_details: Vec::new(),
+            tags_view: Vec::new(),
+            tags_view_state: ListState::default(),
+            tags_by_date: false,
+            pending_undo_commit: None,
+            pending_checkout: None,
+            checkout_return: None,
+            commit_detail: None,
+            commit_detail_state: ListState::default(),
+            commit_file_diff: None,
+            incoming: None,
+            incoming_state: ListState::default(),
+            incoming_row_selected: false,
+            comparison: None,
+            comparison_state: ListState::default(),
+            reflog: Vec::new(),
+            reflog_state: ListState::default(),
+            pending_reflog_reset: None,
             pending_diff_command: None,
             remote_tags_cache: HashSet::new(),
             remote_tags_last_fetch: None,
+            log_limit: LOG_BATCH,
+            log_has_more: false,
+            log_order: config.ui.log_order,
+            confirmations: config.ui.confirmations,
             available_worktrees: Vec::new(),
             worktree_type_new: true,
             worktree_branch_input: String::new(),
@@ -368,66 +1380,318 @@ impl App {
             branch_select_op: BranchSelectOp::Merge,
             branch_list: Vec::new(),
             branch_select_state: ListState::default(),
-        };
-        app.refresh()?;
-        Ok(app)
+            commit_stats: HashMap::new(),
+            commit_stats_pending: HashSet::new(),
+            commit_stats_tx,
+            commit_stats_rx,
+            stashes: Vec::new(),
+            stash_state: ListState::default(),
+            stash_message_input: String::new(),
+            branches: Vec::new(),
+            branches_state: ListState::default(),
+            branch_name_input: String::new(),
+            renaming_branch: None,
+            gitignore_input: String::new(),
+            move_sources: Vec::new(),
+            move_input: String::new(),
+            changelog_input: String::new(),
+            amend_preview: Vec::new(),
+            commit_problems: Vec::new(),
+            commit_problems_state: ListState::default(),
+            review_before_commit: config.commit.review,
+            commit_all_tracked: config.commit.all,
+            subject_hard_limit: config
+                .commit
+                .block_long_subject
+                .then_some(config.commit.subject_max),
+            wrap_body_width: config.commit.wrap_body.then_some(config.commit.body_width),
+            commit_review: Vec::new(),
+            commit_review_scroll: 0,
+            commit_type_state: ListState::default(),
+            commit_scope_input: String::new(),
+            commit_date_input: String::new(),
+            commit_date: None,
+            signoff,
+            macro_recording: None,
+            recorded_macro: Vec::new(),
+            replaying_macro: false,
+            show_generated: false,
+            co_authors_config: config.commit.co_authors,
+            suggest_command: config.commit.suggest_command,
+            suggest_base: String::new(),
+            pending_suggestion: None,
+            co_author_candidates: Vec::new(),
+            co_author_marked: HashSet::new(),
+            co_author_state: ListState::default(),
+            commit_file_choices: Vec::new(),
+            commit_file_marked: HashSet::new(),
+            commit_file_state: ListState::default(),
+            profiles: config.profiles,
+            profile_state: ListState::default(),
+        })
     }
 
     pub fn refresh(&mut self) -> Result<()> {
+        self.check_health(false);
         self.refresh_status()?;
         self.refresh_branch_info()?;
         self.refresh_log()?;
+        self.refresh_stashes();
+        self.refresh_branches();
+        self.refresh_review();
         Ok(())
     }
 
     /// Lightweight refresh for auto-refresh (no network calls, no diff stats)
     pub fn refresh_status_only(&mut self) -> Result<()> {
+        self.check_health(false);
         self.refresh_status_internal(false)?;
         self.refresh_branch_info()?;
         self.refresh_log_local()?;
+        self.refresh_stashes();
+        // Ahead/behind and merged walk history per branch: only while they're on screen
+        if self.tab == Tab::Branches {
+            self.refresh_branches();
+        }
+        self.refresh_review();
         Ok(())
     }
 
-    // ========================================================================
-    // Processing state management
-    // ========================================================================
+    /// Look for index.lock, unfinished operations and, with `probe_write`,
+    /// a .git that can't be written to (costs a file create, so not on every refresh)
+    pub fn check_health(&mut self, probe_write: bool) {
+        let was_read_only = self.health.contains(&HealthIssue::ReadOnly);
+        self.health.clear();
 
-    /// Advance spinner animation frame
-    pub fn tick_spinner(&mut self) {
-        self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
-    }
+        let git_dir = self.repo.path().to_path_buf();
+        if let Ok(meta) = std::fs::metadata(git_dir.join("index.lock")) {
+            let age = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.elapsed().ok())
+                .map(|d| d.as_secs());
+            self.health.push(HealthIssue::IndexLock(age));
+        }
 
-    /// Get current spinner character
-    pub fn spinner_char(&self) -> char {
-        SPINNER_FRAMES[self.spinner_frame]
-    }
+        if let Some((what, abort)) = unfinished_operation(self.repo.state()) {
+            self.health.push(HealthIssue::Unfinished(what, abort));
+        }
 
-    /// Check if background operation completed and handle result
+        let read_only = if probe_write {
+            let probe = git_dir.join(format!("siori-write-check-{}", std::process::id()));
+            let writable = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&probe)
+                .is_ok();
+            let _ = std::fs::remove_file(&probe);
+            !writable
+        } else {
+            was_read_only
+        };
+        if read_only {
+            self.health.push(HealthIssue::ReadOnly);
+        }
+        if !self.git_cli {
+            self.health.push(HealthIssue::NoGitCli);
+        }
+    }
+
+    /// Re-check before writing; explain the blocker instead of letting git fail.
+    /// A running background operation may hold index.lock, so that waits too.
+    fn ensure_writable(&mut self) -> bool {
+        if self.processing.is_active() {
+            self.message = Some(("Wait for the current operation".to_string(), true));
+            return false;
+        }
+        self.ensure_healthy()
+    }
+
+    /// The health part of `ensure_writable`, for operations that queue behind a running one
+    fn ensure_healthy(&mut self) -> bool {
+        self.check_health(true);
+        match self.health.iter().find(|h| h.blocks_writes()) {
+            Some(issue) => {
+                self.message = Some((issue.describe(), true));
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Before running the `git` command: look for it again, and explain if it's still missing
+    fn ensure_git_cli(&mut self) -> bool {
+        if !self.git_cli {
+            self.git_cli = git_cli_available();
+            self.check_health(false);
+        }
+        if !self.git_cli {
+            self.message = Some((HealthIssue::NoGitCli.describe(), true));
+        }
+        self.git_cli
+    }
+
+    /// `!` while the banner shows index.lock: confirm before deleting it
+    fn open_remove_lock_confirm(&mut self) {
+        // Our own git process may be the one holding the lock
+        if self.processing.is_active() {
+            self.message = Some(("Wait for the current operation".to_string(), true));
+            return;
+        }
+        self.check_health(false);
+        if self
+            .health
+            .iter()
+            .any(|h| matches!(h, HealthIssue::IndexLock(_)))
+        {
+            self.input_mode = InputMode::RemoveLockConfirm;
+        } else {
+            self.message = Some(("No index.lock to remove".to_string(), false));
+        }
+    }
+
+    fn remove_index_lock(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        match std::fs::remove_file(self.repo.path().join("index.lock")) {
+            Ok(()) => self.message = Some(("Removed .git/index.lock".to_string(), false)),
+            Err(e) => {
+                self.message = Some((format!("Failed to remove index.lock: {}", e), true));
+            }
+        }
+        self.check_health(false);
+        self.refresh()
+    }
+
+    // ========================================================================
+    // Processing state management
+    // ========================================================================
+
+    /// Advance spinner animation frame
+    pub fn tick_spinner(&mut self) {
+        self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+    }
+
+    /// Get current spinner character
+    pub fn spinner_char(&self) -> char {
+        if self.reduced_motion {
+            return '…';
+        }
+        SPINNER_FRAMES[self.spinner_frame]
+    }
+
+    /// Check if background operation completed and handle result
     pub fn check_processing(&mut self) -> Result<()> {
         if let Some(rx) = &self.processing_rx {
             if let Ok(result) = rx.try_recv() {
-                match result {
-                    Ok(msg) => self.message = Some((msg, false)),
-                    Err(msg) => self.message = Some((msg, true)),
+                let (mut text, is_error) = match result {
+                    Ok(text) => (text, false),
+                    Err(text) => (text, true),
+                };
+                let mut note = None;
+                if self.processing == Processing::Generating && !is_error {
+                    let suggestion = text.trim().to_string();
+                    if self.commit_message.trim().is_empty()
+                        || self.commit_message == self.suggest_base
+                    {
+                        self.commit_message = suggestion;
+                        self.cursor_pos = self.commit_message.len();
+                        text = "Suggested message inserted — edit before committing".to_string();
+                    } else {
+                        // Typed while it ran: the suggestion stays in the output until taken
+                        self.pending_suggestion = Some(suggestion);
+                        note = Some(
+                            "Message edited meanwhile: Ctrl+G swaps in the suggestion (o shows it)",
+                        );
+                    }
                 }
+                let msg = note.unwrap_or_else(|| summary_line(&text)).to_string();
+                if is_error {
+                    self.failed_operation = Some(msg.clone());
+                    self.recovery_actions =
+                        suggest_recovery(&self.processing, &text, self.has_commit_hooks());
+                }
+                self.last_output = Some(OperationOutput {
+                    title: self.processing.name(),
+                    text,
+                    is_error,
+                });
+                self.operation_results.push((msg.clone(), is_error));
+                self.message = Some((msg, is_error));
                 // Invalidate remote tags cache if tags were pushed
                 if self.processing == Processing::PushingTags {
                     self.remote_tags_last_fetch = None;
                 }
+                if self.processing == Processing::Releasing && !is_error {
+                    self.release_rollback = None;
+                }
                 self.processing = Processing::None;
                 self.processing_rx = None;
                 self.processing_handle = None;
                 self.refresh()?;
+
+                // Offer the usual fix without interrupting another dialog.
+                // A rejecting hook explains itself, so its output comes first.
+                if !self.recovery_actions.is_empty() && self.input_mode == InputMode::Normal {
+                    self.input_mode = if self
+                        .recovery_actions
+                        .contains(&RecoveryAction::CommitNoVerify)
+                    {
+                        self.output_scroll = 0;
+                        InputMode::OutputView
+                    } else {
+                        InputMode::RecoveryPrompt
+                    };
+                }
+
+                if let Some(next) = self.queued_operations.pop_front() {
+                    self.spawn_operation(next.state, next.run);
+                } else if self.operation_results.len() > 1 {
+                    // Batch finished: keep every completion message visible
+                    let is_error = self.operation_results.iter().any(|(_, e)| *e);
+                    let summary: Vec<&str> = self
+                        .operation_results
+                        .iter()
+                        .map(|(m, _)| m.as_str())
+                        .collect();
+                    self.message = Some((summary.join(" · "), is_error));
+                }
             }
         }
         Ok(())
     }
 
     /// Start a background git operation
+    ///
+    /// While another operation is running the new one is queued behind it.
     fn start_processing<F>(&mut self, state: Processing, operation: F)
     where
         F: FnOnce() -> GitResult + Send + 'static,
     {
+        if !self.ensure_healthy() || !self.ensure_git_cli() {
+            return;
+        }
+        if self.processing.is_active() {
+            if self.processing == state || self.queued_operations.iter().any(|q| q.state == state) {
+                self.message = Some((format!("Already queued: {}", state.name()), true));
+                return;
+            }
+            self.message = Some((format!("Queued: {}", state.name()), false));
+            self.queued_operations.push_back(QueuedOperation {
+                state,
+                run: Box::new(operation),
+            });
+            return;
+        }
+        self.failed_operation = None;
+        self.operation_results.clear();
+        self.spawn_operation(state, Box::new(operation));
+    }
+
+    fn spawn_operation(
+        &mut self,
+        state: Processing,
+        operation: Box<dyn FnOnce() -> GitResult + Send>,
+    ) {
         let (tx, rx) = mpsc::channel();
         let handle = thread::spawn(move || {
             let result = operation();
@@ -452,12 +1716,14 @@ impl App {
 
         // Quick check: compute a fingerprint of current status and compare to previous
         if !compute_diff_stats {
-            let new_fingerprint = Self::compute_status_fingerprint(&statuses);
+            let new_fingerprint = self.compute_status_fingerprint(&statuses);
             if Some(&new_fingerprint) == self.status_fingerprint.as_ref() {
                 return Ok(()); // No changes, skip rebuild
             }
             self.status_fingerprint = Some(new_fingerprint);
         }
+        // The size sort needs line counts even on the lightweight refresh
+        let compute_diff_stats = compute_diff_stats || self.file_sort == FileSort::Size;
 
         self.files.clear();
         self.visual_list.clear();
@@ -465,13 +1731,42 @@ impl App {
         let mut staged_indices = Vec::new();
         let mut unstaged_indices = Vec::new();
 
+        let index = self.repo.index().ok();
+
+        // Sparse checkout marks every file outside it skip-worktree and leaves it off disk
+        // (libgit2 then reports it deleted); only the ones still on disk were hidden by hand
+        let sparse = self
+            .repo
+            .config()
+            .and_then(|c| c.get_bool("core.sparseCheckout"))
+            .unwrap_or(false);
+        let repo_path = self.repo_path.clone();
+        let outside_sparse = |path: &str| {
+            sparse
+                && index
+                    .as_ref()
+                    .and_then(|idx| idx.get_path(Path::new(path), 0))
+                    .and_then(|e| hidden_flag(&e))
+                    == Some(HiddenFlag::SkipWorktree)
+                && repo_path.join(path).symlink_metadata().is_err()
+        };
+
         // Single pass: collect all files
         for entry in statuses.iter() {
             let path = entry.path().unwrap_or("").to_string();
             let status = entry.status();
 
+            // `git add -N` entries report INDEX_NEW but nothing is actually staged
+            let intent_to_add = status.contains(Status::INDEX_NEW)
+                && index
+                    .as_ref()
+                    .and_then(|idx| idx.get_path(Path::new(&path), 0))
+                    .is_some_and(|e| is_intent_to_add(&e));
+
             // Staged files
-            if status.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED)
+            if !intent_to_add
+                && status
+                    .intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED)
             {
                 let file_status = if status.contains(Status::INDEX_NEW) {
                     FileStatus::Added
@@ -480,47 +1775,146 @@ impl App {
                 } else {
                     FileStatus::Modified
                 };
-                let diff_stats = if compute_diff_stats {
-                    self.get_diff_stats(&path, true)
+                let (summary, text_issues) = if compute_diff_stats {
+                    (
+                        self.get_diff_stats(&path, true),
+                        self.staged_text_issues(&path),
+                    )
                 } else {
-                    None
+                    (DiffSummary::default(), Vec::new())
                 };
+                let symlink_target = self.symlink_target(&path, true);
+                let generated = self.is_generated(&path);
                 staged_indices.push(self.files.len());
                 self.files.push(FileEntry {
                     path: path.clone(),
                     status: file_status,
                     staged: true,
-                    diff_stats,
+                    diff_stats: summary.stats,
+                    text_issues,
+                    intent_to_add: false,
+                    hidden: None,
+                    mode_change: summary.mode_change,
+                    binary_size: summary.binary_size,
+                    symlink_target,
+                    nested_repo: false,
+                    generated,
+                    conflicts: None,
+                });
+            }
+
+            // Unmerged: only listed as unstaged, staging marks it resolved
+            if status.contains(Status::CONFLICTED) {
+                unstaged_indices.push(self.files.len());
+                let conflicts = self.text_conflict_markers(index.as_ref(), &path);
+                self.files.push(FileEntry {
+                    path,
+                    status: FileStatus::Conflicted,
+                    staged: false,
+                    diff_stats: None,
+                    text_issues: Vec::new(),
+                    intent_to_add: false,
+                    hidden: None,
+                    mode_change: None,
+                    binary_size: None,
+                    symlink_target: None,
+                    nested_repo: false,
+                    generated: false,
+                    conflicts,
                 });
+                continue;
             }
 
             // Unstaged/untracked files
-            if status.intersects(Status::WT_NEW | Status::WT_MODIFIED | Status::WT_DELETED) {
-                let file_status = if status.contains(Status::WT_NEW) {
+            if (intent_to_add
+                || status.intersects(Status::WT_NEW | Status::WT_MODIFIED | Status::WT_DELETED))
+                && !(status.contains(Status::WT_DELETED) && outside_sparse(&path))
+            {
+                let file_status = if intent_to_add {
+                    FileStatus::Added
+                } else if status.contains(Status::WT_NEW) {
                     FileStatus::Untracked
                 } else if status.contains(Status::WT_DELETED) {
                     FileStatus::Deleted
                 } else {
                     FileStatus::Modified
                 };
-                let diff_stats = if compute_diff_stats {
+                let summary = if compute_diff_stats {
                     self.get_diff_stats(&path, false)
                 } else {
-                    None
+                    DiffSummary::default()
                 };
+                let symlink_target = self.symlink_target(&path, false);
+                let generated = self.is_generated(&path);
+                let nested_repo = file_status == FileStatus::Untracked
+                    && path.ends_with('/')
+                    && self.repo_path.join(&path).join(".git").exists();
                 unstaged_indices.push(self.files.len());
                 self.files.push(FileEntry {
                     path,
                     status: file_status,
                     staged: false,
-                    diff_stats,
+                    diff_stats: summary.stats,
+                    text_issues: Vec::new(),
+                    intent_to_add,
+                    hidden: None,
+                    mode_change: summary.mode_change,
+                    binary_size: summary.binary_size,
+                    symlink_target,
+                    nested_repo,
+                    generated,
+                    conflicts: None,
+                });
+            }
+        }
+
+        self.sort_file_indices(&mut staged_indices);
+        self.sort_file_indices(&mut unstaged_indices);
+        let staged_indices = self.collapse_generated(staged_indices);
+        let mut unstaged_indices = self.collapse_generated(unstaged_indices);
+
+        // Files whose local changes are hidden never show up in status; list them dimmed
+        if let Some(index) = &index {
+            for entry in index.iter() {
+                let Some(flag) = hidden_flag(&entry) else {
+                    continue;
+                };
+                let path = String::from_utf8_lossy(&entry.path).into_owned();
+                if outside_sparse(&path) {
+                    continue;
+                }
+                unstaged_indices.push(self.files.len());
+                self.files.push(FileEntry {
+                    path,
+                    status: FileStatus::Modified,
+                    staged: false,
+                    diff_stats: None,
+                    text_issues: Vec::new(),
+                    intent_to_add: false,
+                    hidden: Some(flag),
+                    mode_change: None,
+                    binary_size: None,
+                    symlink_target: None,
+                    nested_repo: false,
+                    generated: false,
+                    conflicts: None,
                 });
             }
         }
 
         // Build visual_list: staged first, then unstaged
-        self.visual_list.extend(staged_indices);
-        self.visual_list.extend(unstaged_indices);
+        let filter = self.status_filter;
+        let files = &self.files;
+        let shown = |&i: &usize| filter.matches(files[i].status);
+        self.visual_list
+            .extend(staged_indices.into_iter().filter(shown));
+        self.visual_list
+            .extend(unstaged_indices.into_iter().filter(shown));
+
+        // Drop marks for entries that no longer exist
+        let files = &self.files;
+        self.marked_files
+            .retain(|(path, staged)| files.iter().any(|f| &f.path == path && f.staged == *staged));
 
         // Adjust selection
         if self.files_state.selected().is_none() && !self.visual_list.is_empty() {
@@ -535,42 +1929,266 @@ impl App {
         Ok(())
     }
 
+    /// Order one section of the file list according to `file_sort`
+    fn sort_file_indices(&self, indices: &mut [usize]) {
+        let files = &self.files;
+        match self.file_sort {
+            FileSort::Path => indices.sort_by(|&a, &b| files[a].path.cmp(&files[b].path)),
+            FileSort::Status => indices.sort_by(|&a, &b| {
+                status_rank(files[a].status)
+                    .cmp(&status_rank(files[b].status))
+                    .then_with(|| files[a].path.cmp(&files[b].path))
+            }),
+            FileSort::Size => indices.sort_by(|&a, &b| {
+                let size = |i: usize| files[i].diff_stats.map_or(0, |(add, del)| add + del);
+                size(b)
+                    .cmp(&size(a))
+                    .then_with(|| files[a].path.cmp(&files[b].path))
+            }),
+            FileSort::Modified => {
+                let workdir = self.repo.workdir().unwrap_or(self.repo.path());
+                let mtime = |i: usize| {
+                    std::fs::metadata(workdir.join(&files[i].path))
+                        .and_then(|m| m.modified())
+                        .ok()
+                };
+                indices.sort_by_cached_key(|&i| std::cmp::Reverse(mtime(i)));
+            }
+        }
+        // Conflicted files lead, fewest conflicts first, so the easy ones go quickly
+        indices.sort_by_key(|&i| {
+            (
+                files[i].status != FileStatus::Conflicted,
+                files[i].conflicts.unwrap_or(0),
+            )
+        });
+    }
+
+    /// Markers left in a conflicted file, when git wrote any: both sides must be
+    /// text (modify/delete and binary conflicts leave the file without markers)
+    fn text_conflict_markers(&self, index: Option<&git2::Index>, path: &str) -> Option<usize> {
+        let index = index?;
+        for stage in [2, 3] {
+            let entry = index.get_path(Path::new(path), stage)?;
+            if self.repo.find_blob(entry.id).ok()?.is_binary() {
+                return None;
+            }
+        }
+        let workdir = self.repo.workdir().unwrap_or(self.repo.path());
+        Some(count_conflict_markers(&workdir.join(path)))
+    }
+
+    /// Generated files go last, or are left out of the list while collapsed
+    fn collapse_generated(&self, indices: Vec<usize>) -> Vec<usize> {
+        let (mut visible, generated): (Vec<usize>, Vec<usize>) =
+            indices.into_iter().partition(|&i| !self.files[i].generated);
+        if self.show_generated {
+            visible.extend(generated);
+        }
+        visible
+    }
+
+    /// `linguist-generated` in .gitattributes or a `[generated] paths` match
+    fn is_generated(&self, path: &str) -> bool {
+        let attribute = self
+            .repo
+            .get_attr(
+                Path::new(path),
+                "linguist-generated",
+                git2::AttrCheckFlags::FILE_THEN_INDEX,
+            )
+            .ok()
+            .flatten();
+        match git2::AttrValue::from_string(attribute) {
+            git2::AttrValue::True | git2::AttrValue::String("true") => return true,
+            git2::AttrValue::False | git2::AttrValue::String("false") => return false,
+            _ => {}
+        }
+        let name = path.rsplit('/').next().unwrap_or(path);
+        self.repo_config.generated.paths.iter().any(|pattern| {
+            let target = if pattern.contains('/') { path } else { name };
+            crate::config::glob_match(pattern, target)
+        })
+    }
+
+    fn toggle_generated(&mut self) -> Result<()> {
+        if !self.files.iter().any(|f| f.generated) {
+            self.message = Some(("No generated files".to_string(), true));
+            return Ok(());
+        }
+        self.show_generated = !self.show_generated;
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    /// `D` in the Log tab: relative → absolute → both, for this session
+    fn cycle_date_format(&mut self) {
+        self.date_format = self.date_format.next();
+        self.message = Some((format!("Dates: {}", self.date_format.name()), false));
+    }
+
+    /// `f` in the Files tab: all → untracked → modified → deleted → all
+    fn cycle_status_filter(&mut self) -> Result<()> {
+        self.status_filter = self.status_filter.next();
+        self.files_state.select(Some(0));
+        self.refresh_status()?;
+        self.message = Some((
+            format!("Showing {} files", self.status_filter.name()),
+            false,
+        ));
+        Ok(())
+    }
+
+    fn cycle_file_sort(&mut self) -> Result<()> {
+        self.file_sort = self.file_sort.next();
+        self.refresh_status()?;
+        self.message = Some(
+            match crate::config::save_setting("ui", "file_sort", self.file_sort.name()) {
+                Ok(()) => (format!("Sort: {}", self.file_sort.name()), false),
+                Err(e) => (
+                    format!("Sort: {} (not saved: {e})", self.file_sort.name()),
+                    true,
+                ),
+            },
+        );
+        Ok(())
+    }
+
     /// Compute a fingerprint of the git status for change detection.
     /// This captures path + status bits for each file.
-    fn compute_status_fingerprint(statuses: &git2::Statuses) -> u64 {
+    /// Under the modified and size sorts, saving an already-changed file must
+    /// also count as a change so it moves to its new place
+    fn compute_status_fingerprint(&self, statuses: &git2::Statuses) -> u64 {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
 
+        let workdir = self.repo.workdir().unwrap_or(self.repo.path());
         let mut hasher = DefaultHasher::new();
         for entry in statuses.iter() {
             if let Some(path) = entry.path() {
                 path.hash(&mut hasher);
+                // Conflict counts change while the status stays "both modified"
+                if matches!(self.file_sort, FileSort::Modified | FileSort::Size)
+                    || entry.status().contains(Status::CONFLICTED)
+                {
+                    std::fs::metadata(workdir.join(path))
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .hash(&mut hasher);
+                }
             }
             entry.status().bits().hash(&mut hasher);
         }
         hasher.finish()
     }
 
-    fn get_diff_stats(&self, path: &str, staged: bool) -> Option<(usize, usize)> {
+    fn get_diff_stats(&self, path: &str, staged: bool) -> DiffSummary {
         let mut opts = DiffOptions::new();
         opts.pathspec(path);
         let diff = if staged {
-            let head = self.repo.head().ok()?.peel_to_tree().ok()?;
+            let Some(head) = self.repo.head().ok().and_then(|h| h.peel_to_tree().ok()) else {
+                return DiffSummary::default();
+            };
             self.repo
                 .diff_tree_to_index(Some(&head), None, Some(&mut opts))
-                .ok()?
         } else {
-            self.repo
-                .diff_index_to_workdir(None, Some(&mut opts))
-                .ok()?
+            self.repo.diff_index_to_workdir(None, Some(&mut opts))
         };
-        let stats = diff.stats().ok()?;
-        Some((stats.insertions(), stats.deletions()))
+        let Ok(diff) = diff else {
+            return DiffSummary::default();
+        };
+        // Computing stats loads the content, which sets the binary flag on the delta
+        let stats = diff
+            .stats()
+            .ok()
+            .map(|stats| (stats.insertions(), stats.deletions()));
+        let Some(delta) = diff.deltas().next() else {
+            return DiffSummary {
+                stats,
+                ..DiffSummary::default()
+            };
+        };
+        let old = u32::from(delta.old_file().mode());
+        let new = u32::from(delta.new_file().mode());
+        // Added and deleted files have no mode on one side
+        let mode_change = (old != 0 && new != 0 && old != new).then_some((old, new));
+        let binary_size = delta.flags().is_binary().then(|| {
+            let blob_size = |id: git2::Oid| {
+                self.repo
+                    .find_blob(id)
+                    .map(|b| b.size() as u64)
+                    .unwrap_or(0)
+            };
+            let new_size = if staged {
+                blob_size(delta.new_file().id())
+            } else {
+                self.repo
+                    .workdir()
+                    .and_then(|dir| std::fs::metadata(dir.join(path)).ok())
+                    .map_or(0, |m| m.len())
+            };
+            (blob_size(delta.old_file().id()), new_size)
+        });
+        DiffSummary {
+            stats,
+            mode_change,
+            binary_size,
+        }
+    }
+
+    /// Target of the file when it is a symlink, read from the index or the work tree
+    fn symlink_target(&self, path: &str, staged: bool) -> Option<String> {
+        if staged {
+            let entry = self.repo.index().ok()?.get_path(Path::new(path), 0)?;
+            if entry.mode != diff_viewer::SYMLINK_MODE {
+                return None;
+            }
+            let blob = self.repo.find_blob(entry.id).ok()?;
+            Some(String::from_utf8_lossy(blob.content()).into_owned())
+        } else {
+            let full = self.repo.workdir()?.join(path);
+            let target = std::fs::read_link(full).ok()?;
+            Some(target.to_string_lossy().into_owned())
+        }
+    }
+
+    /// Compare the staged blob against HEAD for line ending / encoding problems
+    fn staged_text_issues(&self, path: &str) -> Vec<TextIssue> {
+        let Some(new) = self
+            .repo
+            .index()
+            .ok()
+            .and_then(|index| index.get_path(Path::new(path), 0))
+            .and_then(|entry| self.repo.find_blob(entry.id).ok())
+        else {
+            return Vec::new();
+        };
+        let old = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_tree().ok())
+            .and_then(|tree| tree.get_path(Path::new(path)).ok())
+            .and_then(|entry| self.repo.find_blob(entry.id()).ok());
+        diff_viewer::detect_text_issues(old.as_ref().map(|b| b.content()), new.content())
     }
 
     fn refresh_branch_info(&mut self) -> Result<()> {
         if let Ok(head) = self.repo.head() {
             self.branch_name = head.shorthand().unwrap_or("HEAD").to_string();
+            self.detached_at = self
+                .repo
+                .head_detached()
+                .unwrap_or(false)
+                .then(|| head.target().map(|id| format!("{:.7}", id)))
+                .flatten();
+            self.upstream_name = head
+                .name()
+                .and_then(|name| self.repo.branch_upstream_name(name).ok())
+                .and_then(|buf| buf.as_str().map(str::to_string))
+                .map(|name| name.trim_start_matches("refs/remotes/").to_string())
+                .unwrap_or_else(|| format!("origin/{}", self.branch_name));
+            self.last_fetch = last_fetch_time(&self.repo);
             if let (Ok(local), Ok(remote)) = (
                 head.peel_to_commit().map(|c| c.id()),
                 self.repo
@@ -585,6 +2203,7 @@ impl App {
             }
         } else {
             self.branch_name = "(no commits)".to_string();
+            self.detached_at = None;
         }
         Ok(())
     }
@@ -598,6 +2217,33 @@ impl App {
         self.refresh_log_internal(false)
     }
 
+    /// Tag names on origin (`git ls-remote --tags`), None when the command can't run
+    pub fn fetch_remote_tags(repo_path: &Path) -> Option<HashSet<String>> {
+        let output = std::process::Command::new("git")
+            .current_dir(repo_path)
+            .args(["ls-remote", "--tags", "origin"])
+            .output()
+            .ok()?;
+        // Offline, denied or a bad URL: unknown, not "no tags on the remote"
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Some(
+            stdout
+                .lines()
+                .filter_map(|line| line.split('\t').nth(1))
+                .map(|tag_ref| {
+                    tag_ref
+                        .strip_prefix("refs/tags/")
+                        .unwrap_or(tag_ref)
+                        .trim_end_matches("^{}")
+                        .to_string()
+                })
+                .collect(),
+        )
+    }
+
     fn refresh_log_internal(&mut self, check_remote_tags: bool) -> Result<()> {
         // Save previous tag pushed status before clearing
         let previous_tag_status: HashMap<String, bool> = self
@@ -614,9 +2260,19 @@ impl App {
         if revwalk.push_head().is_err() {
             return Ok(());
         }
-        let _ = revwalk.set_sorting(git2::Sort::TIME);
+        if self.log_all_branches {
+            let _ = revwalk.push_glob("refs/heads/*");
+            let _ = revwalk.push_glob("refs/remotes/*");
+        }
+        // Children before parents either way, as the graph lanes need
+        let _ = revwalk.set_sorting(match self.log_order {
+            LogOrder::Date => git2::Sort::TOPOLOGICAL | git2::Sort::TIME,
+            LogOrder::Topo => git2::Sort::TOPOLOGICAL,
+        });
         let head_id = self.repo.head().ok().and_then(|h| h.target());
 
+        // Collect local branch heads
+        let mut local_refs: HashMap<git2::Oid, Vec<String>> = HashMap::new();
         // Collect remote branch refs
         let mut remote_refs: HashMap<git2::Oid, Vec<String>> = HashMap::new();
         // Collect local tags
@@ -629,7 +2285,14 @@ impl App {
                 let Some(name) = reference.name() else {
                     continue;
                 };
-                if name.starts_with("refs/remotes/") {
+                if let Some(short_name) = name.strip_prefix("refs/heads/") {
+                    if let Ok(commit) = reference.peel_to_commit() {
+                        local_refs
+                            .entry(commit.id())
+                            .or_default()
+                            .push(short_name.to_string());
+                    }
+                } else if name.starts_with("refs/remotes/") {
                     if let Ok(commit) = reference.peel_to_commit() {
                         let short_name = name.strip_prefix("refs/remotes/").unwrap_or(name);
                         remote_refs
@@ -656,31 +2319,38 @@ impl App {
                 .map(|t| t.elapsed().as_secs() > 30)
                 .unwrap_or(true);
 
-            if should_fetch {
-                if let Ok(output) = std::process::Command::new("git")
-                    .current_dir(&self.repo_path)
-                    .args(["ls-remote", "--tags", "origin"])
-                    .output()
-                {
-                    self.remote_tags_cache.clear();
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    for line in stdout.lines() {
-                        if let Some(tag_ref) = line.split('\t').nth(1) {
-                            let tag_name = tag_ref
-                                .strip_prefix("refs/tags/")
-                                .unwrap_or(tag_ref)
-                                .trim_end_matches("^{}");
-                            self.remote_tags_cache.insert(tag_name.to_string());
-                        }
-                    }
-                    self.remote_tags_last_fetch = Some(Instant::now());
-                }
+            if should_fetch && let Some(tags) = Self::fetch_remote_tags(&self.repo_path) {
+                self.remote_tags_cache = tags;
+                self.remote_tags_last_fetch = Some(Instant::now());
             }
             remote_tags = self.remote_tags_cache.clone();
         }
 
+        // Commits on HEAD that its upstream lacks (all of HEAD without one)
+        let upstream = self
+            .repo
+            .find_branch(&self.upstream_name, git2::BranchType::Remote)
+            .ok()
+            .and_then(|b| b.get().target());
+        let unpushed: HashSet<git2::Oid> = match upstream {
+            Some(upstream) => self
+                .repo
+                .revwalk()
+                .ok()
+                .and_then(|mut walk| {
+                    walk.push_head().ok()?;
+                    walk.hide(upstream).ok()?;
+                    Some(walk.filter_map(|oid| oid.ok()).collect())
+                })
+                .unwrap_or_default(),
+            None => HashSet::new(),
+        };
+
+        let mut lanes = GraphBuilder::new();
+        self.log_has_more = false;
         for (i, oid) in revwalk.enumerate() {
-            if i >= 100 {
+            if i >= self.log_limit {
+                self.log_has_more = true;
                 break;
             }
             let Ok(oid) = oid else { continue };
@@ -705,24 +2375,182 @@ impl App {
                 })
                 .unwrap_or_default();
 
+            let parents: Vec<git2::Oid> = commit.parent_ids().collect();
+            let graph = lanes.push(oid, &parents);
             self.commits.push(CommitEntry {
                 id: format!("{:.7}", oid),
                 full_id: oid,
                 message: commit.summary().unwrap_or("").to_string(),
+                author: commit.author().name().unwrap_or("").to_string(),
                 time: format_relative_time(commit.time().seconds()),
+                date: date::format_local(commit.time().seconds(), commit.time().offset_minutes()),
                 is_head: Some(oid) == head_id,
+                local_branches: local_refs.get(&oid).cloned().unwrap_or_default(),
                 remote_branches: remote_refs.get(&oid).cloned().unwrap_or_default(),
                 tags,
+                unpushed: unpushed.contains(&oid),
+                parents,
+                graph,
             });
         }
 
-        if self.commits_state.selected().is_none() && !self.commits.is_empty() {
+        // Back to the top commit once a pull leaves nothing on the ↓ row
+        if self.commits_state.selected().is_none()
+            && !self.on_incoming_row()
+            && !self.commits.is_empty()
+        {
+            self.incoming_row_selected = false;
             self.commits_state.select(Some(0));
         }
+        self.request_commit_stats();
         Ok(())
     }
 
+    /// Compute diff stats of loaded commits that aren't cached yet, off the UI thread
+    fn request_commit_stats(&mut self) {
+        let missing: Vec<git2::Oid> = self
+            .commits
+            .iter()
+            .map(|c| c.full_id)
+            .filter(|id| !self.commit_stats.contains_key(id))
+            .filter(|id| !self.commit_stats_pending.contains(id))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        self.commit_stats_pending.extend(missing.iter().copied());
+        let repo_path = self.repo_path.clone();
+        let tx = self.commit_stats_tx.clone();
+        thread::spawn(move || {
+            let repo = Repository::open(&repo_path).ok();
+            for id in missing {
+                let stats = repo.as_ref().and_then(|repo| commit_diff_stats(repo, id));
+                if tx.send((id, stats)).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Take stats computed in the background; true when any arrived (redraw)
+    pub fn poll_commit_stats(&mut self) -> bool {
+        let mut received = false;
+        while let Ok((id, stats)) = self.commit_stats_rx.try_recv() {
+            self.commit_stats_pending.remove(&id);
+            if let Some(stats) = stats {
+                self.commit_stats.insert(id, stats);
+            }
+            received = true;
+        }
+        received
+    }
+
+    /// Commit stats are still being computed
+    pub fn commit_stats_loading(&self) -> bool {
+        !self.commit_stats_pending.is_empty()
+    }
+
+    fn refresh_branches(&mut self) {
+        let head_id = self.repo.head().ok().and_then(|h| h.target());
+        let mut branches = Vec::new();
+        if let Ok(iter) = self.repo.branches(Some(git2::BranchType::Local)) {
+            for (branch, _) in iter.flatten() {
+                let Ok(Some(name)) = branch.name() else {
+                    continue;
+                };
+                let Ok(commit) = branch.get().peel_to_commit() else {
+                    continue;
+                };
+                let upstream = branch.upstream().ok();
+                let ahead_behind = upstream
+                    .as_ref()
+                    .and_then(|u| u.get().target())
+                    .and_then(|up| self.repo.graph_ahead_behind(commit.id(), up).ok());
+                // Same base as `git branch -d`: the upstream when there is one, else HEAD
+                let base = match &upstream {
+                    Some(u) => u.get().target(),
+                    None => head_id,
+                };
+                let merged = base.is_some_and(|base| {
+                    base == commit.id()
+                        || self
+                            .repo
+                            .graph_descendant_of(base, commit.id())
+                            .unwrap_or(false)
+                });
+                branches.push(BranchEntry {
+                    name: name.to_string(),
+                    is_head: branch.is_head(),
+                    upstream: upstream
+                        .as_ref()
+                        .and_then(|u| u.name().ok().flatten())
+                        .map(str::to_string),
+                    ahead_behind,
+                    id: format!("{:.7}", commit.id()),
+                    subject: commit.summary().unwrap_or("").to_string(),
+                    time: format_relative_time(commit.time().seconds()),
+                    merged,
+                });
+            }
+        }
+        branches.sort_by(|a, b| a.name.cmp(&b.name));
+        self.branches = branches;
+
+        if self.branches.is_empty() {
+            self.branches_state.select(None);
+        } else if self
+            .branches_state
+            .selected()
+            .is_none_or(|i| i >= self.branches.len())
+        {
+            self.branches_state.select(Some(0));
+        }
+    }
+
+    fn refresh_stashes(&mut self) {
+        let mut entries: Vec<(usize, String, git2::Oid)> = Vec::new();
+        let _ = self.repo.stash_foreach(|index, message, oid| {
+            entries.push((index, message.to_string(), *oid));
+            true
+        });
+
+        self.stashes = entries
+            .into_iter()
+            .map(|(index, message, id)| StashEntry {
+                index,
+                message,
+                id,
+                diff_stats: self.stash_diff_stats(id),
+            })
+            .collect();
+
+        if self.stashes.is_empty() {
+            self.stash_state.select(None);
+        } else if self
+            .stash_state
+            .selected()
+            .is_none_or(|i| i >= self.stashes.len())
+        {
+            self.stash_state.select(Some(0));
+        }
+    }
+
+    /// Diff stats of a stash commit against the commit it was created on
+    fn stash_diff_stats(&self, id: git2::Oid) -> Option<(usize, usize, usize)> {
+        let commit = self.repo.find_commit(id).ok()?;
+        let base = commit.parent(0).ok()?.tree().ok()?;
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&base), Some(&commit.tree().ok()?), None)
+            .ok()?;
+        let stats = diff.stats().ok()?;
+        Some((stats.files_changed(), stats.insertions(), stats.deletions()))
+    }
+
     fn stage_selected(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
         let Some(visual_idx) = self.files_state.selected() else {
             self.message = Some(("No file selected".to_string(), true));
             return Ok(());
@@ -736,6 +2564,10 @@ impl App {
             return Ok(());
         };
 
+        if file.hidden.is_some() {
+            self.message = Some(("Unhide file first (H)".to_string(), true));
+            return Ok(());
+        }
         let file_path = file.path.clone();
         let file_status = file.status;
         let is_staged = file.staged;
@@ -755,11 +2587,17 @@ impl App {
             visual_idx - old_staged_count
         };
 
+        // Folders go through `git add` / `git reset`
+        if is_directory && !self.ensure_git_cli() {
+            return Ok(());
+        }
+
         if is_staged {
             // Unstaging
             if is_directory {
                 // Use git command for directories
                 let output = std::process::Command::new("git")
+                    .current_dir(&self.repo_path)
                     .args(["reset", "HEAD", "--", &file_path])
                     .output();
                 match output {
@@ -795,6 +2633,7 @@ impl App {
             if is_directory {
                 // Use git command for directories (handles recursive add properly)
                 let output = std::process::Command::new("git")
+                    .current_dir(&self.repo_path)
                     .args(["add", "--", &file_path])
                     .output();
                 match output {
@@ -823,6 +2662,17 @@ impl App {
 
         self.refresh_status()?;
 
+        // Warn about noisy-diff problems in what was just staged
+        if !is_staged
+            && let Some(issue) = self
+                .files
+                .iter()
+                .find(|f| f.staged && f.path == file_path)
+                .and_then(|f| f.text_issues.first())
+        {
+            self.message = Some((format!("Staged: {} (⚠ {})", file_path, issue.label()), true));
+        }
+
         // 同じセクション内にカーソルを維持
         let new_staged_count = self.files.iter().filter(|f| f.staged).count();
         let new_changes_count = self.visual_list.len() - new_staged_count;
@@ -850,7 +2700,10 @@ impl App {
     }
 
     fn stage_all(&mut self) -> Result<()> {
-        let has_unstaged = self.files.iter().any(|f| !f.staged);
+        if !self.ensure_writable() || !self.ensure_git_cli() {
+            return Ok(());
+        }
+        let has_unstaged = self.files.iter().any(|f| !f.staged && f.hidden.is_none());
         let output = if has_unstaged {
             std::process::Command::new("git")
                 .current_dir(&self.repo_path)
@@ -884,587 +2737,3941 @@ impl App {
         Ok(())
     }
 
+    /// Check the commit of `commit_scope`, then warn, review or start it.
+    /// Nothing is staged or committed until every check has passed.
     fn commit(&mut self) -> Result<()> {
-        let message = self.commit_message.trim().to_string();
-        if message.is_empty() {
+        let wip = self.committing_wip();
+        if !wip && self.commit_message.trim().is_empty() {
             self.message = Some(("Empty commit message".to_string(), true));
+            self.cancel_commit();
             return Ok(());
         }
-
-        let is_amending = self.is_amending;
-        let repo_path = self.repo_path.clone();
-        self.commit_message.clear();
-        self.cursor_pos = 0;
-        self.is_amending = false;
-        self.input_mode = InputMode::Normal;
-
-        if is_amending {
-            self.start_processing(Processing::Committing, move || {
-                run_git(
-                    &repo_path,
-                    &["commit", "--amend", "-m", &message],
-                    "Amended successfully",
-                    "Amend failed",
-                )
-            });
-        } else {
-            self.start_processing(Processing::Committing, move || {
-                run_git(
-                    &repo_path,
-                    &["commit", "-m", &message],
-                    "Committed successfully",
-                    "Commit failed",
-                )
-            });
-        }
-        Ok(())
-    }
-
-    fn start_amend(&mut self) -> Result<()> {
-        // Only allow amending HEAD commit
-        let Some(idx) = self.commits_state.selected() else {
-            return Ok(());
-        };
-        let Some(commit) = self.commits.get(idx) else {
-            return Ok(());
-        };
-        if !commit.is_head {
-            self.message = Some(("Can only amend HEAD commit".to_string(), true));
+        if !self.amending_commit() && !self.allow_empty && !self.has_changes_to_commit() {
+            let text = if self.commit_scope == CommitScope::Index {
+                "Nothing staged — press Space to stage files"
+            } else {
+                "Nothing to commit"
+            };
+            self.message = Some((text.to_string(), true));
+            self.cancel_commit();
             return Ok(());
         }
-
-        self.commit_message = commit.message.clone();
-        self.cursor_pos = self.commit_message.len();
-        self.is_amending = true;
-        self.input_mode = InputMode::Insert;
-        self.tab = Tab::Files; // Switch to Files tab to show input
-        Ok(())
-    }
-
-    fn push(&mut self) -> Result<()> {
-        // Quick check for remote configuration
-        let check = std::process::Command::new("git")
-            .current_dir(&self.repo_path)
-            .args(["remote", "get-url", "origin"])
-            .output();
-
-        if check.is_err() || !check.unwrap().status.success() {
-            self.input_mode = InputMode::RemoteUrl;
-            self.remote_url.clear();
+        if let Some(profile) = self.expected_profile()
+            && self.current_identity().1.as_deref() != Some(profile.email.as_str())
+        {
             self.message = Some((
-                "No remote configured. Enter repository URL:".to_string(),
+                format!(
+                    "This repo uses the {} identity ({}): press I to switch",
+                    profile.name, profile.email
+                ),
                 true,
             ));
+            self.cancel_commit();
             return Ok(());
         }
-
-        // Check if upstream is configured
-        let has_upstream = std::process::Command::new("git")
-            .current_dir(&self.repo_path)
-            .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-
-        let repo_path = self.repo_path.clone();
-        if has_upstream {
-            self.start_processing(Processing::Pushing, move || {
-                run_git(&repo_path, &["push"], "Pushed successfully", "Push failed")
-            });
-        } else {
-            let branch = self.branch_name.clone();
-            self.start_processing(Processing::Pushing, move || {
-                run_git(
-                    &repo_path,
-                    &["push", "-u", "origin", &branch],
-                    "Pushed successfully",
-                    "Push failed",
-                )
-            });
+        if let Some(max) = self.subject_hard_limit.filter(|_| !wip) {
+            let length = subject_length(&normalize_commit_message(&self.commit_message));
+            if length > max {
+                self.message = Some((
+                    format!("Subject is {} characters (max {})", length, max),
+                    true,
+                ));
+                self.cancel_commit();
+                self.input_mode = InputMode::Insert;
+                return Ok(());
+            }
         }
-        Ok(())
-    }
 
-    fn add_remote_and_push(&mut self) -> Result<()> {
-        let url = self.remote_url.trim().to_string();
-        if url.is_empty() {
-            self.message = Some(("URL is empty".to_string(), true));
+        let problems = self.find_commit_problems();
+        if !problems.is_empty() {
+            self.commit_problems = problems;
+            self.commit_problems_state.select(Some(0));
+            self.input_mode = InputMode::CommitWarning;
             return Ok(());
         }
+        self.check_size_or_commit()
+    }
 
-        let add_output = std::process::Command::new("git")
-            .args(["remote", "add", "origin", &url])
-            .output()
-            .context("Failed to add remote")?;
-
-        if !add_output.status.success() {
-            let err = String::from_utf8_lossy(&add_output.stderr);
-            self.message = Some((format!("Failed: {}", err.trim()), true));
-            self.remote_url.clear();
-            self.input_mode = InputMode::Normal;
-            return Ok(());
-        }
+    /// Ctrl+A (or Enter with `commit.all`): commit modified and deleted tracked
+    /// files with `git commit -a`; untracked files stay out, and the index is
+    /// only touched once the commit actually runs
+    fn commit_tracked_changes(&mut self) -> Result<()> {
+        self.commit_scope = CommitScope::Tracked;
+        self.commit()
+    }
 
-        let push_output = std::process::Command::new("git")
-            .args(["push", "-u", "origin", &self.branch_name])
-            .output()
-            .context("Failed to push")?;
+    /// A commit was refused or backed out of: the next one starts from the index again
+    fn cancel_commit(&mut self) {
+        self.commit_scope = CommitScope::Index;
+        self.allow_empty = false;
+        self.commit_problems.clear();
+        self.commit_review.clear();
+    }
 
-        if push_output.status.success() {
-            self.message = Some(("Remote added & pushed!".to_string(), false));
+    /// Esc in a commit warning or review: back to the message, or the list for `W`
+    fn back_out_of_commit(&mut self) {
+        self.input_mode = if self.committing_wip() {
+            InputMode::Normal
         } else {
-            let err = String::from_utf8_lossy(&push_output.stderr);
-            self.message = Some((format!("Push failed: {}", err.trim()), true));
-        }
-
-        self.remote_url.clear();
-        self.input_mode = InputMode::Normal;
-        self.refresh()?;
-        Ok(())
+            InputMode::Insert
+        };
+        self.cancel_commit();
     }
 
-    fn pull(&mut self) -> Result<()> {
-        let repo_path = self.repo_path.clone();
-        self.start_processing(Processing::Pulling, move || {
-            run_git(
-                &repo_path,
-                &["pull", "--no-rebase"],
-                "Pulled successfully",
-                "Pull failed",
-            )
-        });
-        Ok(())
+    fn committing_wip(&self) -> bool {
+        matches!(self.commit_scope, CommitScope::Wip(_))
     }
 
-    // ========================================================================
-    // Repository switcher
-    // ========================================================================
-    fn switch_repo(&mut self, path: PathBuf) -> Result<()> {
-        self.repo = Repository::open(&path).context("Failed to open repository")?;
-        self.repo_path = path.clone();
-        self.repo_config = RepoConfig::load(&path);
-        self.input_mode = InputMode::Normal;
-        // Clear remote tags cache for new repo
-        self.remote_tags_cache.clear();
-        self.remote_tags_last_fetch = None;
-        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("repo");
-        self.message = Some((format!("Switched to: {}", name), false));
-        self.refresh()?;
-        Ok(())
+    /// A WIP save point is always a new commit, even in the middle of an amend
+    fn amending_commit(&self) -> bool {
+        self.is_amending && !self.committing_wip()
     }
 
-    fn refresh_repo_and_worktree_list(&mut self) {
-        let base_dir = std::env::current_dir().unwrap_or_default();
-        let mut repos = detect_repos(&base_dir);
+    /// Whether the commit of `commit_scope` records anything; a merge in
+    /// progress always has something to commit
+    fn has_changes_to_commit(&self) -> bool {
+        if self.repo.state() == git2::RepositoryState::Merge {
+            return true;
+        }
+        self.commit_diff()
+            .is_none_or(|diff| match self.commit_scope {
+                CommitScope::Index => self.staged_deltas(&diff) > 0,
+                _ => diff.deltas().len() > 0,
+            })
+    }
 
-        // 全リポのworktreeを収集（worktreeが2つ以上あるリポのみ）
-        let mut all_worktrees = Vec::new();
-        let mut worktree_paths: HashSet<PathBuf> = HashSet::new();
-        let mut processed_repos: HashSet<PathBuf> = HashSet::new();
-        for repo in &repos {
-            let wts = detect_worktrees(repo);
-            if wts.len() >= 2 {
-                // メインworktreeのパスで重複チェック
-                let main_path = wts[0].repo_path.clone();
-                let main_canonical =
-                    std::fs::canonicalize(&main_path).unwrap_or_else(|_| main_path.clone());
-                if !processed_repos.insert(main_canonical) {
-                    continue; // 同じリポのworktreeは既に処理済み
-                }
-                for wt in &wts {
-                    if !wt.is_main {
-                        worktree_paths.insert(
-                            std::fs::canonicalize(&wt.path).unwrap_or_else(|_| wt.path.clone()),
-                        );
-                    }
+    /// What the commit of `commit_scope` records, against HEAD (HEAD's parent when amending)
+    fn commit_diff(&self) -> Option<git2::Diff<'_>> {
+        let head = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let base = if self.amending_commit() {
+            head.and_then(|c| c.parent(0).ok())
+        } else {
+            head
+        };
+        let base_tree = base.and_then(|c| c.tree().ok());
+        let mut opts = DiffOptions::new();
+        match &self.commit_scope {
+            CommitScope::Index => {
+                return self
+                    .repo
+                    .diff_tree_to_index(base_tree.as_ref(), None, None)
+                    .ok();
+            }
+            CommitScope::Tracked => {}
+            CommitScope::Paths(paths) => {
+                opts.disable_pathspec_match(true);
+                for path in paths {
+                    opts.pathspec(path);
                 }
-                all_worktrees.extend(wts);
+            }
+            CommitScope::Wip(_) => {
+                opts.include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
             }
         }
+        self.repo
+            .diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut opts))
+            .ok()
+    }
 
-        // worktreeパス（メイン以外）をreposから除外
-        repos.retain(|r| {
-            let canonical = std::fs::canonicalize(r).unwrap_or_else(|_| r.clone());
-            !worktree_paths.contains(&canonical)
-        });
+    /// (files, insertions, deletions) the pending commit records; generated files don't count
+    pub fn commit_totals(&self) -> (usize, usize, usize) {
+        if self.commit_scope == CommitScope::Index {
+            return self.staged_diff_totals();
+        }
+        let Some(diff) = self.commit_diff() else {
+            return (0, 0, 0);
+        };
+        let mut totals = (0, 0, 0);
+        for (index, delta) in diff.deltas().enumerate() {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .and_then(|p| p.to_str())
+                .unwrap_or("");
+            if self.is_generated(path) {
+                continue;
+            }
+            let (add, del) = git2::Patch::from_diff(&diff, index)
+                .ok()
+                .flatten()
+                .and_then(|patch| patch.line_stats().ok())
+                .map_or((0, 0), |(_, add, del)| (add, del));
+            totals = (totals.0 + 1, totals.1 + add, totals.2 + del);
+        }
+        totals
+    }
 
-        self.available_repos = repos;
-        self.available_worktrees = all_worktrees;
+    /// Whether the index differs from HEAD; a merge in progress always has
+    /// something to commit, even when the result matches HEAD
+    fn has_staged_changes(&self) -> bool {
+        if self.repo.state() == git2::RepositoryState::Merge {
+            return true;
+        }
+        let head = self.repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+        self.repo
+            .diff_tree_to_index(head.as_ref(), None, None)
+            .map_or(true, |diff| self.staged_deltas(&diff) > 0)
     }
 
-    fn open_repo_select(&mut self) {
-        self.refresh_repo_and_worktree_list();
-        let current_idx = self.selector_current_index();
-        self.repo_select_state.select(Some(current_idx));
-        self.input_mode = InputMode::RepoSelect;
+    /// Deltas of a HEAD-to-index diff that git would commit: `git add -N`
+    /// entries only hold a place in the index
+    fn staged_deltas(&self, diff: &git2::Diff) -> usize {
+        let index = self.repo.index().ok();
+        diff.deltas()
+            .filter(|delta| {
+                delta.status() != git2::Delta::Added
+                    || !delta
+                        .new_file()
+                        .path()
+                        .and_then(|path| index.as_ref()?.get_path(path, 0))
+                        .is_some_and(|entry| is_intent_to_add(&entry))
+            })
+            .count()
     }
 
-    /// Total items in the selector (worktrees if >=2 + repos)
-    fn selector_items_count(&self) -> usize {
-        let wt = if self.available_worktrees.len() >= 2 {
-            self.available_worktrees.len()
-        } else {
-            0
-        };
-        wt + self.available_repos.len()
+    /// Warn when the commit goes over `[commit] max_files` / `max_lines`
+    fn check_size_or_commit(&mut self) -> Result<()> {
+        let (files, add, del) = self.commit_totals();
+        if self.repo_config.commit.exceeded(files, add + del) {
+            self.commit_problems.clear();
+            self.input_mode = InputMode::CommitSizeWarning;
+            return Ok(());
+        }
+        self.review_or_commit()
     }
 
-    /// Get the path for a selector index (worktrees first, then repos)
-    fn selector_path_at(&self, idx: usize) -> Option<PathBuf> {
-        let wt_count = if self.available_worktrees.len() >= 2 {
-            self.available_worktrees.len()
-        } else {
-            0
-        };
-        if idx < wt_count {
-            Some(self.available_worktrees[idx].path.clone())
-        } else {
-            self.available_repos.get(idx - wt_count).cloned()
+    /// Show the staged diff first when `[commit] review` is on, otherwise commit
+    fn review_or_commit(&mut self) -> Result<()> {
+        if !self.review_before_commit {
+            return self.commit_unchecked();
+        }
+        self.commit_review = self.commit_patch_lines();
+        if self.commit_review.is_empty() && self.allow_empty {
+            return self.commit_unchecked();
+        }
+        if self.commit_review.is_empty() && !self.amending_commit() {
+            self.message = Some(("Nothing staged to review".to_string(), true));
+            self.cancel_commit();
+            return Ok(());
         }
+        self.commit_problems.clear();
+        self.commit_review_scroll = 0;
+        self.input_mode = InputMode::CommitReview;
+        Ok(())
     }
 
-    /// Check if selector index points to a worktree
-    fn selector_is_worktree(&self, idx: usize) -> bool {
-        let wt_count = if self.available_worktrees.len() >= 2 {
-            self.available_worktrees.len()
-        } else {
-            0
-        };
-        idx < wt_count
+    /// Patch of what the commit will contain (see `commit_diff`)
+    fn commit_patch_lines(&self) -> Vec<String> {
+        self.commit_diff()
+            .map(|diff| patch_text(&diff).lines().map(str::to_string).collect())
+            .unwrap_or_default()
     }
 
-    /// Get worktree info at selector index (if it's a worktree)
-    fn selector_worktree_at(&self, idx: usize) -> Option<&WorktreeInfo> {
-        if self.selector_is_worktree(idx) {
-            self.available_worktrees.get(idx)
-        } else {
-            None
-        }
+    fn scroll_commit_review(&mut self, delta: isize) {
+        let max = self.commit_review.len().saturating_sub(1);
+        self.commit_review_scroll = self
+            .commit_review_scroll
+            .saturating_add_signed(delta)
+            .min(max);
     }
 
-    /// Find the selector index for the current repo_path
-    fn selector_current_index(&self) -> usize {
-        let wt_count = if self.available_worktrees.len() >= 2 {
-            self.available_worktrees.len()
+    /// Start a commit message, asking for a conventional `type(scope): ` prefix first if enabled
+    fn open_commit_input(&mut self) {
+        let conventional = &self.repo_config.conventional;
+        if conventional.enabled
+            && !conventional.types.is_empty()
+            && self.commit_message.trim().is_empty()
+        {
+            self.commit_type_state.select(Some(0));
+            self.commit_scope_input.clear();
+            self.input_mode = InputMode::CommitTypeSelect;
         } else {
-            0
-        };
-        // Check worktrees first
-        if wt_count > 0 {
-            if let Some(idx) = self.available_worktrees.iter().position(|w| w.is_current) {
-                return idx;
-            }
+            self.input_mode = InputMode::Insert;
+        }
+        // Staging may have changed since the amend was started
+        if self.is_amending {
+            self.amend_preview = self.amend_files().unwrap_or_default();
         }
-        // Then repos
-        self.available_repos
-            .iter()
-            .position(|p| p == &self.repo_path)
-            .map(|i| i + wt_count)
-            .unwrap_or(0)
     }
 
-    fn repo_select_next(&mut self) {
-        let len = self.selector_items_count();
-        if len > 0 {
-            let i = self.repo_select_state.selected().unwrap_or(0);
+    /// Fill the commit message with the picked prefix and continue typing after it
+    fn apply_conventional_prefix(&mut self) {
+        let kind = self
+            .commit_type_state
+            .selected()
+            .and_then(|i| self.repo_config.conventional.types.get(i))
+            .cloned()
+            .unwrap_or_default();
+        self.commit_message = conventional_prefix(&kind, &self.commit_scope_input);
+        self.cursor_pos = self.commit_message.len();
+        self.commit_scope_input.clear();
+        self.input_mode = InputMode::Insert;
+    }
+
+    /// Tab in the scope input: step through the configured scopes
+    fn cycle_commit_scope(&mut self) {
+        let scopes = &self.repo_config.conventional.scopes;
+        if scopes.is_empty() {
+            return;
+        }
+        let next = scopes
+            .iter()
+            .position(|s| *s == self.commit_scope_input)
+            .map_or(0, |i| (i + 1) % scopes.len());
+        self.commit_scope_input = scopes[next].clone();
+    }
+
+    /// Pick `Co-authored-by:` trailers from configured collaborators and recent authors
+    fn open_co_author_select(&mut self) {
+        let me = self
+            .repo
+            .signature()
+            .ok()
+            .and_then(|s| s.email().map(str::to_lowercase));
+        let mut candidates: Vec<String> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut add = |person: String| {
+            let email = person_email(&person).to_lowercase();
+            if Some(&email) != me.as_ref() && seen.insert(email) {
+                candidates.push(person);
+            }
+        };
+        for person in &self.co_authors_config {
+            add(person.trim().to_string());
+        }
+        if let Ok(mut revwalk) = self.repo.revwalk()
+            && revwalk.push_head().is_ok()
+        {
+            for oid in revwalk.flatten().take(200) {
+                if let Ok(commit) = self.repo.find_commit(oid)
+                    && let (Some(name), Some(email)) =
+                        (commit.author().name(), commit.author().email())
+                {
+                    add(format!("{} <{}>", name, email));
+                }
+            }
+        }
+        if candidates.is_empty() {
+            self.message = Some((
+                "No collaborators ([commit] co_authors or other commit authors)".to_string(),
+                true,
+            ));
+            return;
+        }
+        // Trailers already in the message start out checked
+        self.co_author_marked = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, person)| {
+                self.commit_message
+                    .contains(&format!("Co-authored-by: {}", person))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.co_author_candidates = candidates;
+        self.co_author_state.select(Some(0));
+        self.input_mode = InputMode::CoAuthorSelect;
+    }
+
+    /// Add checked trailers and drop the ones that were unchecked
+    fn apply_co_authors(&mut self) {
+        let mut checked = Vec::new();
+        let mut unchecked = Vec::new();
+        for (i, person) in self.co_author_candidates.iter().enumerate() {
+            let trailer = format!("Co-authored-by: {}", person);
+            if self.co_author_marked.contains(&i) {
+                checked.push(trailer);
+            } else {
+                unchecked.push(trailer);
+            }
+        }
+        let kept: Vec<&str> = self
+            .commit_message
+            .lines()
+            .filter(|l| !unchecked.iter().any(|t| l.trim() == t))
+            .collect();
+        self.commit_message = append_trailers(&kept.join("\n"), &checked);
+        self.cursor_pos = self.commit_message.len();
+        self.co_author_candidates.clear();
+        self.co_author_marked.clear();
+        self.input_mode = InputMode::Insert;
+    }
+
+    /// Editor for commit messages: $GIT_EDITOR, core.editor, then the [editor] config
+    pub fn commit_editor(&self) -> String {
+        std::env::var("GIT_EDITOR")
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+            .or_else(|| {
+                self.repo
+                    .config()
+                    .ok()
+                    .and_then(|c| c.get_string("core.editor").ok())
+            })
+            .unwrap_or_else(|| Config::load().editor.resolve())
+    }
+
+    /// Write .git/COMMIT_EDITMSG with the typed message and a commented status summary
+    pub fn write_commit_template(&self) -> Result<PathBuf> {
+        let path = self.repo.path().join("COMMIT_EDITMSG");
+        let mut text = match self.commit_message.trim_end() {
+            "" => "\n".to_string(),
+            typed => format!("{}\n\n", typed),
+        };
+        text.push_str(
+            "# Please enter the commit message for your changes. Lines starting\n\
+             # with '#' will be ignored, and an empty message aborts the commit.\n#\n",
+        );
+        text.push_str(&format!("# On branch {}\n", self.branch_name));
+        let sections = [
+            ("Changes to be committed:", true),
+            ("Changes not staged for commit:", false),
+        ];
+        for (heading, staged) in sections {
+            let files: Vec<&FileEntry> = self
+                .files
+                .iter()
+                .filter(|f| f.staged == staged && f.status != FileStatus::Untracked)
+                .collect();
+            if files.is_empty() {
+                continue;
+            }
+            text.push_str(&format!("#\n# {}\n", heading));
+            for file in files {
+                let label = match file.status {
+                    FileStatus::Added => "new file",
+                    FileStatus::Modified => "modified",
+                    FileStatus::Deleted => "deleted",
+                    FileStatus::Untracked => "untracked",
+                    FileStatus::Conflicted => "both modified",
+                };
+                text.push_str(&format!("#\t{:<10} {}\n", format!("{}:", label), file.path));
+            }
+        }
+        let untracked: Vec<&FileEntry> = self
+            .files
+            .iter()
+            .filter(|f| f.status == FileStatus::Untracked)
+            .collect();
+        if !untracked.is_empty() {
+            text.push_str("#\n# Untracked files:\n");
+            for file in untracked {
+                text.push_str(&format!("#\t{}\n", file.path));
+            }
+        }
+        std::fs::write(&path, text)?;
+        Ok(path)
+    }
+
+    /// Commit with the message saved in the editor (after the editor exits successfully)
+    pub fn commit_from_editor(&mut self, path: &Path) -> Result<()> {
+        let message = strip_comment_lines(&std::fs::read_to_string(path)?);
+        if message.trim().is_empty() {
+            self.message = Some((
+                "Aborting commit due to empty commit message".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+        self.commit_message = normalize_commit_message(&message);
+        self.cursor_pos = self.commit_message.len();
+        self.commit()
+    }
+
+    /// Scan the commit's hunks for leftover conflict markers and whitespace-only changes
+    fn find_commit_problems(&self) -> Vec<CommitProblem> {
+        let mut problems = Vec::new();
+        let Some(diff) = self.commit_diff() else {
+            return problems;
+        };
+        for file in diff_viewer::parse_diff_files(&patch_text(&diff)) {
+            for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+                let kind = if diff_viewer::has_conflict_markers(hunk) {
+                    CommitProblemKind::ConflictMarker
+                } else if diff_viewer::is_trailing_whitespace_only(hunk) {
+                    CommitProblemKind::TrailingWhitespaceOnly
+                } else {
+                    continue;
+                };
+                problems.push(CommitProblem {
+                    path: file.path.clone(),
+                    hunk_index,
+                    kind,
+                });
+            }
+        }
+        problems
+    }
+
+    /// Open the hunk view on the selected problem (commit message is kept)
+    fn jump_to_commit_problem(&mut self) {
+        let Some(problem) = self
+            .commit_problems_state
+            .selected()
+            .and_then(|i| self.commit_problems.get(i))
+            .cloned()
+        else {
+            return;
+        };
+        self.cancel_commit();
+        self.input_mode = InputMode::Normal;
+        self.tab = Tab::Files;
+        // Ctrl+A / Ctrl+O commits take the working tree, so the problem may be unstaged
+        let find = |staged: bool| {
+            self.visual_list.iter().position(|&i| {
+                self.files
+                    .get(i)
+                    .is_some_and(|f| f.path == problem.path && (f.staged || !staged))
+            })
+        };
+        if let Some(visual_idx) = find(true).or_else(|| find(false)) {
+            self.files_state.select(Some(visual_idx));
+        }
+        self.open_hunk_view();
+        if let Some(view) = &mut self.diff_view {
+            view.selected = problem.hunk_index.min(view.hunks.len().saturating_sub(1));
+        }
+    }
+
+    /// Ctrl+G: pipe the staged diff to `commit.suggest_command` in the background,
+    /// or take the suggestion that was held back because the message changed
+    fn suggest_commit_message(&mut self) {
+        if let Some(suggestion) = self.pending_suggestion.take() {
+            self.commit_message = suggestion;
+            self.cursor_pos = self.commit_message.len();
+            self.message = Some((
+                "Suggested message inserted — edit before committing".to_string(),
+                false,
+            ));
+            return;
+        }
+        let Some(command) = self.suggest_command.clone() else {
+            self.message = Some((
+                "No suggest command (config.toml [commit] suggest_command)".to_string(),
+                true,
+            ));
+            return;
+        };
+        if !self.has_staged_changes() {
+            self.message = Some(("Nothing staged to describe".to_string(), true));
+            return;
+        }
+        self.suggest_base = self.commit_message.clone();
+        let repo_path = self.repo_path.clone();
+        self.start_processing(Processing::Generating, move || {
+            run_suggest_command(&repo_path, &command)
+        });
+    }
+
+    /// `W`: stage everything and commit it as a timestamped save point
+    fn wip_commit(&mut self) -> Result<()> {
+        if self.processing.is_active() {
+            self.message = Some(("Wait for the current operation".to_string(), true));
+            return Ok(());
+        }
+        let message = format!("{}{}", WIP_PREFIX, date::now_utc());
+        self.commit_scope = CommitScope::Wip(message);
+        self.commit()
+    }
+
+    /// `U`: put the changes of a WIP commit at HEAD back into the working tree
+    fn undo_wip_commit(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Ok((is_wip, parents)) = self.repo.head().and_then(|h| h.peel_to_commit()).map(|c| {
+            (
+                c.summary().unwrap_or("").starts_with(WIP_PREFIX),
+                c.parent_count(),
+            )
+        }) else {
+            return Ok(());
+        };
+        if !is_wip {
+            self.message = Some(("HEAD is not a WIP commit".to_string(), true));
+            return Ok(());
+        }
+        if parents == 0 {
+            self.message = Some(("Can't undo the first commit".to_string(), true));
+            return Ok(());
+        }
+        if self.head_is_pushed() {
+            self.message = Some(("WIP commit is already pushed".to_string(), true));
+            return Ok(());
+        }
+        // Mixed reset: the changes come back unstaged, as before `W`
+        match run_git(
+            &self.repo_path,
+            &["reset", "HEAD~1"],
+            "WIP changes restored to the working tree",
+            "Undo WIP failed",
+        ) {
+            Ok(msg) => self.message = Some((summary_line(&msg).to_string(), false)),
+            Err(e) => self.message = Some((summary_line(&e).to_string(), true)),
+        }
+        self.refresh()
+    }
+
+    /// `git commit --allow-empty` with the typed message (CI triggers, release markers)
+    fn commit_empty(&mut self) -> Result<()> {
+        if self.commit_message.trim().is_empty() {
+            self.message = Some(("Empty commit message".to_string(), true));
+            return Ok(());
+        }
+        if self.is_amending {
+            self.message = Some(("Empty commits can't amend HEAD".to_string(), true));
+            return Ok(());
+        }
+        if self.files.iter().any(|f| f.staged) {
+            self.message = Some((
+                "Staged changes would be included: unstage them or commit with Enter".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+        self.allow_empty = true;
+        self.commit()
+    }
+
+    // === Identity profiles ===
+
+    /// (user.name, user.email) as git resolves them for this repo
+    pub fn current_identity(&self) -> (Option<String>, Option<String>) {
+        let Ok(config) = self.repo.config() else {
+            return (None, None);
+        };
+        (
+            config.get_string("user.name").ok(),
+            config.get_string("user.email").ok(),
+        )
+    }
+
+    /// The profile whose host matches origin, if any
+    pub fn expected_profile(&self) -> Option<&Profile> {
+        let remote = self.repo.find_remote("origin").ok()?;
+        profile_for_remote(&self.profiles, remote.url()?)
+    }
+
+    fn open_profile_select(&mut self) {
+        if self.profiles.is_empty() {
+            self.message = Some((
+                "No [[profiles]] in ~/.config/siori/config.toml".to_string(),
+                true,
+            ));
+            return;
+        }
+        // Start on the identity this repo should use, else the one in use
+        let email = self.current_identity().1;
+        let selected = self
+            .expected_profile()
+            .and_then(|expected| self.profiles.iter().position(|p| p == expected))
+            .or_else(|| {
+                self.profiles
+                    .iter()
+                    .position(|p| Some(&p.email) == email.as_ref())
+            })
+            .unwrap_or(0);
+        self.profile_state.select(Some(selected));
+        self.input_mode = InputMode::ProfileSelect;
+    }
+
+    /// Write the selected profile to the repo's local git config
+    fn apply_profile(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(profile) = self
+            .profile_state
+            .selected()
+            .and_then(|i| self.profiles.get(i))
+            .cloned()
+        else {
+            return;
+        };
+        let mut settings = vec![
+            ("user.name", profile.user_name.as_str()),
+            ("user.email", profile.email.as_str()),
+        ];
+        if let Some(key) = &profile.signing_key {
+            settings.push(("user.signingkey", key.as_str()));
+        }
+        for (key, value) in settings {
+            if let Err(e) = run_git(
+                &self.repo_path,
+                &["config", "--local", key, value],
+                "",
+                "Switching identity failed",
+            ) {
+                self.message = Some((summary_line(&e).to_string(), true));
+                return;
+            }
+        }
+        self.message = Some((
+            format!(
+                "Identity: {} <{}> ({})",
+                profile.user_name, profile.email, profile.name
+            ),
+            false,
+        ));
+    }
+
+    /// Ctrl+D: type a date for the next commit, prefilled with the current one
+    fn open_commit_date_input(&mut self) {
+        self.commit_date_input = self
+            .commit_date
+            .as_ref()
+            .map(|(text, _)| text.clone())
+            .unwrap_or_default();
+        self.input_mode = InputMode::CommitDateInput;
+    }
+
+    /// Resolve the typed date with git's own parser ("yesterday 17:00",
+    /// "2024-05-01 09:30", ...); an empty input goes back to now
+    fn apply_commit_date(&mut self) {
+        let text = self.commit_date_input.trim().to_string();
+        self.commit_date_input.clear();
+        self.input_mode = InputMode::Insert;
+        if text.is_empty() {
+            if self.commit_date.take().is_some() {
+                self.message = Some(("Commit date: now".to_string(), false));
+            }
+            return;
+        }
+        let Some(timestamp) = resolve_git_date(&self.repo_path, &text) else {
+            self.message = Some((format!("Could not parse date: {}", text), true));
+            return;
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        if timestamp > now {
+            self.message = Some((format!("Date is in the future: {}", text), true));
+            return;
+        }
+        // git falls back to now for words it doesn't know ("yesterdy")
+        if now - timestamp <= 5 && !means_now(&text) {
+            self.message = Some((format!("Could not parse date: {}", text), true));
+            return;
+        }
+        self.message = Some((
+            format!(
+                "Commit date: {} ({})",
+                text,
+                format_relative_time(timestamp)
+            ),
+            false,
+        ));
+        self.commit_date = Some((text, timestamp));
+    }
+
+    /// `git commit -- <paths>` for the marked files (or the selected one), leaving
+    /// the rest of the index staged
+    fn commit_only_marked(&mut self) -> Result<()> {
+        if self.commit_message.trim().is_empty() {
+            self.message = Some(("Empty commit message".to_string(), true));
+            return Ok(());
+        }
+        let targets: Vec<&FileEntry> = if self.marked_files.is_empty() {
+            self.selected_file().into_iter().collect()
+        } else {
+            self.files.iter().filter(|f| self.is_marked(f)).collect()
+        };
+        if targets.is_empty() {
+            self.message = Some(("No file selected".to_string(), true));
+            return Ok(());
+        }
+        // git only knows paths that are tracked or already in the index
+        if let Some(file) = targets
+            .iter()
+            .find(|f| f.status == FileStatus::Untracked && !f.staged)
+        {
+            self.message = Some((format!("Stage untracked file first: {}", file.path), true));
+            return Ok(());
+        }
+        let mut paths: Vec<String> = targets.iter().map(|f| f.path.clone()).collect();
+        paths.sort();
+        paths.dedup();
+        self.commit_only_paths(paths)
+    }
+
+    /// Ctrl+F: pick which staged files go into this commit (all checked to start)
+    fn open_commit_file_select(&mut self) {
+        if self.commit_message.trim().is_empty() {
+            self.message = Some(("Empty commit message".to_string(), true));
+            return;
+        }
+        if self.is_amending {
+            self.message = Some(("Partial commits can't amend HEAD".to_string(), true));
+            return;
+        }
+        let mut choices: Vec<(String, bool)> = self
+            .files
+            .iter()
+            .filter(|f| f.staged)
+            .map(|f| {
+                // The working tree version is what `git commit -- <path>` records
+                let unstaged = self.files.iter().any(|u| !u.staged && u.path == f.path);
+                (f.path.clone(), unstaged)
+            })
+            .collect();
+        choices.sort();
+        if choices.is_empty() {
+            self.message = Some((
+                "Nothing staged — press Space to stage files".to_string(),
+                true,
+            ));
+            return;
+        }
+        self.commit_file_marked = (0..choices.len()).collect();
+        self.commit_file_choices = choices;
+        self.commit_file_state.select(Some(0));
+        self.input_mode = InputMode::CommitFileSelect;
+    }
+
+    /// Commit the checked files; the unchecked ones stay staged
+    fn commit_selected_files(&mut self) -> Result<()> {
+        let choices = std::mem::take(&mut self.commit_file_choices);
+        let marked = std::mem::take(&mut self.commit_file_marked);
+        self.input_mode = InputMode::Insert;
+        if marked.is_empty() {
+            self.message = Some(("No file checked".to_string(), true));
+            return Ok(());
+        }
+        if marked.len() == choices.len() {
+            return self.commit();
+        }
+        let paths = choices
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| marked.contains(i))
+            .map(|(_, (path, _))| path)
+            .collect();
+        self.commit_only_paths(paths)
+    }
+
+    fn commit_only_paths(&mut self, paths: Vec<String>) -> Result<()> {
+        self.commit_scope = CommitScope::Paths(paths);
+        self.commit()
+    }
+
+    /// Start the commit of `commit_scope`; only after `commit()`'s checks
+    fn commit_unchecked(&mut self) -> Result<()> {
+        // A commit can queue behind a running push or fetch
+        if !self.ensure_healthy() {
+            self.cancel_commit();
+            return Ok(());
+        }
+        if self.processing == Processing::Committing
+            || self
+                .queued_operations
+                .iter()
+                .any(|q| q.state == Processing::Committing)
+        {
+            // Keep the typed message until the pending commit finishes
+            self.message = Some(("Already queued: Commit".to_string(), true));
+            self.cancel_commit();
+            return Ok(());
+        }
+        self.commit_problems.clear();
+        self.input_mode = InputMode::Normal;
+
+        if let CommitScope::Wip(message) = &self.commit_scope {
+            // The typed message, amend and date are for the next real commit
+            let attempt = CommitAttempt {
+                message: message.clone(),
+                amend: false,
+                allow_empty: false,
+                scope: std::mem::take(&mut self.commit_scope),
+                date: None,
+                signoff: self.signoff,
+            };
+            self.start_commit(attempt, false);
+            return Ok(());
+        }
+        if matches!(self.commit_scope, CommitScope::Paths(_)) {
+            self.marked_files.clear();
+        }
+        let attempt = CommitAttempt {
+            message: normalize_commit_message(&self.commit_message),
+            amend: self.is_amending,
+            allow_empty: self.allow_empty,
+            scope: std::mem::take(&mut self.commit_scope),
+            date: self.commit_date.take().map(|(_, timestamp)| timestamp),
+            // Ctrl+S is for this commit; the next one starts from the config again
+            signoff: std::mem::replace(&mut self.signoff, self.repo_config.commit.signoff),
+        };
+        self.commit_message.clear();
+        self.cursor_pos = 0;
+        self.pending_suggestion = None;
+        self.is_amending = false;
+        self.amend_preview.clear();
+        self.allow_empty = false;
+
+        self.start_commit(attempt, false);
+        Ok(())
+    }
+
+    fn start_commit(&mut self, attempt: CommitAttempt, no_verify: bool) {
+        self.last_commit_attempt = Some(attempt.clone());
+        let repo_path = self.repo_path.clone();
+        self.start_processing(Processing::Committing, move || {
+            let CommitAttempt {
+                message,
+                amend,
+                allow_empty,
+                scope,
+                date,
+                signoff,
+            } = attempt;
+            // --date only sets the author date; the committer date comes from the env
+            let date = date.map(|timestamp| format!("@{}", timestamp));
+            let date_arg = date.as_ref().map(|d| format!("--date={}", d));
+            let mut args = vec!["commit"];
+            if amend {
+                args.push("--amend");
+            }
+            if let Some(arg) = &date_arg {
+                args.push(arg);
+            }
+            if allow_empty {
+                args.push("--allow-empty");
+            }
+            if scope == CommitScope::Tracked {
+                args.push("--all");
+            }
+            if signoff {
+                args.push("--signoff");
+            }
+            if no_verify {
+                args.push("--no-verify");
+            }
+            args.extend(["-m", &message]);
+            if let CommitScope::Paths(paths) = &scope {
+                args.push("--");
+                args.extend(paths.iter().map(String::as_str));
+            }
+            let (success, prefix) = match &scope {
+                CommitScope::Wip(_) => {
+                    run_git(&repo_path, &["add", "-A"], "", "Stage failed")?;
+                    (format!("Saved: {}", message), "WIP commit failed")
+                }
+                _ if amend => ("Amended successfully".to_string(), "Amend failed"),
+                _ => ("Committed successfully".to_string(), "Commit failed"),
+            };
+            let envs: Vec<(&str, &str)> = date
+                .iter()
+                .map(|d| ("GIT_COMMITTER_DATE", d.as_str()))
+                .collect();
+            run_git_with_env(&repo_path, &args, &envs, &success, prefix)
+        });
+    }
+
+    /// Whether a hook can reject `git commit` (pre-commit, commit-msg, ...)
+    fn has_commit_hooks(&self) -> bool {
+        let hooks_dir = self
+            .repo
+            .config()
+            .ok()
+            .and_then(|c| c.get_path("core.hooksPath").ok())
+            .map(|p| {
+                if p.is_relative() {
+                    self.repo_path.join(p)
+                } else {
+                    p
+                }
+            })
+            .unwrap_or_else(|| self.repo.path().join("hooks"));
+        ["pre-commit", "prepare-commit-msg", "commit-msg"]
+            .iter()
+            .any(|hook| hooks_dir.join(hook).is_file())
+    }
+
+    fn run_recovery(&mut self, action: RecoveryAction) {
+        self.input_mode = InputMode::Normal;
+        self.recovery_actions.clear();
+        match action {
+            RecoveryAction::PullRebaseThenPush => {
+                let pull = self.upstream_args(&["pull", "--rebase"]);
+                let push = self.upstream_args(&["push"]);
+                let repo_path = self.repo_path.clone();
+                self.start_processing(Processing::Pushing, move || {
+                    let pull: Vec<&str> = pull.iter().map(String::as_str).collect();
+                    let push: Vec<&str> = push.iter().map(String::as_str).collect();
+                    run_git(&repo_path, &pull, "Rebased", "Pull --rebase failed")?;
+                    run_git(&repo_path, &push, "Pushed successfully", "Push failed")
+                });
+            }
+            RecoveryAction::ForceWithLease => {
+                let push = self.upstream_args(&["push", "--force-with-lease"]);
+                let repo_path = self.repo_path.clone();
+                self.start_processing(Processing::Pushing, move || {
+                    let push: Vec<&str> = push.iter().map(String::as_str).collect();
+                    run_git(
+                        &repo_path,
+                        &push,
+                        "Force-pushed (with lease)",
+                        "Push failed",
+                    )
+                });
+            }
+            RecoveryAction::CommitNoVerify => {
+                if let Some(attempt) = self.last_commit_attempt.clone() {
+                    self.start_commit(attempt, true);
+                }
+            }
+            RecoveryAction::RollbackRelease => {
+                if let Err(e) = self.rollback_release() {
+                    self.message = Some((format!("Rollback failed: {e}"), true));
+                }
+            }
+        }
+    }
+
+    /// Delete the release tag and drop the bump commit if it never left this machine
+    fn rollback_release(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(rollback) = self.release_rollback.take() else {
+            return Ok(());
+        };
+        // The rollback is also offered when creating the tag was what failed
+        let had_tag = self
+            .repo
+            .find_reference(&format!("refs/tags/{}", rollback.tag_name))
+            .is_ok();
+        if had_tag {
+            let output = std::process::Command::new("git")
+                .current_dir(&self.repo_path)
+                .args(["tag", "-d", &rollback.tag_name])
+                .output()?;
+            if !output.status.success() {
+                anyhow::bail!("{}", command_error(&output, "git tag -d failed"));
+            }
+        }
+        let tag_result = if had_tag {
+            format!("Deleted tag {}", rollback.tag_name)
+        } else {
+            format!("Tag {} was never created", rollback.tag_name)
+        };
+
+        let head = self.repo.head().ok().and_then(|h| h.target());
+        let bump = rollback.bump_commit.to_string();
+        let on_remote = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["branch", "-r", "--contains", &bump])
+            .output()
+            .map(|o| !o.stdout.is_empty())
+            .unwrap_or(false);
+
+        let msg = if on_remote {
+            format!(
+                "{}; bump commit is already on the remote and was kept",
+                tag_result
+            )
+        } else if head == Some(rollback.bump_commit) {
+            // --keep restores the bumped files but refuses to touch unrelated edits
+            let output = std::process::Command::new("git")
+                .current_dir(&self.repo_path)
+                .args(["reset", "--keep", &rollback.previous.to_string()])
+                .output()?;
+            if !output.status.success() {
+                anyhow::bail!("{}", command_error(&output, "git reset failed"));
+            }
+            format!("Rolled back release {}", rollback.tag_name)
+        } else {
+            format!("{}; HEAD moved since the bump, commit kept", tag_result)
+        };
+        self.message = Some((msg, false));
+        self.refresh()
+    }
+
+    /// `args` plus `origin <branch>` when the branch has no upstream yet
+    fn upstream_args(&self, args: &[&str]) -> Vec<String> {
+        let has_upstream = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        if !has_upstream {
+            args.extend(["origin".to_string(), self.branch_name.clone()]);
+        }
+        args
+    }
+
+    fn start_amend(&mut self) -> Result<()> {
+        // Only allow amending HEAD commit
+        let Some(idx) = self.commits_state.selected() else {
+            return Ok(());
+        };
+        let Some(commit) = self.commits.get(idx) else {
+            return Ok(());
+        };
+        if !commit.is_head {
+            self.message = Some(("Can only amend HEAD commit".to_string(), true));
+            return Ok(());
+        }
+
+        // Show what the amended commit will contain before touching the message
+        self.amend_preview = self.amend_files()?;
+        self.input_mode = InputMode::AmendPreview;
+        Ok(())
+    }
+
+    fn confirm_amend(&mut self) {
+        // The full message, so amending keeps the body
+        let Some(message) = self
+            .repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .ok()
+            .and_then(|c| c.message().map(|m| m.trim_end().to_string()))
+        else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        self.commit_message = message;
+        self.cursor_pos = self.commit_message.len();
+        // amend_preview stays listed under the message until the commit is made
+        self.is_amending = true;
+        self.input_mode = InputMode::Insert;
+        self.tab = Tab::Files; // Switch to Files tab to show input
+    }
+
+    /// HEAD's own changes merged with what is staged on top of it
+    fn amend_files(&self) -> Result<Vec<AmendFile>> {
+        let head = self.repo.head()?.peel_to_commit()?;
+        let head_tree = head.tree()?;
+        let parent_tree = head.parent(0).ok().map(|p| p.tree()).transpose()?;
+
+        let mut files: Vec<AmendFile> = Vec::new();
+        let head_diff =
+            self.repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&head_tree), None)?;
+        for delta in head_diff.deltas() {
+            files.push(AmendFile {
+                path: delta_path(&delta),
+                status: delta_status_char(delta.status()),
+                in_head: true,
+                staged: false,
+                stats: None,
+            });
+        }
+        let staged_diff = self.repo.diff_tree_to_index(Some(&head_tree), None, None)?;
+        for delta in staged_diff.deltas() {
+            let path = delta_path(&delta);
+            if let Some(file) = files.iter_mut().find(|f| f.path == path) {
+                file.staged = true;
+            } else {
+                files.push(AmendFile {
+                    path,
+                    status: delta_status_char(delta.status()),
+                    in_head: false,
+                    staged: true,
+                    stats: None,
+                });
+            }
+        }
+        // What the amended commit will change relative to HEAD's parent
+        let amended_diff = self
+            .repo
+            .diff_tree_to_index(parent_tree.as_ref(), None, None)?;
+        for idx in 0..amended_diff.deltas().len() {
+            let Ok(Some(patch)) = git2::Patch::from_diff(&amended_diff, idx) else {
+                continue;
+            };
+            let path = delta_path(&patch.delta());
+            let binary = patch.delta().flags().is_binary();
+            if let Some(file) = files.iter_mut().find(|f| f.path == path)
+                && !binary
+                && let Ok((_, add, del)) = patch.line_stats()
+            {
+                file.stats = Some((add, del));
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    fn push(&mut self) -> Result<()> {
+        // Quick check for remote configuration
+        let check = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["remote", "get-url", "origin"])
+            .output();
+
+        if check.is_err() || !check.unwrap().status.success() {
+            self.input_mode = InputMode::RemoteUrl;
+            self.remote_url.clear();
+            self.message = Some((
+                "No remote configured. Enter repository URL:".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+
+        // Check if upstream is configured
+        let has_upstream = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+
+        let repo_path = self.repo_path.clone();
+        if has_upstream {
+            self.start_processing(Processing::Pushing, move || {
+                run_git(&repo_path, &["push"], "Pushed successfully", "Push failed")
+            });
+        } else {
+            let branch = self.branch_name.clone();
+            self.start_processing(Processing::Pushing, move || {
+                run_git(
+                    &repo_path,
+                    &["push", "-u", "origin", &branch],
+                    "Pushed successfully",
+                    "Push failed",
+                )
+            });
+        }
+        Ok(())
+    }
+
+    fn add_remote_and_push(&mut self) -> Result<()> {
+        let url = self.remote_url.trim().to_string();
+        if url.is_empty() {
+            self.message = Some(("URL is empty".to_string(), true));
+            return Ok(());
+        }
+
+        let add_output = std::process::Command::new("git")
+            .args(["remote", "add", "origin", &url])
+            .output()
+            .context("Failed to add remote")?;
+
+        if !add_output.status.success() {
+            let err = String::from_utf8_lossy(&add_output.stderr);
+            self.message = Some((format!("Failed: {}", err.trim()), true));
+            self.remote_url.clear();
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        }
+
+        let push_output = std::process::Command::new("git")
+            .args(["push", "-u", "origin", &self.branch_name])
+            .output()
+            .context("Failed to push")?;
+
+        if push_output.status.success() {
+            self.message = Some(("Remote added & pushed!".to_string(), false));
+        } else {
+            let err = String::from_utf8_lossy(&push_output.stderr);
+            self.message = Some((format!("Push failed: {}", err.trim()), true));
+        }
+
+        self.remote_url.clear();
+        self.input_mode = InputMode::Normal;
+        self.refresh()?;
+        Ok(())
+    }
+
+    fn pull(&mut self) -> Result<()> {
+        let repo_path = self.repo_path.clone();
+        self.start_processing(Processing::Pulling, move || {
+            run_git(
+                &repo_path,
+                &["pull", "--no-rebase"],
+                "Pulled successfully",
+                "Pull failed",
+            )
+        });
+        Ok(())
+    }
+
+    /// `f` in the Log tab: update remote-tracking branches without touching the work
+    fn fetch(&mut self) {
+        let repo_path = self.repo_path.clone();
+        self.start_processing(Processing::Fetching, move || {
+            run_git(
+                &repo_path,
+                &["fetch", "--prune", "origin"],
+                "Fetched: press i to see what pull brings in",
+                "Fetch failed",
+            )
+        });
+    }
+
+    /// Commits HEAD is behind origin, when the Log tab shows the ↓ row for them
+    pub fn incoming_row(&self) -> Option<usize> {
+        self.ahead_behind
+            .map(|(_, behind)| behind)
+            .filter(|&behind| behind > 0)
+    }
+
+    /// Whether the Log cursor is on the ↓ row, which Enter opens as the incoming view
+    pub fn on_incoming_row(&self) -> bool {
+        self.incoming_row_selected && self.incoming_row().is_some()
+    }
+
+    /// Put the Log cursor on the ↓ row; commit actions find nothing selected meanwhile
+    fn select_incoming_row(&mut self) {
+        self.incoming_row_selected = true;
+        self.commits_state.select(None);
+    }
+
+    /// Enter on the ↓ row, or `i`, in the Log tab: commits on the upstream not in HEAD,
+    /// and their combined diffstat
+    fn open_incoming(&mut self) -> Result<()> {
+        let upstream = self.upstream_name.clone();
+        let Ok(remote) = self
+            .repo
+            .find_branch(&upstream, git2::BranchType::Remote)
+            .and_then(|b| b.get().peel_to_commit())
+        else {
+            self.message = Some((format!("No {} yet: press f to fetch", upstream), true));
+            return Ok(());
+        };
+        let Ok(head) = self.repo.head().and_then(|h| h.peel_to_commit()) else {
+            self.message = Some(("No commits yet: nothing to compare with".to_string(), true));
+            return Ok(());
+        };
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(remote.id())?;
+        revwalk.hide(head.id())?;
+        let _ = revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME);
+        let commits: Vec<(String, String, String, String)> = revwalk
+            .filter_map(|oid| oid.ok())
+            .filter_map(|oid| self.repo.find_commit(oid).ok())
+            .map(|c| {
+                (
+                    format!("{:.7}", c.id()),
+                    c.summary().unwrap_or("").to_string(),
+                    c.author().name().unwrap_or("").to_string(),
+                    format_relative_time(c.time().seconds()),
+                )
+            })
+            .collect();
+        if commits.is_empty() {
+            self.message = Some((format!("Nothing to pull from {}", upstream), false));
+            return Ok(());
+        }
+
+        let Ok(base) = self.repo.merge_base(head.id(), remote.id()) else {
+            self.message = Some((format!("{} shares no history with HEAD", upstream), true));
+            return Ok(());
+        };
+        let base_tree = self.repo.find_commit(base)?.tree()?;
+        let files = diff_file_stats(&self.repo, &base_tree, &remote.tree()?)?;
+
+        self.incoming_state.select((!files.is_empty()).then_some(0));
+        self.incoming = Some(Incoming {
+            upstream,
+            commits,
+            files,
+        });
+        self.input_mode = InputMode::IncomingView;
+        Ok(())
+    }
+
+    // === Compare ===
+
+    /// `d` in the Log tab: files changed between the marked and the selected commit
+    fn open_comparison(&mut self) -> Result<()> {
+        let Some(index) = self.commits_state.selected() else {
+            return Ok(());
+        };
+        let Some(selected) = self.commits.get(index).map(|c| c.full_id) else {
+            return Ok(());
+        };
+        let Some(marked) = self.log_mark else {
+            self.message = Some((
+                "Mark a commit with Space, then select another to compare".to_string(),
+                true,
+            ));
+            return Ok(());
+        };
+        if marked == selected {
+            self.message = Some((
+                "Select another commit to compare with the marked one".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+        // The log lists newest first, so the lower row is the older side
+        let marked_row = self.commits.iter().position(|c| c.full_id == marked);
+        let (from, to) = match marked_row {
+            Some(row) if row < index => (selected, marked),
+            _ => (marked, selected),
+        };
+        let files = diff_file_stats(
+            &self.repo,
+            &self.repo.find_commit(from)?.tree()?,
+            &self.repo.find_commit(to)?.tree()?,
+        )?;
+        let comparison = Comparison { from, to, files };
+        if comparison.files.is_empty() {
+            self.message = Some((format!("No differences in {}", comparison.range()), false));
+            return Ok(());
+        }
+        self.comparison_state.select(Some(0));
+        self.comparison = Some(comparison);
+        self.input_mode = InputMode::CompareView;
+        Ok(())
+    }
+
+    /// Enter in the compare view: every file's diff, starting at the selected one
+    fn open_comparison_file_diff(&mut self) {
+        let Some(comparison) = &self.comparison else {
+            return;
+        };
+        let Some(file) = self
+            .comparison_state
+            .selected()
+            .and_then(|i| comparison.files.get(i))
+        else {
+            return;
+        };
+        let range = format!("{}..{}", comparison.from, comparison.to);
+        let output = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["diff", "--no-color", "-M", &range])
+            .output();
+        let files = match output {
+            Ok(o) => diff_viewer::parse_diff_files(&String::from_utf8_lossy(&o.stdout)),
+            Err(e) => {
+                self.message = Some((format!("Failed to run git diff: {}", e), true));
+                return;
+            }
+        };
+        let Some(index) = files.iter().position(|f| f.path == file.path) else {
+            self.message = Some((format!("No text diff for {}", file.path), false));
+            return;
+        };
+        let mut pager = DiffPager::new(comparison.range(), files);
+        pager.file = index;
+        self.commit_file_diff = Some(pager);
+        self.input_mode = InputMode::CommitFileDiff;
+    }
+
+    /// `y` in the compare view: the `git diff` command for the same range
+    fn copy_comparison_command(&mut self) -> Result<()> {
+        let Some(comparison) = &self.comparison else {
+            return Ok(());
+        };
+        let command = format!("git diff {}", comparison.range());
+        if let Err(e) = copy_to_clipboard(&command, self.clipboard_backend) {
+            self.message = Some((format!("Copy failed: {}", e), true));
+        } else {
+            self.message = Some((format!("Copied: {}", command), false));
+        }
+        Ok(())
+    }
+
+    // === Reflog ===
+
+    /// `L` in the Log tab: where HEAD has been, to get back to a state from
+    /// before a rebase, reset or amend went wrong
+    fn open_reflog(&mut self) -> Result<()> {
+        let reflog = self.repo.reflog("HEAD")?;
+        self.reflog = reflog
+            .iter()
+            .enumerate()
+            .map(|(n, entry)| ReflogEntry {
+                selector: format!("HEAD@{{{}}}", n),
+                id: entry.id_new(),
+                message: entry.message().unwrap_or("").to_string(),
+                time: format_relative_time(entry.committer().when().seconds()),
+            })
+            .collect();
+        if self.reflog.is_empty() {
+            self.message = Some(("The reflog is empty".to_string(), true));
+            return Ok(());
+        }
+        self.reflog_state.select(Some(0));
+        self.input_mode = InputMode::ReflogView;
+        Ok(())
+    }
+
+    fn selected_reflog_entry(&self) -> Option<(String, String)> {
+        let entry = self.reflog.get(self.reflog_state.selected()?)?;
+        Some((
+            entry.id.to_string(),
+            format!("{} {:.7} {}", entry.selector, entry.id, entry.message),
+        ))
+    }
+
+    /// `c` in the reflog view: detach HEAD at the entry, leaving branches alone
+    fn checkout_reflog_entry(&mut self) -> Result<()> {
+        let Some((id, label)) = self.selected_reflog_entry() else {
+            return Ok(());
+        };
+        self.input_mode = InputMode::Normal;
+        self.confirm_checkout(id, label)
+    }
+
+    /// `r` in the reflog view: ask before moving the current branch to the entry
+    fn open_reflog_reset_confirm(&mut self) {
+        if self.detached_at.is_some() {
+            self.message = Some((
+                "HEAD is detached: use c to check out the entry".to_string(),
+                true,
+            ));
+            return;
+        }
+        self.pending_reflog_reset = self.selected_reflog_entry();
+        if self.pending_reflog_reset.is_some() {
+            self.input_mode = InputMode::ReflogResetConfirm;
+        }
+    }
+
+    /// `git reset --keep`: like --hard for committed work, but refuses instead
+    /// of overwriting uncommitted changes
+    fn reflog_reset(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        self.input_mode = InputMode::Normal;
+        let Some((id, label)) = self.pending_reflog_reset.take() else {
+            return Ok(());
+        };
+        self.reflog.clear();
+        let result = run_git(
+            &self.repo_path,
+            &["reset", "--keep", &id],
+            &format!("Reset {} to {}", self.branch_name, label),
+            "Reset failed",
+        );
+        self.finish_checkout(result)
+    }
+
+    /// Enter in the incoming view: the selected file's diff from the merge base
+    fn open_incoming_file_diff(&mut self) {
+        let Some(incoming) = &self.incoming else {
+            return;
+        };
+        let Some(file) = self
+            .incoming_state
+            .selected()
+            .and_then(|i| incoming.files.get(i))
+        else {
+            return;
+        };
+        let range = format!("HEAD...{}", incoming.upstream);
+        let output = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["diff", "--no-color", "-M", &range])
+            .output();
+        let files = match output {
+            Ok(o) => diff_viewer::parse_diff_files(&String::from_utf8_lossy(&o.stdout)),
+            Err(e) => {
+                self.message = Some((format!("Failed to run git diff: {}", e), true));
+                return;
+            }
+        };
+        let Some(index) = files.iter().position(|f| f.path == file.path) else {
+            self.message = Some((format!("No text diff for {}", file.path), false));
+            return;
+        };
+        let mut pager = DiffPager::new(format!("incoming from {}", incoming.upstream), files);
+        pager.file = index;
+        self.commit_file_diff = Some(pager);
+        self.input_mode = InputMode::CommitFileDiff;
+    }
+
+    // ========================================================================
+    // Repository switcher
+    // ========================================================================
+    fn switch_repo(&mut self, path: PathBuf) -> Result<()> {
+        // Its result would land in the other repository's state
+        if self.processing.is_active() {
+            self.message = Some(("Wait for the current operation".to_string(), true));
+            return Ok(());
+        }
+        self.repo = Repository::open(&path).context("Failed to open repository")?;
+        self.repo_path = path.clone();
+        self.repo_config = RepoConfig::load(&path);
+        self.signoff = self.repo_config.commit.signoff;
+        self.input_mode = InputMode::Normal;
+        // Clear remote tags cache for new repo
+        self.remote_tags_cache.clear();
+        self.remote_tags_last_fetch = None;
+        self.log_limit = LOG_BATCH;
+        // The baseline tree lives in the old repository's object database
+        self.review = None;
+        // Marks are paths in the old work tree
+        self.marked_files.clear();
+        // ...and commits in the old history
+        self.log_mark = None;
+        self.detached_at = None;
+        self.incoming_row_selected = false;
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("repo");
+        self.message = Some((format!("Switched to: {}", name), false));
+        self.refresh()?;
+        Ok(())
+    }
+
+    /// Whether siori works on a repository other than the one at the current
+    /// directory (discovered in a parent, or switched to)
+    pub fn repo_differs_from_cwd(&self) -> bool {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+        canonical(&self.repo_path) != canonical(&cwd)
+    }
+
+    /// `^`: switch to the repository containing this one, or back down to the
+    /// nested repository `^` came up from
+    fn toggle_parent_repo(&mut self) -> Result<()> {
+        // switch_repo would refuse too, after `nested_return` had already changed
+        if self.processing.is_active() {
+            self.message = Some(("Wait for the current operation".to_string(), true));
+            return Ok(());
+        }
+        let parent = self
+            .repo_path
+            .parent()
+            .and_then(|dir| Repository::discover(dir).ok())
+            .and_then(|repo| repo.workdir().map(Path::to_path_buf));
+        if let Some(parent) = parent {
+            self.nested_return = Some(self.repo_path.clone());
+            return self.switch_repo(parent);
+        }
+        match self.nested_return.take() {
+            Some(nested) => self.switch_repo(nested),
+            None => {
+                self.message = Some((
+                    "Not inside another repository (Enter on a ⎇ directory opens a nested one)"
+                        .to_string(),
+                    false,
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    fn refresh_repo_and_worktree_list(&mut self) {
+        let base_dir = std::env::current_dir().unwrap_or_default();
+        let mut repos = detect_repos(&base_dir);
+
+        // 全リポのworktreeを収集（worktreeが2つ以上あるリポのみ）
+        let mut all_worktrees = Vec::new();
+        let mut worktree_paths: HashSet<PathBuf> = HashSet::new();
+        let mut processed_repos: HashSet<PathBuf> = HashSet::new();
+        for repo in &repos {
+            let wts = detect_worktrees(repo);
+            if wts.len() >= 2 {
+                // メインworktreeのパスで重複チェック
+                let main_path = wts[0].repo_path.clone();
+                let main_canonical =
+                    std::fs::canonicalize(&main_path).unwrap_or_else(|_| main_path.clone());
+                if !processed_repos.insert(main_canonical) {
+                    continue; // 同じリポのworktreeは既に処理済み
+                }
+                for wt in &wts {
+                    if !wt.is_main {
+                        worktree_paths.insert(
+                            std::fs::canonicalize(&wt.path).unwrap_or_else(|_| wt.path.clone()),
+                        );
+                    }
+                }
+                all_worktrees.extend(wts);
+            }
+        }
+
+        // worktreeパス（メイン以外）をreposから除外
+        repos.retain(|r| {
+            let canonical = std::fs::canonicalize(r).unwrap_or_else(|_| r.clone());
+            !worktree_paths.contains(&canonical)
+        });
+
+        self.available_repos = repos;
+        self.available_worktrees = all_worktrees;
+    }
+
+    fn open_repo_select(&mut self) {
+        self.refresh_repo_and_worktree_list();
+        let current_idx = self.selector_current_index();
+        self.repo_select_state.select(Some(current_idx));
+        self.input_mode = InputMode::RepoSelect;
+    }
+
+    /// Total items in the selector (worktrees if >=2 + repos)
+    fn selector_items_count(&self) -> usize {
+        let wt = if self.available_worktrees.len() >= 2 {
+            self.available_worktrees.len()
+        } else {
+            0
+        };
+        wt + self.available_repos.len()
+    }
+
+    /// Get the path for a selector index (worktrees first, then repos)
+    fn selector_path_at(&self, idx: usize) -> Option<PathBuf> {
+        let wt_count = if self.available_worktrees.len() >= 2 {
+            self.available_worktrees.len()
+        } else {
+            0
+        };
+        if idx < wt_count {
+            Some(self.available_worktrees[idx].path.clone())
+        } else {
+            self.available_repos.get(idx - wt_count).cloned()
+        }
+    }
+
+    /// Check if selector index points to a worktree
+    fn selector_is_worktree(&self, idx: usize) -> bool {
+        let wt_count = if self.available_worktrees.len() >= 2 {
+            self.available_worktrees.len()
+        } else {
+            0
+        };
+        idx < wt_count
+    }
+
+    /// Get worktree info at selector index (if it's a worktree)
+    fn selector_worktree_at(&self, idx: usize) -> Option<&WorktreeInfo> {
+        if self.selector_is_worktree(idx) {
+            self.available_worktrees.get(idx)
+        } else {
+            None
+        }
+    }
+
+    /// Find the selector index for the current repo_path
+    fn selector_current_index(&self) -> usize {
+        let wt_count = if self.available_worktrees.len() >= 2 {
+            self.available_worktrees.len()
+        } else {
+            0
+        };
+        // Check worktrees first
+        if wt_count > 0 {
+            if let Some(idx) = self.available_worktrees.iter().position(|w| w.is_current) {
+                return idx;
+            }
+        }
+        // Then repos
+        self.available_repos
+            .iter()
+            .position(|p| p == &self.repo_path)
+            .map(|i| i + wt_count)
+            .unwrap_or(0)
+    }
+
+    fn repo_select_next(&mut self) {
+        let len = self.selector_items_count();
+        if len > 0 {
+            let i = self.repo_select_state.selected().unwrap_or(0);
             self.repo_select_state.select(Some((i + 1) % len));
         }
     }
 
-    fn repo_select_prev(&mut self) {
-        let len = self.selector_items_count();
-        if len > 0 {
-            let i = self.repo_select_state.selected().unwrap_or(0);
-            self.repo_select_state
-                .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+    fn repo_select_prev(&mut self) {
+        let len = self.selector_items_count();
+        if len > 0 {
+            let i = self.repo_select_state.selected().unwrap_or(0);
+            self.repo_select_state
+                .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+        }
+    }
+
+    // ========================================================================
+    // Tag operations
+    // ========================================================================
+    fn open_tag_input(&mut self) {
+        let Some(idx) = self.commits_state.selected() else {
+            return;
+        };
+        let Some(commit) = self.commits.get(idx) else {
+            return;
+        };
+        // If commit has a tag, pre-fill for editing
+        if let Some(tag) = commit.tags.first() {
+            self.tag_input = tag.name.clone();
+            self.editing_tag = Some(tag.name.clone());
+        } else {
+            self.tag_input.clear();
+            self.editing_tag = None;
+        }
+        self.input_mode = InputMode::TagInput;
+    }
+
+    /// Tag format for the checked-out branch (.siori.toml)
+    pub fn tag_format(&self) -> &str {
+        self.repo_config.version.tag_format_for(&self.branch_name)
+    }
+
+    fn create_or_update_tag(&mut self) -> Result<()> {
+        let version_input = self
+            .tag_input
+            .trim()
+            .strip_prefix('v')
+            .unwrap_or(self.tag_input.trim())
+            .to_string();
+        if version_input.is_empty() {
+            self.input_mode = InputMode::Normal;
+            self.message = Some(("Version is empty".to_string(), true));
+            return Ok(());
+        }
+
+        // Validate version format
+        if !version::is_valid_version(&version_input) {
+            self.input_mode = InputMode::Normal;
+            self.message = Some(("Invalid version format (e.g., 0.1.6)".to_string(), true));
+            return Ok(());
+        }
+
+        // Generate tag name from version using the current branch's tag format
+        let tag_name = version::generate_tag_name(&version_input, self.tag_format());
+
+        // Detect version files
+        let files = version::detect_version_files(&self.repo_path, &self.repo_config);
+
+        // Check if any file needs update
+        let needs_update = files.iter().any(|f| f.current_version != version_input);
+
+        if needs_update && !files.is_empty() {
+            let fragments = if self.repo_config.changelog.enabled {
+                changelog::collect_fragments(&self.repo_path, &self.repo_config.changelog)
+            } else {
+                Vec::new()
+            };
+            // Store pending update and show confirmation
+            self.pending_version_update = Some(PendingVersionUpdate {
+                tag_name,
+                new_version: version_input,
+                files,
+                commit_id: "HEAD".to_string(),
+                fragments,
+                push: self.repo_config.version.push,
+            });
+
+            if self.repo_config.version.confirm {
+                self.input_mode = InputMode::VersionConfirm;
+                return Ok(());
+            } else {
+                // Auto-confirm: check for uncommitted changes
+                return self.check_uncommitted_and_update_version();
+            }
+        }
+
+        // No version update needed, create tag directly
+        self.finish_tag_creation(&tag_name, "HEAD")
+    }
+
+    fn check_uncommitted_and_update_version(&mut self) -> Result<()> {
+        // Check for uncommitted changes
+        let has_uncommitted = !self.files.is_empty();
+        if has_uncommitted {
+            self.input_mode = InputMode::UncommittedWarning;
+            return Ok(());
+        }
+        self.do_version_update_and_tag()
+    }
+
+    fn do_version_update_and_tag(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            self.pending_version_update = None;
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        }
+        let Some(pending) = self.pending_version_update.take() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+
+        // Update version files
+        for file in &pending.files {
+            let file_path = self.repo_path.join(&file.path);
+            if let Ok(content) = std::fs::read_to_string(&file_path) {
+                let updated =
+                    version::update_version_content(&content, &file.pattern, &pending.new_version);
+                if let Err(e) = std::fs::write(&file_path, updated) {
+                    self.message = Some((format!("Failed to update {}: {e}", file.path), true));
+                    self.input_mode = InputMode::Normal;
+                    return Ok(());
+                }
+            }
+        }
+
+        // Regenerate derived files so they land in the same commit
+        for file in &pending.files {
+            if let Some(command) = &file.post_update
+                && let Err(e) =
+                    version::run_post_update(&self.repo_path, command, &pending.new_version)
+            {
+                self.message = Some((e, true));
+                self.input_mode = InputMode::Normal;
+                return Ok(());
+            }
+        }
+
+        // Assemble changelog fragments and delete them in the same commit
+        let mut file_paths: Vec<&str> = pending
+            .files
+            .iter()
+            .flat_map(|f| {
+                std::iter::once(f.path.as_str()).chain(f.outputs.iter().map(String::as_str))
+            })
+            .collect();
+        if !pending.fragments.is_empty() {
+            let config = &self.repo_config.changelog;
+            let changelog_path = self.repo_path.join(&config.file);
+            let existing = std::fs::read_to_string(&changelog_path).unwrap_or_default();
+            let release = changelog::render_release(
+                &config.heading,
+                &pending.new_version,
+                &date::today(),
+                &pending.fragments,
+            );
+            if let Err(e) = std::fs::write(
+                &changelog_path,
+                changelog::insert_release(&existing, &release),
+            ) {
+                self.message = Some((format!("Failed to update {}: {e}", config.file), true));
+                self.input_mode = InputMode::Normal;
+                return Ok(());
+            }
+            file_paths.push(&config.file);
+        }
+
+        // Stage version changes; fragments stay on disk until this worked
+        let mut add = vec!["add", "-A", "--"];
+        add.extend(file_paths.iter().copied());
+        if let Err(text) = run_git(&self.repo_path, &add, "", "Staging version files failed") {
+            self.show_failure_output("Version commit", text);
+            return Ok(());
+        }
+        // A fragment git doesn't know (hand-written, or unstaged) is just deleted;
+        // naming it in `git commit --` would fail the pathspec
+        let index = self.repo.index()?;
+        for fragment in &pending.fragments {
+            let _ = std::fs::remove_file(self.repo_path.join(&fragment.path));
+            if index.get_path(Path::new(&fragment.path), 0).is_some() {
+                file_paths.push(&fragment.path);
+            }
+        }
+
+        let commit_msg = self
+            .repo_config
+            .version
+            .commit_message
+            .replace("{version}", &pending.new_version);
+        let previous = self.repo.head().ok().and_then(|h| h.target());
+        // Commit only the bump paths, leaving any other staged work alone.
+        // Same `git commit` as regular commits, so hooks run here too.
+        let mut args = vec!["commit", "-m", commit_msg.as_str()];
+        if self.signoff {
+            args.push("--signoff");
+        }
+        args.push("--");
+        args.extend(file_paths.iter().copied());
+        if let Err(text) = run_git(
+            &self.repo_path,
+            &args,
+            "Version commit created",
+            "Version commit failed",
+        ) {
+            self.show_failure_output("Version commit", text);
+            return Ok(());
+        }
+
+        // Refresh to get new commit
+        self.refresh()?;
+        self.release_rollback = previous
+            .zip(self.repo.head().ok().and_then(|h| h.target()))
+            .map(|(previous, bump_commit)| ReleaseRollback {
+                tag_name: pending.tag_name.clone(),
+                previous,
+                bump_commit,
+            });
+
+        // Create tag on the new version commit (HEAD)
+        self.finish_tag_creation(&pending.tag_name, "HEAD")?;
+
+        let tagged = self
+            .repo
+            .find_reference(&format!("refs/tags/{}", pending.tag_name))
+            .is_ok();
+        if !tagged && self.release_rollback.is_some() {
+            // The bump commit exists without its tag; offer to undo it
+            let text = self
+                .message
+                .as_ref()
+                .map(|(m, _)| m.clone())
+                .unwrap_or_else(|| format!("Failed to create tag {}", pending.tag_name));
+            self.last_output = Some(OperationOutput {
+                title: Processing::Releasing.name(),
+                text,
+                is_error: true,
+            });
+            self.recovery_actions = vec![RecoveryAction::RollbackRelease];
+            self.input_mode = InputMode::RecoveryPrompt;
+        } else if pending.push {
+            self.push_release(pending.tag_name);
+        } else {
+            self.release_rollback = None;
+        }
+        Ok(())
+    }
+
+    /// Push the bump commit, then its tag
+    fn push_release(&mut self, tag_name: String) {
+        let push = self.upstream_args(&["push"]);
+        let repo_path = self.repo_path.clone();
+        self.start_processing(Processing::Releasing, move || {
+            let push: Vec<&str> = push.iter().map(String::as_str).collect();
+            run_git(&repo_path, &push, "Pushed", "Push failed")?;
+            run_git(
+                &repo_path,
+                &["push", "origin", &tag_name],
+                &format!("Released {} (commit and tag pushed)", tag_name),
+                "Tag push failed",
+            )
+        });
+    }
+
+    fn finish_tag_creation(&mut self, tag_name: &str, commit_ref: &str) -> Result<()> {
+        let was_pushed = self
+            .commits
+            .first()
+            .and_then(|c| c.tags.first())
+            .is_some_and(|t| t.pushed);
+        let old_tag = self.editing_tag.clone();
+
+        // Delete old tag if editing
+        if let Some(ref old) = old_tag {
+            if old != tag_name {
+                self.delete_tag_by_name(old, was_pushed);
+            }
+        }
+
+        // Create new tag using git command
+        let output = std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(["tag", "-f", tag_name, commit_ref])
+            .output();
+
+        if let Err(e) = output {
+            self.message = Some((format!("Failed to create tag: {e}"), true));
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        }
+
+        // If old tag was pushed, push new tag too
+        if was_pushed {
+            let push_output = std::process::Command::new("git")
+                .current_dir(&self.repo_path)
+                .args(["push", "origin", tag_name])
+                .output();
+            if let Ok(out) = push_output {
+                if !out.status.success() {
+                    let err = String::from_utf8_lossy(&out.stderr);
+                    self.message = Some((format!("Tag created, push failed: {err}"), true));
+                    self.input_mode = InputMode::Normal;
+                    self.refresh_log()?;
+                    return Ok(());
+                }
+            }
+            self.message = Some((format!("Tag updated: {} (pushed)", tag_name), false));
+        } else {
+            self.message = Some((format!("Created tag: {}", tag_name), false));
+        }
+
+        self.tag_input.clear();
+        self.editing_tag = None;
+        self.input_mode = InputMode::Normal;
+        self.refresh_log()?;
+        Ok(())
+    }
+
+    // === Discard Changes ===
+
+    fn open_discard_confirm(&mut self) {
+        let pending = match self.pending_discard_for_selected_file() {
+            Ok(pending) => pending,
+            Err(message) => {
+                self.message = Some((message, true));
+                return;
+            }
+        };
+        self.pending_discard = Some(PendingDiscardTarget::Single(pending));
+        self.input_mode = InputMode::DiscardConfirm;
+    }
+
+    fn open_delete_untracked_confirm(&mut self) {
+        let pending = match self.pending_discard_for_selected_file() {
+            Ok(pending) if pending.action == PendingDiscardAction::TrashUntracked => pending,
+            Ok(_) => {
+                self.message = Some(("Only untracked files can be deleted".to_string(), true));
+                return;
+            }
+            Err(message) => {
+                self.message = Some((message, true));
+                return;
+            }
+        };
+        self.pending_discard = Some(PendingDiscardTarget::Single(PendingDiscard {
+            action: PendingDiscardAction::DeleteUntracked,
+            ..pending
+        }));
+        self.input_mode = InputMode::DiscardConfirm;
+    }
+
+    fn discard_changes(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(PendingDiscardTarget::Single(pending)) = self.pending_discard.take() else {
+            return Ok(());
+        };
+        match execute_pending_discard_with(
+            &self.repo_path,
+            &pending,
+            run_restore_command,
+            move_to_trash,
+            run_clean_command,
+        ) {
+            Ok(message) => self.message = Some((message, false)),
+            Err(message) => self.message = Some((message, true)),
+        }
+        self.input_mode = InputMode::Normal;
+        self.refresh()?;
+        Ok(())
+    }
+
+    fn open_discard_marked_confirm(&mut self) {
+        let targets: Vec<PendingDiscard> = self
+            .files
+            .iter()
+            .filter(|f| self.is_marked(f))
+            .filter_map(|f| PendingDiscard::for_file(f).ok())
+            .collect();
+        if targets.is_empty() {
+            self.message = Some(("No marked unstaged changes to discard".to_string(), true));
+            return;
+        }
+        self.pending_discard = Some(PendingDiscardTarget::All(targets));
+        self.input_mode = InputMode::DiscardConfirm;
+    }
+
+    fn open_discard_all_confirm(&mut self) {
+        let targets: Vec<PendingDiscard> = self
+            .files
+            .iter()
+            .filter_map(|f| PendingDiscard::for_file(f).ok())
+            .collect();
+        if targets.is_empty() {
+            self.message = Some(("No unstaged changes to discard".to_string(), true));
+            return;
+        }
+        self.pending_discard = Some(PendingDiscardTarget::All(targets));
+        self.input_mode = InputMode::DiscardConfirm;
+    }
+
+    fn discard_all_changes(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(PendingDiscardTarget::All(targets)) = self.pending_discard.take() else {
+            return Ok(());
+        };
+
+        // Batch restore tracked files in a single git command
+        let restore_paths: Vec<&str> = targets
+            .iter()
+            .filter(|p| p.action == PendingDiscardAction::RestoreTracked)
+            .map(|p| p.path.as_str())
+            .collect();
+
+        let mut success = 0usize;
+        let mut failure = 0usize;
+
+        if !restore_paths.is_empty() {
+            let mut args = vec!["restore", "--"];
+            args.extend(&restore_paths);
+            let output = std::process::Command::new("git")
+                .current_dir(&self.repo_path)
+                .args(&args)
+                .output();
+            match output {
+                Ok(out) if out.status.success() => success += restore_paths.len(),
+                _ => failure += restore_paths.len(),
+            }
+        }
+
+        // Trash untracked files individually
+        for pending in targets
+            .iter()
+            .filter(|p| p.action == PendingDiscardAction::TrashUntracked)
+        {
+            match move_to_trash(&self.repo_path, &pending.path) {
+                Ok(_) => success += 1,
+                Err(_) => failure += 1,
+            }
+        }
+
+        let msg = if failure == 0 {
+            format!("Discarded all {} files", success)
+        } else {
+            format!("Discarded {} files ({} failed)", success, failure)
+        };
+        self.message = Some((msg, failure > 0));
+        self.input_mode = InputMode::Normal;
+        self.marked_files.clear();
+        self.refresh()?;
+        Ok(())
+    }
+
+    // === Marks ===
+
+    pub fn is_marked(&self, file: &FileEntry) -> bool {
+        self.marked_files
+            .contains(&(file.path.clone(), file.staged))
+    }
+
+    /// Toggle the mark on the selected file and move to the next one
+    fn toggle_mark(&mut self) {
+        let Some(file) = self.selected_file() else {
+            return;
+        };
+        let key = (file.path.clone(), file.staged);
+        if !self.marked_files.remove(&key) {
+            self.marked_files.insert(key);
+        }
+        self.select_next();
+    }
+
+    /// Stage marked unstaged files and unstage marked staged files in one go
+    fn stage_marked(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let (to_stage, to_unstage) = split_marked_for_staging(&self.files, &self.marked_files);
+        let mut errors = Vec::new();
+        if !to_stage.is_empty() {
+            let mut args = vec!["add", "-A", "--"];
+            args.extend(to_stage.iter().map(String::as_str));
+            if let Err(e) = run_git(&self.repo_path, &args, "", "Stage failed") {
+                errors.push(summary_line(&e).to_string());
+            }
+        }
+        if !to_unstage.is_empty() {
+            // Unborn HEAD: nothing to reset to, drop the entries from the index instead
+            let mut args = if self.repo.head().is_ok() {
+                vec!["reset", "-q", "--"]
+            } else {
+                vec!["rm", "--cached", "-r", "-q", "--"]
+            };
+            args.extend(to_unstage.iter().map(String::as_str));
+            if let Err(e) = run_git(&self.repo_path, &args, "", "Unstage failed") {
+                errors.push(summary_line(&e).to_string());
+            }
+        }
+        self.message = Some(if errors.is_empty() {
+            (
+                format!(
+                    "Staged {}, unstaged {} marked files",
+                    to_stage.len(),
+                    to_unstage.len()
+                ),
+                false,
+            )
+        } else {
+            (errors.join(" · "), true)
+        });
+        self.marked_files.clear();
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    // === Hunk View ===
+
+    fn open_hunk_view(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.message = Some(("No file selected".to_string(), true));
+            return;
+        };
+        if file.status == FileStatus::Untracked {
+            self.message = Some(("Untracked file has no diff".to_string(), true));
+            return;
+        }
+        let path = file.path.clone();
+        let staged = file.staged;
+        let issues = file.text_issues.clone();
+        let mode_change = file.mode_change;
+        let binary_size = file.binary_size;
+        let symlink_target = file.symlink_target.clone();
+        // Like git, files marked -diff or with a textconv/binary diff driver start collapsed
+        let diff_attribute = self.diff_attribute(&path);
+        let collapsed = diff_attribute.is_some();
+        let hunks = if collapsed {
+            Vec::new()
+        } else {
+            self.load_file_hunks(&path, staged)
+        };
+        // Mode changes and binary files have no hunks but are still worth showing
+        if hunks.is_empty() && mode_change.is_none() && binary_size.is_none() && !collapsed {
+            self.message = Some(("No hunks to show".to_string(), true));
+            return;
+        }
+        self.diff_view = Some(DiffView {
+            path,
+            staged,
+            hunks,
+            selected: 0,
+            issues,
+            mode_change,
+            binary_size,
+            symlink_target,
+            diff_attribute,
+            collapsed,
+            line_selection: None,
+        });
+        self.input_mode = InputMode::HunkView;
+    }
+
+    // === Quick Look ===
+
+    fn open_quick_look(&mut self) {
+        if self.selected_file().is_none() {
+            self.message = Some(("No file selected".to_string(), true));
+            return;
+        }
+        self.load_quick_look();
+        self.input_mode = InputMode::QuickLook;
+    }
+
+    /// Fill the quick look for the file under the cursor (follows j/k)
+    fn load_quick_look(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.quick_look = None;
+            return;
+        };
+        let path = file.path.clone();
+        let staged = file.staged;
+        let mut lines = Vec::new();
+        let mut changed = 0;
+        if file.status == FileStatus::Untracked {
+            // No diff yet: the whole file is the change
+            let content = std::fs::read(self.repo_path.join(&path)).unwrap_or_default();
+            if !content.contains(&0) {
+                for line in String::from_utf8_lossy(&content).lines() {
+                    if changed < QUICK_LOOK_LINES {
+                        lines.push(format!("+{}", line));
+                    }
+                    changed += 1;
+                }
+            }
+        } else if self.diff_attribute(&path).is_none() {
+            for hunk in self.load_file_hunks(&path, staged) {
+                let mut header_shown = false;
+                for line in hunk
+                    .lines
+                    .iter()
+                    .filter(|l| l.starts_with('+') || l.starts_with('-'))
+                {
+                    if changed < QUICK_LOOK_LINES {
+                        if !header_shown {
+                            lines.push(hunk.header.clone());
+                            header_shown = true;
+                        }
+                        lines.push(line.clone());
+                    }
+                    changed += 1;
+                }
+            }
+        }
+        self.quick_look = Some(QuickLook {
+            path,
+            staged,
+            lines,
+            changed,
+        });
+    }
+
+    // === Review Session ===
+
+    /// `B`: start a review from the current worktree, or reopen the running one
+    fn open_review(&mut self) -> Result<()> {
+        if self.review.is_none() {
+            if !self.ensure_writable() {
+                return Ok(());
+            }
+            match self.review_baseline() {
+                Ok(session) => {
+                    self.review = Some(session);
+                    self.message = Some((
+                        "Review started: changes from now on are listed here".to_string(),
+                        false,
+                    ));
+                }
+                Err(e) => {
+                    self.message = Some((format!("Cannot start review: {}", e), true));
+                    return Ok(());
+                }
+            }
+        }
+        self.refresh_review();
+        self.input_mode = InputMode::Review;
+        Ok(())
+    }
+
+    /// Record the worktree: the index as a tree, updated with every file that differs from it
+    fn review_baseline(&self) -> Result<ReviewSession> {
+        let mut index = self.repo.index()?;
+        if index.has_conflicts() {
+            anyhow::bail!("resolve the conflicts first");
+        }
+        let index_tree = self.repo.find_tree(index.write_tree()?)?;
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .exclude_submodules(true);
+        let mut update = git2::build::TreeUpdateBuilder::new();
+        for entry in self.repo.statuses(Some(&mut opts))?.iter() {
+            let Some(path) = entry.path() else {
+                continue;
+            };
+            self.record_worktree_file(&mut update, path)?;
+        }
+        let baseline = update.create_updated(&self.repo, &index_tree)?;
+        let started = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Ok(ReviewSession {
+            started,
+            baseline,
+            approved: HashMap::new(),
+            rejected: Vec::new(),
+            files: Vec::new(),
+            selected: 0,
+            lines: Vec::new(),
+            scroll: 0,
+        })
+    }
+
+    /// Put the worktree file (with its mode; symlinks as links) into a tree update,
+    /// or remove the entry when the file is missing
+    fn record_worktree_file(
+        &self,
+        update: &mut git2::build::TreeUpdateBuilder,
+        path: &str,
+    ) -> Result<()> {
+        let full = self.repo_path.join(path);
+        let Ok(meta) = full.symlink_metadata() else {
+            update.remove(path);
+            return Ok(());
+        };
+        if meta.is_dir() {
+            return Ok(());
+        }
+        let mode = if meta.file_type().is_symlink() {
+            git2::FileMode::Link
+        } else if is_executable(&meta) {
+            git2::FileMode::BlobExecutable
+        } else {
+            git2::FileMode::Blob
+        };
+        let id = self.repo.blob_path(&full)?;
+        update.upsert(path, id, mode);
+        Ok(())
+    }
+
+    /// The worktree against the baseline; `path` narrows it to one file
+    fn review_diff(&self, baseline: git2::Oid, path: Option<&str>) -> Option<git2::Diff<'_>> {
+        let tree = self.repo.find_tree(baseline).ok()?;
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .ignore_submodules(true)
+            .disable_pathspec_match(true);
+        let candidates: Vec<String> = match path {
+            Some(path) => {
+                opts.show_untracked_content(true);
+                vec![path.to_string()]
+            }
+            // Going through the index skips files whose stat info is unchanged, but it
+            // doesn't hash the rest, so a file put back still shows; that only narrows it down
+            None => self
+                .repo
+                .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut opts))
+                .ok()?
+                .deltas()
+                .filter_map(|d| {
+                    let file = d.new_file().path().or_else(|| d.old_file().path())?;
+                    Some(file.to_str()?.to_string())
+                })
+                .collect(),
+        };
+        if candidates.is_empty() {
+            return self
+                .repo
+                .diff_tree_to_tree(Some(&tree), Some(&tree), None)
+                .ok();
+        }
+        for path in &candidates {
+            opts.pathspec(path);
+        }
+        // Compares content through the checkout filters, without writing blobs
+        self.repo
+            .diff_tree_to_workdir(Some(&tree), Some(&mut opts))
+            .ok()
+    }
+
+    /// Files whose content differs from the session start; the cursor stays on its path.
+    /// Nothing is written to the object database here.
+    fn refresh_review(&mut self) {
+        let Some(session) = &self.review else {
+            return;
+        };
+        let mut files = Vec::new();
+        if let Some(diff) = self.review_diff(session.baseline, None) {
+            for delta in diff.deltas() {
+                let status = match delta.status() {
+                    git2::Delta::Added | git2::Delta::Untracked => FileStatus::Added,
+                    git2::Delta::Deleted => FileStatus::Deleted,
+                    git2::Delta::Unmodified | git2::Delta::Ignored => continue,
+                    _ => FileStatus::Modified,
+                };
+                let Some(path) = delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .and_then(|p| p.to_str())
+                else {
+                    continue;
+                };
+                let current = worktree_hash(&self.repo_path.join(path));
+                files.push(ReviewFile {
+                    path: path.to_string(),
+                    status,
+                    approved: session.approved.get(path) == Some(&current),
+                    current,
+                });
+            }
+        }
+        let Some(session) = &mut self.review else {
+            return;
+        };
+        let previous = session.files.get(session.selected).map(|f| f.path.clone());
+        session.selected = previous
+            .and_then(|path| files.iter().position(|f| f.path == path))
+            .unwrap_or(session.selected)
+            .min(files.len().saturating_sub(1));
+        session.files = files;
+        self.load_review_diff();
+    }
+
+    /// Diff of the selected file from its content at the start to now
+    fn load_review_diff(&mut self) {
+        let Some(session) = &self.review else {
+            return;
+        };
+        let mut lines = Vec::new();
+        if let Some(file) = session.files.get(session.selected)
+            && let Some(diff) = self.review_diff(session.baseline, Some(&file.path))
+            && let Ok(Some(patch)) = git2::Patch::from_diff(&diff, 0)
+            && !patch.delta().flags().is_binary()
+        {
+            for h in 0..patch.num_hunks() {
+                let Ok((hunk, count)) = patch.hunk(h) else {
+                    continue;
+                };
+                lines.push(
+                    String::from_utf8_lossy(hunk.header())
+                        .trim_end()
+                        .to_string(),
+                );
+                for l in 0..count {
+                    let Ok(line) = patch.line_in_hunk(h, l) else {
+                        continue;
+                    };
+                    if matches!(line.origin(), '+' | '-' | ' ') {
+                        let content = String::from_utf8_lossy(line.content());
+                        lines.push(format!(
+                            "{}{}",
+                            line.origin(),
+                            content.trim_end_matches('\n')
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(session) = &mut self.review {
+            session.lines = lines;
+            session.scroll = 0;
+        }
+    }
+
+    fn select_review_file(&mut self, delta: isize) {
+        let Some(session) = &mut self.review else {
+            return;
+        };
+        let max = session.files.len().saturating_sub(1);
+        session.selected = session.selected.saturating_add_signed(delta).min(max);
+        self.load_review_diff();
+    }
+
+    /// After a decision: the next pending file from the cursor on, wrapping around
+    fn next_pending_review_file(&mut self) {
+        let Some(session) = &mut self.review else {
+            return;
+        };
+        let len = session.files.len();
+        if let Some(index) = (0..len)
+            .map(|i| (session.selected + i) % len)
+            .find(|&i| !session.files[i].approved)
+        {
+            session.selected = index;
+        }
+        self.load_review_diff();
+    }
+
+    fn scroll_review(&mut self, delta: isize) {
+        if let Some(session) = &mut self.review {
+            let max = session.lines.len().saturating_sub(1);
+            session.scroll = session.scroll.saturating_add_signed(delta).min(max);
+        }
+    }
+
+    fn selected_review_file(&self) -> Option<ReviewFile> {
+        let session = self.review.as_ref()?;
+        session.files.get(session.selected).cloned()
+    }
+
+    /// Approve: stage the file as it is now
+    fn approve_review_file(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(file) = self.selected_review_file() else {
+            return Ok(());
+        };
+        // An untracked file that is gone again has nothing to stage
+        let untracked_gone = file.current.is_none()
+            && self
+                .repo
+                .index()?
+                .get_path(Path::new(&file.path), 0)
+                .is_none();
+        let result = if untracked_gone {
+            Ok(String::new())
+        } else {
+            run_git(
+                &self.repo_path,
+                &["add", "-A", "--", &file.path],
+                "",
+                "Stage failed",
+            )
+        };
+        match result {
+            Ok(_) => {
+                if let Some(session) = &mut self.review {
+                    session.approved.insert(file.path.clone(), file.current);
+                }
+                let action = if untracked_gone {
+                    "Approved"
+                } else {
+                    "Approved and staged"
+                };
+                self.message = Some((format!("{}: {}", action, file.path), false));
+            }
+            Err(e) => self.message = Some((summary_line(&e).to_string(), true)),
+        }
+        self.refresh()?;
+        self.next_pending_review_file();
+        Ok(())
+    }
+
+    /// Reject: put the file back the way it was when the session started
+    fn reject_review_file(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some((file, baseline)) = self
+            .review
+            .as_ref()
+            .and_then(|s| Some((s.files.get(s.selected)?.clone(), s.baseline)))
+        else {
+            return Ok(());
+        };
+        // Keep the rejected version in a tree of its own, so `u` can check it out again
+        let saved = match file.current {
+            Some(_) => {
+                let mut update = git2::build::TreeUpdateBuilder::new();
+                self.record_worktree_file(&mut update, &file.path)
+                    .and_then(|_| {
+                        let tree = self.repo.find_tree(baseline)?;
+                        Ok(update.create_updated(&self.repo, &tree)?)
+                    })
+                    .map(Some)
+            }
+            None => Ok(None),
+        };
+        let source = (file.status != FileStatus::Added).then_some(baseline);
+        match saved.and_then(|saved| {
+            self.restore_review_path(&file.path, source)?;
+            Ok(saved)
+        }) {
+            Ok(saved) => {
+                if let Some(session) = &mut self.review {
+                    session.approved.remove(&file.path);
+                    session.rejected.push((file.path.clone(), saved));
+                }
+                self.message = Some((format!("Rejected: {} (u to undo)", file.path), false));
+            }
+            Err(e) => self.message = Some((format!("Reject failed: {}", e), true)),
+        }
+        self.refresh()?;
+        self.next_pending_review_file();
+        Ok(())
+    }
+
+    /// `u`: bring back the most recently rejected change
+    fn undo_review_reject(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some((path, saved)) = self.review.as_mut().and_then(|s| s.rejected.pop()) else {
+            self.message = Some(("Nothing rejected to undo".to_string(), true));
+            return Ok(());
+        };
+        match self.restore_review_path(&path, saved) {
+            Ok(()) => self.message = Some((format!("Restored: {}", path), false)),
+            Err(e) => self.message = Some((format!("Undo failed: {}", e), true)),
+        }
+        self.refresh()?;
+        if let Some(session) = &mut self.review
+            && let Some(index) = session.files.iter().position(|f| f.path == path)
+        {
+            session.selected = index;
+        }
+        self.load_review_diff();
+        Ok(())
+    }
+
+    /// Check `path` out of `source` into the worktree only (checkout filters, mode and
+    /// symlinks as git does it); None removes the file
+    fn restore_review_path(&self, path: &str, source: Option<git2::Oid>) -> Result<()> {
+        let Some(tree) = source else {
+            let full = self.repo_path.join(path);
+            if full.symlink_metadata().is_ok() {
+                std::fs::remove_file(&full)?;
+            }
+            return Ok(());
+        };
+        run_git(
+            &self.repo_path,
+            &[
+                "restore",
+                &format!("--source={}", tree),
+                "--worktree",
+                "--",
+                path,
+            ],
+            "",
+            "git restore failed",
+        )
+        .map_err(|e| anyhow::anyhow!("{}", summary_line(&e)))?;
+        Ok(())
+    }
+
+    /// End the session; staged and rejected changes stay as they are
+    fn end_review(&mut self) {
+        if let Some(session) = self.review.take() {
+            let approved = session.files.iter().filter(|f| f.approved).count();
+            self.message = Some((
+                format!(
+                    "Review ended: {} approved, {} left pending",
+                    approved,
+                    session.pending()
+                ),
+                false,
+            ));
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// `-diff` / `diff=<driver>` set for the path in .gitattributes, when git wouldn't
+    /// show a plain text diff for it: built-in drivers like `diff=rust` only change hunk
+    /// headers, but a driver with `textconv` or `binary = true` in the config does
+    fn diff_attribute(&self, path: &str) -> Option<String> {
+        let value = self
+            .repo
+            .get_attr(
+                Path::new(path),
+                "diff",
+                git2::AttrCheckFlags::FILE_THEN_INDEX,
+            )
+            .ok()?;
+        match git2::AttrValue::from_string(value) {
+            git2::AttrValue::False => Some("-diff".to_string()),
+            git2::AttrValue::String(driver) => {
+                let config = self.repo.config().ok()?;
+                let textconv = config
+                    .get_string(&format!("diff.{}.textconv", driver))
+                    .is_ok();
+                let binary = config
+                    .get_bool(&format!("diff.{}.binary", driver))
+                    .unwrap_or(false);
+                (textconv || binary).then(|| format!("diff={}", driver))
+            }
+            _ => None,
+        }
+    }
+
+    /// "Show anyway" for a collapsed diff: load the hunks as plain text
+    fn expand_hunk_view(&mut self) {
+        let Some(view) = &self.diff_view else {
+            return;
+        };
+        if !view.collapsed {
+            return;
+        }
+        let hunks = self.load_file_hunks_as(&view.path, view.staged, true);
+        if let Some(view) = &mut self.diff_view {
+            if hunks.is_empty() {
+                self.message = Some(("No text changes to show".to_string(), true));
+            }
+            view.hunks = hunks;
+            view.selected = 0;
+            view.collapsed = false;
+            view.line_selection = None;
+        }
+    }
+
+    /// Build the file's diff with git2 and split it into hunks
+    fn load_file_hunks(&self, path: &str, staged: bool) -> Vec<DiffHunk> {
+        self.load_file_hunks_as(path, staged, false)
+    }
+
+    /// `force_text` diffs files that git would treat as binary (e.g. `-diff`)
+    fn load_file_hunks_as(&self, path: &str, staged: bool, force_text: bool) -> Vec<DiffHunk> {
+        let mut opts = DiffOptions::new();
+        opts.pathspec(path).force_text(force_text);
+        let diff = if staged {
+            let head = self.repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            self.repo
+                .diff_tree_to_index(head.as_ref(), None, Some(&mut opts))
+        } else {
+            self.repo.diff_index_to_workdir(None, Some(&mut opts))
+        };
+        let Ok(diff) = diff else {
+            return Vec::new();
+        };
+        let mut text = String::new();
+        let _ = diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                text.push(line.origin());
+            }
+            text.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        });
+        diff_viewer::parse_diff(&text)
+    }
+
+    fn hunk_view_next(&mut self) {
+        if let Some(view) = &mut self.diff_view
+            && !view.hunks.is_empty()
+        {
+            view.selected = (view.selected + 1) % view.hunks.len();
+        }
+    }
+
+    fn hunk_view_prev(&mut self) {
+        if let Some(view) = &mut self.diff_view
+            && !view.hunks.is_empty()
+        {
+            let len = view.hunks.len();
+            view.selected = if view.selected == 0 {
+                len - 1
+            } else {
+                view.selected - 1
+            };
+        }
+    }
+
+    fn close_hunk_view(&mut self) {
+        self.diff_view = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn open_discard_hunk_confirm(&mut self) {
+        let Some(view) = &self.diff_view else {
+            return;
+        };
+        if view.staged {
+            self.message = Some(("Unstage file first (Space)".to_string(), true));
+            return;
+        }
+        let Some(hunk) = view.hunks.get(view.selected) else {
+            return;
+        };
+        self.pending_discard = Some(PendingDiscardTarget::Hunk(PendingHunkDiscard {
+            path: view.path.clone(),
+            index: view.selected,
+            header: hunk.header.clone(),
+            force_text: view.force_text(),
+        }));
+        self.input_mode = InputMode::DiscardConfirm;
+    }
+
+    fn discard_hunk(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(PendingDiscardTarget::Hunk(pending)) = self.pending_discard.take() else {
+            return Ok(());
+        };
+        match discard_hunk_in_workdir(&self.repo, &pending.path, pending.index, pending.force_text)
+        {
+            Ok(()) => self.message = Some((format!("Discarded hunk: {}", pending.path), false)),
+            Err(e) => self.message = Some((e, true)),
+        }
+        self.refresh()?;
+        self.reload_hunk_view(&pending.path, false, pending.index);
+        Ok(())
+    }
+
+    /// Stage the selected unstaged hunk, or unstage the selected staged hunk
+    fn toggle_hunk_stage(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(view) = &self.diff_view else {
+            return Ok(());
+        };
+        if view.hunks.is_empty() {
+            return Ok(());
+        }
+        let (path, staged, index) = (view.path.clone(), view.staged, view.selected);
+        let force_text = view.force_text();
+        let (result, done) = if staged {
+            (
+                unstage_hunk_in_index(&self.repo, &path, index, force_text),
+                "Unstaged",
+            )
+        } else {
+            (
+                stage_hunk_in_index(&self.repo, &path, index, force_text),
+                "Staged",
+            )
+        };
+        match result {
+            Ok(()) => self.message = Some((format!("{} hunk: {}", done, path), false)),
+            Err(e) => self.message = Some((e, true)),
+        }
+        self.refresh_status()?;
+        self.reload_hunk_view(&path, staged, index);
+        Ok(())
+    }
+
+    /// Enter line mode at the selected hunk's first change, or leave it
+    fn toggle_line_selection(&mut self) {
+        let Some(view) = &mut self.diff_view else {
+            return;
+        };
+        if view.line_selection.take().is_some() {
+            return;
         }
+        let Some(hunk) = view.hunks.get(view.selected) else {
+            return;
+        };
+        let first = hunk
+            .lines
+            .iter()
+            .position(|line| line.starts_with(['+', '-']))
+            .unwrap_or(0);
+        view.line_selection = Some((first, first));
     }
 
-    // ========================================================================
-    // Tag operations
-    // ========================================================================
-    fn open_tag_input(&mut self) {
-        let Some(idx) = self.commits_state.selected() else {
+    /// Move the line-mode cursor; the anchor stays where V was pressed
+    fn move_line_cursor(&mut self, down: bool) {
+        let Some(view) = &mut self.diff_view else {
             return;
         };
-        let Some(commit) = self.commits.get(idx) else {
+        let len = view.hunks.get(view.selected).map_or(0, |h| h.lines.len());
+        if let Some((_, cursor)) = &mut view.line_selection {
+            *cursor = if down {
+                (*cursor + 1).min(len.saturating_sub(1))
+            } else {
+                cursor.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Stage (or unstage, in a staged view) just the lines selected in line mode
+    fn stage_selected_lines(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(view) = &self.diff_view else {
+            return Ok(());
+        };
+        let (Some((anchor, cursor)), Some(hunk)) =
+            (view.line_selection, view.hunks.get(view.selected))
+        else {
+            return Ok(());
+        };
+        let (path, staged, index) = (view.path.clone(), view.staged, view.selected);
+        let Some(patch) =
+            diff_viewer::line_patch(&path, hunk, anchor.min(cursor), anchor.max(cursor), staged)
+        else {
+            self.message = Some(("No changed lines selected".to_string(), true));
+            return Ok(());
+        };
+        let done = if staged { "Unstaged" } else { "Staged" };
+        let lines = anchor.abs_diff(cursor) + 1;
+        let result = git2::Diff::from_buffer(patch.as_bytes())
+            .and_then(|diff| self.repo.apply(&diff, git2::ApplyLocation::Index, None))
+            .map_err(|e| format!("{} lines failed: {}", done, e.message()));
+        match result {
+            Ok(()) => self.message = Some((format!("{} {} line(s): {}", done, lines, path), false)),
+            Err(e) => self.message = Some((e, true)),
+        }
+        self.refresh_status()?;
+        self.reload_hunk_view(&path, staged, index);
+        Ok(())
+    }
+
+    /// Stay in the hunk view while the file still has hunks on the same side
+    fn reload_hunk_view(&mut self, path: &str, staged: bool, index: usize) {
+        let force_text = self.diff_view.as_ref().is_some_and(|v| v.force_text());
+        let hunks = self.load_file_hunks_as(path, staged, force_text);
+        if hunks.is_empty() {
+            self.close_hunk_view();
+        } else if let Some(view) = &mut self.diff_view {
+            view.selected = index.min(hunks.len() - 1);
+            view.hunks = hunks;
+            view.line_selection = None;
+            self.input_mode = InputMode::HunkView;
+        }
+    }
+
+    // === Skip-worktree ===
+
+    /// Hide the selected file's local changes with `skip-worktree`, or show them again
+    fn toggle_skip_worktree(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(file) = self.selected_file() else {
+            self.message = Some(("No file selected".to_string(), true));
+            return Ok(());
+        };
+        let path = file.path.clone();
+        let result = if let Some(flag) = file.hidden {
+            // update-index applies only one of the --no-* flags per path,
+            // so clear the one this entry actually carries
+            let unset = match flag {
+                HiddenFlag::SkipWorktree => "--no-skip-worktree",
+                HiddenFlag::AssumeUnchanged => "--no-assume-unchanged",
+            };
+            run_git(
+                &self.repo_path,
+                &["update-index", unset, "--", &path],
+                &format!("Unhidden: {}", path),
+                "Unhide failed",
+            )
+        } else if matches!(file.status, FileStatus::Modified | FileStatus::Deleted)
+            && !file.intent_to_add
+        {
+            run_git(
+                &self.repo_path,
+                &["update-index", "--skip-worktree", "--", &path],
+                &format!("Hidden (skip-worktree): {}", path),
+                "Hide failed",
+            )
+        } else {
+            self.message = Some(("Only tracked files can be hidden".to_string(), true));
+            return Ok(());
+        };
+        self.message = Some(match result {
+            Ok(msg) => (msg, false),
+            Err(e) => (e, true),
+        });
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    // === Output Viewer ===
+
+    fn open_output_view(&mut self) {
+        let Some(output) = &self.last_output else {
+            self.message = Some(("No operation output yet".to_string(), true));
             return;
         };
-        // If commit has a tag, pre-fill for editing
-        if let Some(tag) = commit.tags.first() {
-            self.tag_input = tag.name.clone();
-            self.editing_tag = Some(tag.name.clone());
+        if self.pager.is_some() {
+            self.pager_requested = Some(output.text.clone());
+            return;
+        }
+        self.output_scroll = 0;
+        self.input_mode = InputMode::OutputView;
+    }
+
+    /// Report a failed step with its full output (e.g. hook messages) open for scrolling
+    fn show_failure_output(&mut self, title: &'static str, text: String) {
+        self.message = Some((summary_line(&text).to_string(), true));
+        self.last_output = Some(OperationOutput {
+            title,
+            text,
+            is_error: true,
+        });
+        self.output_scroll = 0;
+        self.input_mode = InputMode::OutputView;
+    }
+
+    fn scroll_output(&mut self, down: bool) {
+        let lines = self
+            .last_output
+            .as_ref()
+            .map_or(0, |o| o.text.lines().count());
+        self.output_scroll = if down {
+            (self.output_scroll + 1).min(lines.saturating_sub(1))
         } else {
-            self.tag_input.clear();
-            self.editing_tag = None;
+            self.output_scroll.saturating_sub(1)
+        };
+    }
+
+    // === Intent-to-add ===
+
+    /// `git add -N` an untracked file, or drop the intent-to-add entry again
+    fn toggle_intent_to_add(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(file) = self.selected_file() else {
+            self.message = Some(("No file selected".to_string(), true));
+            return Ok(());
+        };
+        let path = file.path.clone();
+        let result = if file.intent_to_add {
+            run_git(
+                &self.repo_path,
+                &["reset", "-q", "--", &path],
+                &format!("Untracked again: {}", path),
+                "Undo intent-to-add failed",
+            )
+        } else if file.status == FileStatus::Untracked {
+            run_git(
+                &self.repo_path,
+                &["add", "--intent-to-add", "--", &path],
+                &format!("Intent to add: {}", path),
+                "Intent-to-add failed",
+            )
+        } else {
+            self.message = Some(("Only untracked files can be intent-added".to_string(), true));
+            return Ok(());
+        };
+        self.message = Some(match result {
+            Ok(msg) => (msg, false),
+            Err(e) => (e, true),
+        });
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    // === Restore deleted files ===
+
+    /// Bring back a file deleted in the working tree (`git checkout -- <path>`)
+    fn restore_deleted(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(file) = self.selected_file() else {
+            self.message = Some(("No file selected".to_string(), true));
+            return Ok(());
+        };
+        if file.staged || file.status != FileStatus::Deleted || file.hidden.is_some() {
+            self.message = Some((
+                "Only files deleted in the working tree can be restored".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+        let path = file.path.clone();
+        let result = run_git(
+            &self.repo_path,
+            &["checkout", "--", &path],
+            &format!("Restored: {}", path),
+            "Restore failed",
+        );
+        self.message = Some(match result {
+            Ok(msg) => (msg, false),
+            Err(e) => (e, true),
+        });
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    // === Changelog fragments ===
+
+    fn open_changelog_input(&mut self) {
+        if !self.repo_config.changelog.enabled {
+            self.message = Some((
+                "Changelog fragments are off (.siori.toml [changelog] enabled = true)".to_string(),
+                true,
+            ));
+            return;
+        }
+        self.changelog_input.clear();
+        self.input_mode = InputMode::ChangelogInput;
+    }
+
+    /// Write a fragment file from "type: text" and stage it with the next commit
+    fn add_changelog_fragment(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let input = std::mem::take(&mut self.changelog_input);
+        self.input_mode = InputMode::Normal;
+        let Some((kind, text)) = changelog::parse_fragment_input(&input) else {
+            self.message = Some(("Changelog entry is empty".to_string(), true));
+            return Ok(());
+        };
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let dir = self
+            .repo_config
+            .changelog
+            .dir
+            .trim_end_matches('/')
+            .to_string();
+        let path = format!("{}/{}", dir, changelog::fragment_file_name(kind, timestamp));
+        let written = std::fs::create_dir_all(self.repo_path.join(&dir))
+            .and_then(|_| std::fs::write(self.repo_path.join(&path), format!("{}\n", text)));
+        if let Err(e) = written {
+            self.message = Some((format!("Failed to write {}: {e}", path), true));
+            return Ok(());
+        }
+        let mut index = self.repo.index()?;
+        index.add_path(Path::new(&path))?;
+        index.write()?;
+        self.message = Some((format!("Added changelog fragment: {}", path), false));
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    // === .gitignore ===
+
+    fn open_gitignore_input(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.message = Some(("No file selected".to_string(), true));
+            return;
+        };
+        if file.status != FileStatus::Untracked {
+            self.message = Some(("Only untracked files can be ignored".to_string(), true));
+            return;
+        }
+        self.gitignore_input = file.path.clone();
+        self.input_mode = InputMode::GitignoreInput;
+    }
+
+    /// `n` in the Files tab: rename the selected tracked file, or move the
+    /// marked ones into a directory
+    fn open_move_input(&mut self) {
+        let candidates: Vec<&FileEntry> = if self.marked_files.is_empty() {
+            self.selected_file().into_iter().collect()
+        } else {
+            self.files
+                .iter()
+                .filter(|f| self.marked_files.contains(&(f.path.clone(), f.staged)))
+                .collect()
+        };
+        // A file with staged and unstaged changes is listed twice
+        let mut sources: Vec<String> = Vec::new();
+        for file in candidates {
+            if !matches!(file.status, FileStatus::Untracked | FileStatus::Deleted)
+                && !sources.contains(&file.path)
+            {
+                sources.push(file.path.clone());
+            }
+        }
+        if sources.is_empty() {
+            self.message = Some((
+                "Only tracked files that still exist can be moved".to_string(),
+                true,
+            ));
+            return;
+        }
+        self.move_input = match sources.as_slice() {
+            [path] => path.clone(),
+            _ => String::new(),
+        };
+        self.move_sources = sources;
+        self.input_mode = InputMode::MoveInput;
+    }
+
+    /// `git mv`, so the rename is staged as one instead of a D / ?? pair
+    fn move_files(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        self.input_mode = InputMode::Normal;
+        let sources = std::mem::take(&mut self.move_sources);
+        let target = std::mem::take(&mut self.move_input)
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        if target.is_empty() || sources.first() == Some(&target) {
+            self.message = Some(("Not moved: no new path".to_string(), true));
+            return Ok(());
+        }
+        // An existing directory takes the files in; anything else would be overwritten
+        let existing = self.repo_path.join(&target);
+        if existing.symlink_metadata().is_ok() && !existing.is_dir() {
+            self.message = Some((format!("Not moved: {} already exists", target), true));
+            return Ok(());
+        }
+        // git mv wants the destination directory to exist already
+        let directory = if sources.len() > 1 {
+            Some(self.repo_path.join(&target))
+        } else {
+            self.repo_path.join(&target).parent().map(Path::to_path_buf)
+        };
+        if let Some(directory) = directory
+            && let Err(e) = std::fs::create_dir_all(&directory)
+        {
+            self.message = Some((format!("Move failed: {}", e), true));
+            return Ok(());
+        }
+        let mut args = vec!["mv", "--"];
+        args.extend(sources.iter().map(String::as_str));
+        args.push(&target);
+        let done = match sources.as_slice() {
+            [source] => format!("Moved {} → {}", source, target),
+            _ => format!("Moved {} files to {}/", sources.len(), target),
+        };
+        let result = run_git(&self.repo_path, &args, &done, "Move failed");
+        self.message = Some(match result {
+            Ok(msg) => (summary_line(&msg).to_string(), false),
+            Err(e) => (summary_line(&e).to_string(), true),
+        });
+        self.marked_files.clear();
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    fn add_to_gitignore(&mut self) -> Result<()> {
+        let pattern = self.gitignore_input.trim().to_string();
+        self.gitignore_input.clear();
+        self.input_mode = InputMode::Normal;
+        if pattern.is_empty() {
+            self.message = Some(("Pattern is empty".to_string(), true));
+            return Ok(());
+        }
+
+        let gitignore_path = self.repo_path.join(".gitignore");
+        let content = std::fs::read_to_string(&gitignore_path).unwrap_or_default();
+        let Some(updated) = append_gitignore_entry(&content, &pattern) else {
+            self.message = Some((format!("Already in .gitignore: {}", pattern), true));
+            return Ok(());
+        };
+        match std::fs::write(&gitignore_path, updated) {
+            Ok(()) => self.message = Some((format!("Ignored: {}", pattern), false)),
+            Err(e) => self.message = Some((format!("Failed to update .gitignore: {e}"), true)),
+        }
+        self.refresh_status()?;
+        Ok(())
+    }
+
+    // === Stash ===
+
+    fn open_stash_input(&mut self) {
+        if self
+            .files
+            .iter()
+            .all(|f| f.status == FileStatus::Untracked || f.hidden.is_some())
+        {
+            self.message = Some(("No local changes to stash".to_string(), true));
+            return;
+        }
+        self.stash_message_input.clear();
+        self.input_mode = InputMode::StashInput;
+    }
+
+    fn stash_save(&mut self) -> Result<()> {
+        let message = self.stash_message_input.trim().to_string();
+        self.stash_message_input.clear();
+        self.input_mode = InputMode::Normal;
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+
+        let signature = match self.repo.signature() {
+            Ok(sig) => sig,
+            Err(e) => {
+                self.message = Some((format!("Stash failed: {}", e.message()), true));
+                return Ok(());
+            }
+        };
+        let msg = (!message.is_empty()).then_some(message.as_str());
+        match self.repo.stash_save2(&signature, msg, None) {
+            Ok(_) => self.message = Some(("Stashed changes".to_string(), false)),
+            Err(e) => self.message = Some((format!("Stash failed: {}", e.message()), true)),
+        }
+        self.refresh()?;
+        Ok(())
+    }
+
+    fn selected_stash(&self) -> Option<&StashEntry> {
+        self.stash_state
+            .selected()
+            .and_then(|i| self.stashes.get(i))
+    }
+
+    fn stash_apply(&mut self, pop: bool) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(index) = self.selected_stash().map(|s| s.index) else {
+            self.message = Some(("No stash selected".to_string(), true));
+            return Ok(());
+        };
+        let result = if pop {
+            self.repo.stash_pop(index, None)
+        } else {
+            self.repo.stash_apply(index, None)
+        };
+        let action = if pop { "Popped" } else { "Applied" };
+        match result {
+            Ok(()) => self.message = Some((format!("{action}: stash@{{{index}}}"), false)),
+            // Nothing was written, and a pop only drops the stash once it applied
+            Err(e) if e.code() == git2::ErrorCode::Conflict => {
+                self.message = Some((
+                    format!(
+                        "Conflicts with local changes: commit or stash them first (stash@{{{index}}} kept)"
+                    ),
+                    true,
+                ));
+            }
+            Err(e) => {
+                self.message = Some((format!("{action} failed: {}", e.message()), true));
+            }
+        }
+        self.refresh()?;
+        Ok(())
+    }
+
+    fn open_stash_drop_confirm(&mut self) {
+        if self.selected_stash().is_none() {
+            self.message = Some(("No stash selected".to_string(), true));
+            return;
+        }
+        self.input_mode = InputMode::StashDropConfirm;
+    }
+
+    fn stash_drop(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some(index) = self.selected_stash().map(|s| s.index) else {
+            return Ok(());
+        };
+        match self.repo.stash_drop(index) {
+            Ok(()) => self.message = Some((format!("Dropped: stash@{{{index}}}"), false)),
+            Err(e) => self.message = Some((format!("Drop failed: {}", e.message()), true)),
         }
-        self.input_mode = InputMode::TagInput;
+        self.refresh_stashes();
+        Ok(())
     }
 
-    fn create_or_update_tag(&mut self) -> Result<()> {
-        let version_input = self
-            .tag_input
-            .trim()
-            .strip_prefix('v')
-            .unwrap_or(self.tag_input.trim())
-            .to_string();
-        if version_input.is_empty() {
-            self.input_mode = InputMode::Normal;
-            self.message = Some(("Version is empty".to_string(), true));
+    // === Branches ===
+
+    fn selected_branch(&self) -> Option<&BranchEntry> {
+        self.branches_state
+            .selected()
+            .and_then(|i| self.branches.get(i))
+    }
+
+    /// Enter in the Branches tab: `git switch` to the selected branch
+    fn checkout_selected_branch(&mut self) -> Result<()> {
+        let Some(branch) = self.selected_branch() else {
+            return Ok(());
+        };
+        if branch.is_head {
+            self.message = Some((format!("Already on {}", branch.name), false));
             return Ok(());
         }
-
-        // Validate version format
-        if !version::is_valid_version(&version_input) {
-            self.input_mode = InputMode::Normal;
-            self.message = Some(("Invalid version format (e.g., 0.1.6)".to_string(), true));
+        let name = branch.name.clone();
+        if !self.ensure_writable() {
             return Ok(());
         }
+        let result = run_git(
+            &self.repo_path,
+            &["switch", &name],
+            &format!("Switched to {}", name),
+            "Checkout failed",
+        );
+        self.finish_checkout(result)
+    }
 
-        // Generate tag name from version using tag_format
-        let tag_format = &self.repo_config.version.tag_format;
-        let tag_name = version::generate_tag_name(&version_input, tag_format);
-
-        // Detect version files
-        let files = version::detect_version_files(&self.repo_path, &self.repo_config);
-
-        // Check if any file needs update
-        let needs_update = files.iter().any(|f| f.current_version != version_input);
+    /// `n` in the Branches tab: name a new branch to create at HEAD
+    fn open_branch_create_input(&mut self) {
+        self.renaming_branch = None;
+        self.branch_name_input.clear();
+        self.input_mode = InputMode::BranchNameInput;
+    }
 
-        if needs_update && !files.is_empty() {
-            // Store pending update and show confirmation
-            self.pending_version_update = Some(PendingVersionUpdate {
-                tag_name,
-                new_version: version_input,
-                files,
-                commit_id: "HEAD".to_string(),
-            });
+    /// `e` in the Branches tab: new name for the selected branch
+    fn open_branch_rename_input(&mut self) {
+        let Some(name) = self.selected_branch().map(|b| b.name.clone()) else {
+            self.message = Some(("No branch selected".to_string(), true));
+            return;
+        };
+        self.branch_name_input = name.clone();
+        self.renaming_branch = Some(name);
+        self.input_mode = InputMode::BranchNameInput;
+    }
 
-            if self.repo_config.version.confirm {
-                self.input_mode = InputMode::VersionConfirm;
-                return Ok(());
-            } else {
-                // Auto-confirm: check for uncommitted changes
-                return self.check_uncommitted_and_update_version();
-            }
+    /// Create (`git switch -c`) or rename (`git branch -m`) with the typed name
+    fn submit_branch_name(&mut self) -> Result<()> {
+        let name = self.branch_name_input.trim().to_string();
+        if name.is_empty() {
+            self.message = Some(("Branch name is empty".to_string(), true));
+            return Ok(());
         }
-
-        // No version update needed, create tag directly
-        self.finish_tag_creation(&tag_name, "HEAD")
+        self.input_mode = InputMode::Normal;
+        self.branch_name_input.clear();
+        if !self.ensure_writable() {
+            self.renaming_branch = None;
+            return Ok(());
+        }
+        let result = match self.renaming_branch.take() {
+            Some(old) if old == name => return Ok(()),
+            Some(old) => run_git(
+                &self.repo_path,
+                &["branch", "-m", &old, &name],
+                &format!("Renamed {} to {}", old, name),
+                "Rename failed",
+            ),
+            None => run_git(
+                &self.repo_path,
+                &["switch", "-c", &name],
+                &format!("Created and switched to {}", name),
+                "Create branch failed",
+            ),
+        };
+        match result {
+            Ok(msg) => self.message = Some((summary_line(&msg).to_string(), false)),
+            Err(e) => self.message = Some((summary_line(&e).to_string(), true)),
+        }
+        self.refresh()?;
+        if let Some(idx) = self.branches.iter().position(|b| b.name == name) {
+            self.branches_state.select(Some(idx));
+        }
+        Ok(())
     }
 
-    fn check_uncommitted_and_update_version(&mut self) -> Result<()> {
-        // Check for uncommitted changes
-        let has_uncommitted = !self.files.is_empty();
-        if has_uncommitted {
-            self.input_mode = InputMode::UncommittedWarning;
+    /// `x` in the Branches tab: branches merged into HEAD go right away,
+    /// unmerged ones ask first since their commits would be left behind
+    fn open_branch_delete_confirm(&mut self) -> Result<()> {
+        let Some(branch) = self.selected_branch() else {
+            self.message = Some(("No branch selected".to_string(), true));
+            return Ok(());
+        };
+        if branch.is_head {
+            self.message = Some((
+                format!(
+                    "{} is checked out; switch to another branch first",
+                    branch.name
+                ),
+                true,
+            ));
             return Ok(());
         }
-        self.do_version_update_and_tag()
+        if branch.merged {
+            return self.delete_selected_branch(false);
+        }
+        self.input_mode = InputMode::BranchDeleteConfirm;
+        Ok(())
     }
 
-    fn do_version_update_and_tag(&mut self) -> Result<()> {
-        let Some(pending) = self.pending_version_update.take() else {
-            self.input_mode = InputMode::Normal;
+    /// `force` (-D) only after the unmerged warning was confirmed
+    fn delete_selected_branch(&mut self, force: bool) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(branch) = self.selected_branch() else {
             return Ok(());
         };
-
-        // Update version files
-        for file in &pending.files {
-            let file_path = self.repo_path.join(&file.path);
-            if let Ok(content) = std::fs::read_to_string(&file_path) {
-                let updated =
-                    version::update_version_content(&content, &file.pattern, &pending.new_version);
-                if let Err(e) = std::fs::write(&file_path, updated) {
-                    self.message = Some((format!("Failed to update {}: {e}", file.path), true));
-                    self.input_mode = InputMode::Normal;
-                    return Ok(());
-                }
-            }
+        let (name, id) = (branch.name.clone(), branch.id.clone());
+        if !self.ensure_writable() {
+            return Ok(());
         }
+        let result = run_git(
+            &self.repo_path,
+            &["branch", if force { "-D" } else { "-d" }, &name],
+            &format!("Deleted branch {} (was {})", name, id),
+            "Delete branch failed",
+        );
+        let refused = result.is_err() && !force;
+        match result {
+            Ok(msg) => self.message = Some((summary_line(&msg).to_string(), false)),
+            Err(e) => self.message = Some((summary_line(&e).to_string(), true)),
+        }
+        self.refresh()?;
+        // git still sees unmerged commits: offer -D instead of stopping at its error
+        if refused && self.selected_branch().is_some_and(|b| b.name == name) {
+            self.input_mode = InputMode::BranchDeleteConfirm;
+        }
+        Ok(())
+    }
 
-        // Stage and commit version changes
-        let file_paths: Vec<&str> = pending.files.iter().map(|f| f.path.as_str()).collect();
-        let _ = std::process::Command::new("git")
-            .current_dir(&self.repo_path)
-            .args(["add"])
-            .args(&file_paths)
-            .output();
-
-        let commit_msg = self
-            .repo_config
-            .version
-            .commit_message
-            .replace("{version}", &pending.new_version);
-        let commit_result = std::process::Command::new("git")
-            .current_dir(&self.repo_path)
-            .args(["commit", "-m", &commit_msg])
-            .output();
+    // === Undo Commit ===
 
-        if let Ok(output) = commit_result {
-            if !output.status.success() {
-                let err = String::from_utf8_lossy(&output.stderr);
-                self.message = Some((format!("Version commit failed: {err}"), true));
-                self.input_mode = InputMode::Normal;
-                return Ok(());
-            }
+    /// Whether the current branch's upstream already contains HEAD
+    fn head_is_pushed(&self) -> bool {
+        let Ok(head_ref) = self.repo.head() else {
+            return false;
+        };
+        let Some(head) = head_ref.target() else {
+            return false;
+        };
+        if !head_ref.is_branch() {
+            return false;
         }
+        git2::Branch::wrap(head_ref)
+            .upstream()
+            .ok()
+            .and_then(|u| u.get().target())
+            .is_some_and(|remote| {
+                remote == head || self.repo.graph_descendant_of(remote, head).unwrap_or(false)
+            })
+    }
 
-        // Refresh to get new commit
-        self.refresh()?;
-
-        // Create tag on the new version commit (HEAD)
-        self.finish_tag_creation(&pending.tag_name, "HEAD")
+    fn open_undo_commit_confirm(&mut self) {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return;
+        };
+        if !commit.is_head {
+            self.message = Some(("Can only undo the HEAD commit".to_string(), true));
+            return;
+        }
+        let label = format!("{} {}", commit.id, commit.message);
+        let has_parent = self
+            .repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .is_ok_and(|c| c.parent_count() > 0);
+        if !has_parent {
+            self.message = Some(("Can't undo the first commit".to_string(), true));
+            return;
+        }
+        self.pending_undo_commit = Some((label, self.head_is_pushed()));
+        self.input_mode = InputMode::UndoCommitConfirm;
     }
 
-    fn finish_tag_creation(&mut self, tag_name: &str, commit_ref: &str) -> Result<()> {
-        let was_pushed = self
-            .commits
-            .first()
-            .and_then(|c| c.tags.first())
-            .is_some_and(|t| t.pushed);
-        let old_tag = self.editing_tag.clone();
+    /// `v` / Ctrl+R in the Log tab: undo the selected commit's changes in the
+    /// work tree and index, with `Revert "…"` waiting in the commit box
+    fn revert_commit(&mut self) -> Result<()> {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+            .cloned()
+        else {
+            return Ok(());
+        };
+        if self.files.iter().any(|f| f.status != FileStatus::Untracked) {
+            self.message = Some((
+                "Commit or stash your changes before reverting".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let id = commit.full_id.to_string();
+        let mut args = vec!["revert", "--no-commit"];
+        // A merge is reverted relative to the branch it was merged into
+        if commit.is_merge() {
+            args.extend(["-m", "1"]);
+        }
+        args.push(&id);
+        let result = run_git(&self.repo_path, &args, "", "Revert failed");
+        let message = format!(
+            "Revert \"{}\"\n\nThis reverts commit {}.",
+            commit.message, id
+        );
+        self.finish_apply(result, "Revert", message)
+    }
 
-        // Delete old tag if editing
-        if let Some(ref old) = old_tag {
-            if old != tag_name {
-                self.delete_tag_by_name(old, was_pushed);
-            }
+    /// After a revert or cherry-pick: the result waits in the Files tab with its
+    /// message pre-filled, conflicted files first
+    fn finish_apply(&mut self, result: GitResult, what: &str, message: String) -> Result<()> {
+        self.refresh()?;
+        let conflicts = self
+            .files
+            .iter()
+            .filter(|f| f.status == FileStatus::Conflicted)
+            .count();
+        if let Err(e) = &result
+            && conflicts == 0
+        {
+            self.message = Some((summary_line(e).to_string(), true));
+            return Ok(());
         }
+        self.cursor_pos = message.len();
+        self.commit_message = message;
+        self.tab = Tab::Files;
+        self.message = Some(if conflicts > 0 {
+            (
+                format!(
+                    "{} stopped on {} conflict{}: resolve, stage with Space, then commit",
+                    what,
+                    conflicts,
+                    if conflicts == 1 { "" } else { "s" }
+                ),
+                true,
+            )
+        } else {
+            (format!("{} staged: review, then commit", what), false)
+        });
+        Ok(())
+    }
 
-        // Create new tag using git command
-        let output = std::process::Command::new("git")
-            .current_dir(&self.repo_path)
-            .args(["tag", "-f", tag_name, commit_ref])
-            .output();
+    /// `c` in the Log tab: confirm checking out the selected commit, refusing
+    /// while tracked files have changes the checkout could clobber
+    fn open_checkout_confirm(&mut self) -> Result<()> {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return Ok(());
+        };
+        let (id, label) = (
+            commit.full_id.to_string(),
+            format!("{} {}", commit.id, commit.message),
+        );
+        self.confirm_checkout(id, label)
+    }
 
-        if let Err(e) = output {
-            self.message = Some((format!("Failed to create tag: {e}"), true));
-            self.input_mode = InputMode::Normal;
+    /// Ask before detaching HEAD at `id` (also used from the reflog view)
+    fn confirm_checkout(&mut self, id: String, label: String) -> Result<()> {
+        if self.files.iter().any(|f| f.status != FileStatus::Untracked) {
+            self.message = Some((
+                "Commit or stash your changes before checking out".to_string(),
+                true,
+            ));
             return Ok(());
         }
-
-        // If old tag was pushed, push new tag too
-        if was_pushed {
-            let push_output = std::process::Command::new("git")
-                .current_dir(&self.repo_path)
-                .args(["push", "origin", tag_name])
-                .output();
-            if let Ok(out) = push_output {
-                if !out.status.success() {
-                    let err = String::from_utf8_lossy(&out.stderr);
-                    self.message = Some((format!("Tag created, push failed: {err}"), true));
-                    self.input_mode = InputMode::Normal;
-                    self.refresh_log()?;
-                    return Ok(());
-                }
-            }
-            self.message = Some((format!("Tag updated: {} (pushed)", tag_name), false));
+        self.pending_checkout = Some((id, label));
+        self.checkout_return = if self.detached_at.is_some() {
+            self.previous_branch()
         } else {
-            self.message = Some((format!("Created tag: {}", tag_name), false));
+            None
+        };
+        // Refused above with local changes, so nothing can be lost; the dialog
+        // stays when it also offers the way back to a branch
+        if self.confirmations == Confirmations::Minimal && self.checkout_return.is_none() {
+            return self.checkout_commit();
         }
-
-        self.tag_input.clear();
-        self.editing_tag = None;
-        self.input_mode = InputMode::Normal;
-        self.refresh_log()?;
+        self.input_mode = InputMode::CheckoutConfirm;
         Ok(())
     }
 
-    // === Discard Changes ===
+    /// Most recent branch in the checkout history (`@{-N}`), skipping detached checkouts
+    fn previous_branch(&self) -> Option<String> {
+        (1..=10).find_map(|n| {
+            let output = std::process::Command::new("git")
+                .current_dir(&self.repo_path)
+                .args(["rev-parse", "--symbolic-full-name", &format!("@{{-{}}}", n)])
+                .output()
+                .ok()?;
+            let name = String::from_utf8_lossy(&output.stdout);
+            name.trim().strip_prefix("refs/heads/").map(str::to_string)
+        })
+    }
 
-    fn open_discard_confirm(&mut self) {
-        let pending = match self.pending_discard_for_selected_file() {
-            Ok(pending) => pending,
-            Err(message) => {
-                self.message = Some((message, true));
-                return;
-            }
+    /// `git checkout --detach`: HEAD moves to the commit, no branch follows it
+    fn checkout_commit(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some((id, label)) = self.pending_checkout.take() else {
+            return Ok(());
         };
-        self.pending_discard = Some(PendingDiscardTarget::Single(pending));
-        self.input_mode = InputMode::DiscardConfirm;
+        let result = run_git(
+            &self.repo_path,
+            &["checkout", "--detach", &id],
+            &format!("HEAD detached at {}", label),
+            "Checkout failed",
+        );
+        self.finish_checkout(result)
     }
 
-    fn discard_changes(&mut self) -> Result<()> {
-        let Some(PendingDiscardTarget::Single(pending)) = self.pending_discard.take() else {
+    /// `b` in the checkout dialog: leave the detached HEAD for the branch it came from
+    fn checkout_return_branch(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        self.pending_checkout = None;
+        let Some(branch) = self.checkout_return.take() else {
+            return Ok(());
+        };
+        let result = run_git(
+            &self.repo_path,
+            &["checkout", &branch],
+            &format!("Back on {}", branch),
+            "Checkout failed",
+        );
+        self.finish_checkout(result)
+    }
+
+    fn finish_checkout(&mut self, result: GitResult) -> Result<()> {
+        match result {
+            Ok(msg) => self.message = Some((summary_line(&msg).to_string(), false)),
+            Err(e) => self.message = Some((summary_line(&e).to_string(), true)),
+        }
+        self.refresh()?;
+        // Keep the checked-out commit selected
+        if let Some(idx) = self.commits.iter().position(|c| c.is_head) {
+            self.commits_state.select(Some(idx));
+        }
+        Ok(())
+    }
+
+    /// `git reset --soft HEAD~1`: the commit's changes go back to STAGED and
+    /// its message back into the input, ready to be committed again
+    fn undo_commit(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let Some((label, _)) = self.pending_undo_commit.take() else {
             return Ok(());
         };
-        match execute_pending_discard_with(
+        let message = self
+            .repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .ok()
+            .and_then(|c| c.message().map(|m| m.trim_end().to_string()));
+        match run_git(
             &self.repo_path,
-            &pending,
-            run_restore_command,
-            move_to_trash,
+            &["reset", "--soft", "HEAD~1"],
+            &format!("Undid {}: changes are staged", label),
+            "Undo failed",
         ) {
-            Ok(message) => self.message = Some((message, false)),
-            Err(message) => self.message = Some((message, true)),
+            Ok(msg) => {
+                self.message = Some((summary_line(&msg).to_string(), false));
+                if self.commit_message.trim().is_empty()
+                    && let Some(message) = message
+                {
+                    self.commit_message = message;
+                    self.cursor_pos = self.commit_message.len();
+                }
+            }
+            Err(e) => self.message = Some((summary_line(&e).to_string(), true)),
         }
-        self.input_mode = InputMode::Normal;
-        self.refresh()?;
-        Ok(())
+        self.refresh()
     }
 
-    fn open_discard_all_confirm(&mut self) {
-        let targets: Vec<PendingDiscard> = self
-            .files
-            .iter()
-            .filter_map(|f| PendingDiscard::for_file(f).ok())
-            .collect();
-        if targets.is_empty() {
-            self.message = Some(("No unstaged changes to discard".to_string(), true));
+    // === Tag Detail ===
+
+    /// Tagger, date, annotation and target of every tag on the selected commit
+    fn open_tag_detail(&mut self) {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return;
+        };
+        if commit.tags.is_empty() {
+            self.message = Some(("No tag on this commit".to_string(), true));
             return;
         }
-        self.pending_discard = Some(PendingDiscardTarget::All(targets));
-        self.input_mode = InputMode::DiscardConfirm;
+        self.tag_details = commit
+            .tags
+            .iter()
+            .filter_map(|tag| tag_detail(&self.repo, &tag.name, tag.pushed))
+            .collect();
+        self.input_mode = InputMode::TagDetail;
     }
 
-    fn discard_all_changes(&mut self) -> Result<()> {
-        let Some(PendingDiscardTarget::All(targets)) = self.pending_discard.take() else {
-            return Ok(());
-        };
+    // === Tags View ===
 
-        // Batch restore tracked files in a single git command
-        let restore_paths: Vec<&str> = targets
+    /// `g` in the Log tab: all tags, not just those on the loaded commits
+    fn open_tags_view(&mut self) -> Result<()> {
+        let names = self.repo.tag_names(None)?;
+        self.tags_view = names
             .iter()
-            .filter(|p| p.action == PendingDiscardAction::RestoreTracked)
-            .map(|p| p.path.as_str())
+            .flatten()
+            .filter_map(|name| tag_detail(&self.repo, name, self.remote_tags_cache.contains(name)))
             .collect();
-
-        let mut success = 0usize;
-        let mut failure = 0usize;
-
-        if !restore_paths.is_empty() {
-            let mut args = vec!["restore", "--"];
-            args.extend(&restore_paths);
-            let output = std::process::Command::new("git")
-                .current_dir(&self.repo_path)
-                .args(&args)
-                .output();
-            match output {
-                Ok(out) if out.status.success() => success += restore_paths.len(),
-                _ => failure += restore_paths.len(),
-            }
+        if self.tags_view.is_empty() {
+            self.message = Some(("No tags yet: t tags the selected commit".to_string(), true));
+            return Ok(());
         }
+        self.sort_tags_view();
+        self.tags_view_state.select(Some(0));
+        self.input_mode = InputMode::TagsView;
+        Ok(())
+    }
 
-        // Trash untracked files individually
-        for pending in targets
-            .iter()
-            .filter(|p| p.action == PendingDiscardAction::TrashUntracked)
-        {
-            match move_to_trash(&self.repo_path, &pending.path) {
-                Ok(()) => success += 1,
-                Err(_) => failure += 1,
-            }
+    /// Newest first, by version number or by tagging date (`s` toggles)
+    fn sort_tags_view(&mut self) {
+        if self.tags_by_date {
+            self.tags_view
+                .sort_by_key(|t| std::cmp::Reverse(t.timestamp));
+        } else {
+            self.tags_view
+                .sort_by(|a, b| version::compare_versions(&b.name, &a.name));
         }
+    }
 
-        let msg = if failure == 0 {
-            format!("Discarded all {} files", success)
-        } else {
-            format!("Discarded {} files ({} failed)", success, failure)
+    fn toggle_tags_sort(&mut self) {
+        self.tags_by_date = !self.tags_by_date;
+        self.sort_tags_view();
+        self.tags_view_state.select(Some(0));
+    }
+
+    /// Enter in the tags view: select the tagged commit in the Log tab
+    fn jump_to_tag(&mut self) {
+        let Some(tag) = self
+            .tags_view_state
+            .selected()
+            .and_then(|i| self.tags_view.get(i))
+            .cloned()
+        else {
+            return;
         };
-        self.message = Some((msg, failure > 0));
-        self.input_mode = InputMode::Normal;
-        self.refresh()?;
-        Ok(())
+        self.load_log_until(0, |c| c.full_id == tag.target_id);
+        match self.commits.iter().position(|c| c.full_id == tag.target_id) {
+            Some(idx) => {
+                self.commits_state.select(Some(idx));
+                self.tags_view.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            None => {
+                let hint = if self.log_all_branches {
+                    ""
+                } else {
+                    " (A shows all branches)"
+                };
+                self.message = Some((
+                    format!("{} is not in the loaded log{}", tag.name, hint),
+                    true,
+                ))
+            }
+        }
     }
 
     // === Delete Tag ===
@@ -1798,13 +7005,345 @@ impl App {
                 };
                 format!("siori diff -C \"{}\" {}", repo_path, commit.id)
             }
+            Tab::Stash => {
+                let Some(stash) = self.selected_stash() else {
+                    return;
+                };
+                format!("siori diff -C \"{}\" stash@{{{}}}", repo_path, stash.index)
+            }
+            Tab::Branches => {
+                let Some(branch) = self.selected_branch() else {
+                    return;
+                };
+                format!("siori diff -C \"{}\" {}", repo_path, branch.name)
+            }
         };
         self.pending_diff_command = Some(cmd);
     }
 
+    /// Merge commits in the Log tab: the diff against parent `n` instead of the combined one
+    fn copy_parent_diff_command(&mut self, n: usize) -> Result<()> {
+        if self.tab == Tab::Log
+            && let Some(commit) = self
+                .commits_state
+                .selected()
+                .and_then(|i| self.commits.get(i))
+            && n <= commit.parents.len()
+            && commit.is_merge()
+        {
+            self.pending_diff_command = Some(format!(
+                "siori diff -C \"{}\" {} --parent {}",
+                self.repo_path.display(),
+                commit.id,
+                n
+            ));
+            return self.copy_diff_command();
+        }
+        Ok(())
+    }
+
+    /// `1` / `2` in the Log tab: select the first / second parent of the commit
+    fn jump_to_parent(&mut self, n: usize) {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return;
+        };
+        let Some(&parent) = commit.parents.get(n - 1) else {
+            self.message = Some((format!("{} has no parent {}", commit.id, n), true));
+            return;
+        };
+        // A long-lived branch can fork far down: load a few more batches to find it
+        self.load_log_until(0, |c| c.full_id == parent);
+        match self.commits.iter().position(|c| c.full_id == parent) {
+            Some(idx) => self.commits_state.select(Some(idx)),
+            None => {
+                self.message = Some((
+                    format!("Parent {:.7} is beyond the loaded log", parent),
+                    true,
+                ))
+            }
+        }
+    }
+
+    /// Walk up to ten more batches of history until a commit from `from` on satisfies `found`
+    fn load_log_until(&mut self, from: usize, found: impl Fn(&CommitEntry) -> bool) {
+        for _ in 0..10 {
+            if !self.log_has_more || self.commits.iter().skip(from).any(&found) {
+                break;
+            }
+            self.log_limit += LOG_BATCH;
+            if self.refresh_log_local().is_err() {
+                break;
+            }
+        }
+    }
+
+    fn open_log_search(&mut self) {
+        self.log_search_input.clear();
+        self.input_mode = InputMode::LogSearch;
+    }
+
+    fn apply_log_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let query = self.log_search_input.trim().to_string();
+        if query.is_empty() {
+            self.log_search = None;
+            return;
+        }
+        self.log_search = Some(query);
+        self.search_log(true, true);
+    }
+
+    /// Select the next (or previous) commit matching the search, wrapping around
+    fn search_log(&mut self, forward: bool, include_current: bool) {
+        let Some(query) = self.log_search.clone() else {
+            return;
+        };
+        let current = self.commits_state.selected().unwrap_or(0);
+        // Nothing further down the loaded log: older history may have it
+        if forward {
+            self.load_log_until(current + usize::from(!include_current), |c| {
+                c.matches(&query)
+            });
+        }
+        let matches: Vec<usize> = (0..self.commits.len())
+            .filter(|&i| self.commits[i].matches(&query))
+            .collect();
+        if matches.is_empty() {
+            self.message = Some((format!("No commit matches \"{}\"", query), true));
+            return;
+        }
+        let next = if forward {
+            matches
+                .iter()
+                .find(|&&i| {
+                    if include_current {
+                        i >= current
+                    } else {
+                        i > current
+                    }
+                })
+                .unwrap_or(&matches[0])
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&i| i < current)
+                .unwrap_or(&matches[matches.len() - 1])
+        };
+        self.commits_state.select(Some(*next));
+        let position = matches.iter().position(|i| i == next).unwrap_or(0) + 1;
+        self.message = Some((
+            format!("\"{}\" {}/{}", query, position, matches.len()),
+            false,
+        ));
+    }
+
+    /// Enter / `l` in the Log tab: message, people, parents and files of the selected commit
+    fn open_commit_detail(&mut self) -> Result<()> {
+        let Some(entry) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return Ok(());
+        };
+        let commit = self.repo.find_commit(entry.full_id)?;
+        let signature = |sig: git2::Signature| {
+            format!(
+                "{} <{}>",
+                sig.name().unwrap_or("?"),
+                sig.email().unwrap_or("?")
+            )
+        };
+        let date = |time: git2::Time| {
+            format!(
+                "{} ({})",
+                date::format_utc(time.seconds()),
+                format_relative_time(time.seconds())
+            )
+        };
+
+        let files = self.commit_files_vs_parent(&commit, 1)?;
+
+        self.commit_detail = Some(CommitDetail {
+            id: entry.id.clone(),
+            full_id: entry.full_id,
+            message: commit.message().unwrap_or("").trim_end().to_string(),
+            author: signature(commit.author()),
+            author_date: date(commit.author().when()),
+            committer: signature(commit.committer()),
+            commit_date: date(commit.committer().when()),
+            parents: commit.parent_ids().map(|id| format!("{:.7}", id)).collect(),
+            refs: entry
+                .local_branches
+                .iter()
+                .chain(&entry.remote_branches)
+                .cloned()
+                .chain(entry.tags.iter().map(|t| format!("tag: {}", t.name)))
+                .collect(),
+            files,
+            diff_parent: Some(1),
+        });
+        self.commit_detail_state.select(
+            self.commit_detail
+                .as_ref()
+                .and_then(|d| (!d.files.is_empty()).then_some(0)),
+        );
+        self.input_mode = InputMode::CommitDetail;
+        Ok(())
+    }
+
+    /// Files changed by `commit` against its parent `n` (1-based); everything for a root commit
+    fn commit_files_vs_parent(
+        &self,
+        commit: &git2::Commit,
+        n: usize,
+    ) -> Result<Vec<CommitDetailFile>> {
+        let tree = commit.tree()?;
+        let parent_tree = commit.parent(n - 1).ok().map(|p| p.tree()).transpose()?;
+        let mut diff = self
+            .repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        let _ = diff.find_similar(None);
+        let mut files = Vec::new();
+        for idx in 0..diff.deltas().len() {
+            let Ok(Some(patch)) = git2::Patch::from_diff(&diff, idx) else {
+                continue;
+            };
+            let binary = patch.delta().flags().is_binary();
+            files.push(CommitDetailFile {
+                path: delta_path(&patch.delta()),
+                status: delta_status_char(patch.delta().status()),
+                stats: patch
+                    .line_stats()
+                    .ok()
+                    .filter(|_| !binary)
+                    .map(|(_, add, del)| (add, del)),
+            });
+        }
+        Ok(files)
+    }
+
+    /// Diff text of the detail pane's commit against the chosen parent, or the
+    /// combined diff of a merge
+    fn commit_detail_diff_text(&self, detail: &CommitDetail) -> Result<String, String> {
+        let id = detail.full_id.to_string();
+        let args: Vec<String> = match detail.diff_parent {
+            // The root commit has no parent to diff against
+            _ if detail.parents.is_empty() => {
+                vec!["show".into(), "--format=".into(), "--no-color".into(), id]
+            }
+            Some(n) => vec![
+                "diff".into(),
+                "--no-color".into(),
+                "-M".into(),
+                format!("{}^{}", id, n),
+                id,
+            ],
+            None => vec![
+                "show".into(),
+                "--cc".into(),
+                "--format=".into(),
+                "--no-color".into(),
+                id,
+            ],
+        };
+        std::process::Command::new("git")
+            .current_dir(&self.repo_path)
+            .args(&args)
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+            .map_err(|e| format!("Failed to run git diff: {}", e))
+    }
+
+    /// `p` in the detail pane of a merge: parent 1 → parent 2 → … → combined → parent 1
+    fn cycle_commit_diff_parent(&mut self) -> Result<()> {
+        let Some(detail) = &self.commit_detail else {
+            return Ok(());
+        };
+        if detail.parents.len() < 2 {
+            self.message = Some(("Not a merge commit".to_string(), true));
+            return Ok(());
+        }
+        let next = match detail.diff_parent {
+            Some(n) if n < detail.parents.len() => Some(n + 1),
+            Some(_) => None,
+            None => Some(1),
+        };
+        let files = match next {
+            Some(n) => {
+                let commit = self.repo.find_commit(detail.full_id)?;
+                self.commit_files_vs_parent(&commit, n)?
+            }
+            None => {
+                let combined = CommitDetail {
+                    diff_parent: None,
+                    ..detail.clone()
+                };
+                let text = match self.commit_detail_diff_text(&combined) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        self.message = Some((e, true));
+                        return Ok(());
+                    }
+                };
+                combined_diff_files(&text)
+            }
+        };
+        if let Some(detail) = &mut self.commit_detail {
+            detail.diff_parent = next;
+            detail.files = files;
+        }
+        self.commit_detail_state.select(
+            self.commit_detail
+                .as_ref()
+                .and_then(|d| (!d.files.is_empty()).then_some(0)),
+        );
+        Ok(())
+    }
+
+    /// Enter on a file in the detail pane: its diff against the chosen parent
+    fn open_commit_file_diff(&mut self) {
+        let Some(detail) = &self.commit_detail else {
+            return;
+        };
+        let Some(file) = self
+            .commit_detail_state
+            .selected()
+            .and_then(|i| detail.files.get(i))
+        else {
+            return;
+        };
+        let files = match self.commit_detail_diff_text(detail) {
+            Ok(text) => diff_viewer::parse_diff_files(&text),
+            Err(e) => {
+                self.message = Some((e, true));
+                return;
+            }
+        };
+        let Some(index) = files.iter().position(|f| f.path == file.path) else {
+            self.message = Some((format!("No text diff for {}", file.path), false));
+            return;
+        };
+        let title = format!("{} {}", detail.id, summary_line(&detail.message));
+        let mut pager = DiffPager::new(title, files);
+        pager.file = index;
+        self.commit_file_diff = Some(pager);
+        self.input_mode = InputMode::CommitFileDiff;
+    }
+
+    fn close_commit_detail(&mut self) {
+        self.commit_detail = None;
+        self.input_mode = InputMode::Normal;
+    }
+
     fn copy_diff_command(&mut self) -> Result<()> {
         if let Some(cmd) = self.pending_diff_command.take() {
-            if let Err(e) = copy_to_clipboard(&cmd) {
+            if let Err(e) = copy_to_clipboard(&cmd, self.clipboard_backend) {
                 self.message = Some((format!("Copy failed: {}", e), true));
             } else {
                 self.message = Some((format!("Copied: {}", cmd), false));
@@ -1815,25 +7354,47 @@ impl App {
     }
 
     fn open_diff_confirm(&mut self) -> Result<()> {
+        // A nested repository has no diff of its own here: open it instead
+        if self.tab == Tab::Files
+            && let Some(file) = self.selected_file()
+            && file.nested_repo
+        {
+            let path = self.repo_path.join(file.path.trim_end_matches('/'));
+            return self.switch_repo(path);
+        }
         self.prepare_diff_command();
         if self.pending_diff_command.is_none() {
             return Ok(());
         }
+        // A merge still asks which parent to diff against
+        let merge = self.tab == Tab::Log
+            && self
+                .commits_state
+                .selected()
+                .and_then(|i| self.commits.get(i))
+                .is_some_and(|c| c.is_merge());
+        if self.confirmations == Confirmations::Minimal && !merge {
+            return self.copy_diff_command();
+        }
         self.input_mode = InputMode::DiffConfirm;
         Ok(())
     }
 
     // === Cherry-pick / Merge / Rebase ===
 
-    fn copy_commit_hash(&mut self) -> Result<()> {
+    fn copy_commit_hash(&mut self, full: bool) -> Result<()> {
         let Some(idx) = self.commits_state.selected() else {
             return Ok(());
         };
         let Some(commit) = self.commits.get(idx) else {
             return Ok(());
         };
-        let id = commit.id.clone();
-        if let Err(e) = copy_to_clipboard(&id) {
+        let id = if full {
+            commit.full_id.to_string()
+        } else {
+            commit.id.clone()
+        };
+        if let Err(e) = copy_to_clipboard(&id, self.clipboard_backend) {
             self.message = Some((format!("Copy failed: {}", e), true));
         } else {
             self.message = Some((format!("Copied: {}", id), false));
@@ -1841,6 +7402,24 @@ impl App {
         Ok(())
     }
 
+    /// `s` in the Log tab: `<short hash>  <subject>`, for PR descriptions and chat
+    fn copy_commit_line(&mut self) -> Result<()> {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return Ok(());
+        };
+        let line = format!("{}  {}", commit.id, commit.message);
+        if let Err(e) = copy_to_clipboard(&line, self.clipboard_backend) {
+            self.message = Some((format!("Copy failed: {}", e), true));
+        } else {
+            self.message = Some((format!("Copied: {}", line), false));
+        }
+        Ok(())
+    }
+
     fn open_cherry_pick_input(&mut self) {
         self.cherry_pick_input.clear();
         self.input_mode = InputMode::CherryPickInput;
@@ -1853,21 +7432,125 @@ impl App {
             self.input_mode = InputMode::Normal;
             return Ok(());
         }
+        self.input_mode = InputMode::Normal;
+        self.run_cherry_pick(&[&hash], &hash)
+    }
+
+    /// Space in the Log tab: mark the selected commit as one end of a range
+    fn toggle_log_mark(&mut self) {
+        let Some(commit) = self
+            .commits_state
+            .selected()
+            .and_then(|i| self.commits.get(i))
+        else {
+            return;
+        };
+        if self.log_mark == Some(commit.full_id) {
+            self.log_mark = None;
+        } else {
+            self.log_mark = Some(commit.full_id);
+            self.message = Some((
+                format!(
+                    "Marked {}: C picks everything up to here, d compares",
+                    commit.id
+                ),
+                false,
+            ));
+        }
+    }
+
+    /// `C` in the Log tab: cherry-pick the selected commit, or every commit
+    /// from the marked one to the selected one (oldest first)
+    fn cherry_pick_selected(&mut self) -> Result<()> {
+        let Some(index) = self.commits_state.selected() else {
+            return Ok(());
+        };
+        let Some(commit) = self.commits.get(index).cloned() else {
+            return Ok(());
+        };
+        if self.files.iter().any(|f| f.status != FileStatus::Untracked) {
+            self.message = Some((
+                "Commit or stash your changes before cherry-picking".to_string(),
+                true,
+            ));
+            return Ok(());
+        }
+        if self.is_in_head(commit.full_id) {
+            self.open_cherry_pick_input();
+            self.message = Some((
+                format!(
+                    "{} is already on {}: enter a commit from another branch",
+                    commit.id, self.branch_name
+                ),
+                false,
+            ));
+            return Ok(());
+        }
+        let marked = self
+            .log_mark
+            .filter(|mark| *mark != commit.full_id)
+            .and_then(|mark| self.commits.iter().position(|c| c.full_id == mark));
+        let Some(marked) = marked else {
+            let id = commit.full_id.to_string();
+            let label = format!("{} {}", commit.id, commit.message);
+            // A merge is picked relative to the branch it was merged into
+            return if commit.is_merge() {
+                self.run_cherry_pick(&["-m", "1", &id], &label)
+            } else {
+                self.run_cherry_pick(&[&id], &label)
+            };
+        };
+        // The log lists newest first
+        let (newest, oldest) = (index.min(marked), index.max(marked));
+        let range = format!(
+            "{}^..{}",
+            self.commits[oldest].full_id, self.commits[newest].full_id
+        );
+        let label = format!(
+            "{} commits ({}..{})",
+            oldest - newest + 1,
+            self.commits[oldest].id,
+            self.commits[newest].id
+        );
+        self.log_mark = None;
+        self.run_cherry_pick(&[&range], &label)
+    }
+
+    /// HEAD itself or one of its ancestors
+    fn is_in_head(&self, id: git2::Oid) -> bool {
+        let Some(head) = self.repo.head().ok().and_then(|h| h.target()) else {
+            return false;
+        };
+        head == id || self.repo.graph_descendant_of(head, id).unwrap_or(false)
+    }
+
+    /// Commits straight away; a conflict leaves the pick waiting in the Files
+    /// tab with its original message, like a revert
+    fn run_cherry_pick(&mut self, args: &[&str], label: &str) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
+        let mut cmd = vec!["cherry-pick"];
+        cmd.extend_from_slice(args);
         let result = run_git(
             &self.repo_path,
-            &["cherry-pick", &hash],
-            &format!("Cherry-picked: {}", hash),
+            &cmd,
+            &format!("Cherry-picked: {}", label),
             "Cherry-pick failed",
         );
-        match result {
-            Ok(msg) => {
-                self.message = Some((msg, false));
-                self.refresh()?;
-            }
-            Err(msg) => self.message = Some((msg, true)),
+        if let Ok(msg) = result {
+            self.message = Some((msg, false));
+            return self.refresh();
         }
-        self.input_mode = InputMode::Normal;
-        Ok(())
+        let message = std::fs::read_to_string(self.repo.path().join("MERGE_MSG"))
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string();
+        self.finish_apply(result, "Cherry-pick", message)
     }
 
     fn open_branch_select(&mut self, op: BranchSelectOp) {
@@ -1891,6 +7574,9 @@ impl App {
     }
 
     fn execute_branch_op(&mut self) -> Result<()> {
+        if !self.ensure_writable() {
+            return Ok(());
+        }
         let Some(idx) = self.branch_select_state.selected() else {
             return Ok(());
         };
@@ -1936,6 +7622,53 @@ impl App {
         }
     }
 
+    /// "as of 12 min ago" beside ↑/↓, and whether the last fetch is older than the threshold
+    pub fn fetch_freshness(&self) -> Option<(String, bool)> {
+        self.ahead_behind?;
+        let Some(fetched) = self.last_fetch else {
+            return Some(("never fetched".to_string(), true));
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let stale =
+            self.fetch_stale_minutes > 0 && now - fetched > self.fetch_stale_minutes as i64 * 60;
+        Some((format!("as of {}", format_relative_time(fetched)), stale))
+    }
+
+    /// Aggregate (files, insertions, deletions) of everything staged
+    /// Totals for the STAGED header; generated files don't count
+    pub fn staged_diff_totals(&self) -> (usize, usize, usize) {
+        diff_totals(self.files.iter().filter(|f| f.staged && !f.generated))
+    }
+
+    /// (directory, insertions, deletions) for the CHANGES summary row, when enabled
+    pub fn changes_directory_totals(&self) -> Option<Vec<(String, usize, usize)>> {
+        if !self.directory_stats {
+            return None;
+        }
+        let totals = directory_totals(
+            self.visual_list
+                .iter()
+                .map(|&i| &self.files[i])
+                .filter(|f| !f.staged && f.hidden.is_none() && !f.generated),
+        );
+        (!totals.is_empty()).then_some(totals)
+    }
+
+    fn copy_file_path(&mut self) -> Result<()> {
+        let Some(path) = self.selected_file().map(|f| f.path.clone()) else {
+            return Ok(());
+        };
+        if let Err(e) = copy_to_clipboard(&path, self.clipboard_backend) {
+            self.message = Some((format!("Copy failed: {}", e), true));
+        } else {
+            self.message = Some((format!("Copied: {}", path), false));
+        }
+        Ok(())
+    }
+
     pub fn selected_file(&self) -> Option<&FileEntry> {
         let idx = self.files_state.selected()?;
         let &file_idx = self.visual_list.get(idx)?;
@@ -1957,16 +7690,87 @@ impl App {
     }
 
     pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        let result = self.dispatch_key(code, modifiers);
+        // A lock or read-only .git makes libgit2 fail mid-operation: point at
+        // the banner instead of quitting with the raw error
+        if let Err(e) = &result {
+            self.check_health(true);
+            if self.health.iter().any(|h| h.blocks_writes()) {
+                self.message = Some((summary_line(&e.to_string()).to_string(), true));
+                self.input_mode = InputMode::Normal;
+                return Ok(());
+            }
+        }
+        result
+    }
+
+    fn dispatch_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
         let code = match code {
             KeyCode::Char(c) => KeyCode::Char(normalize_fullwidth(c)),
             other => other,
         };
         self.message = None;
+        self.failed_operation = None;
+
+        let macro_key = self.input_mode == InputMode::Normal
+            && matches!(code, KeyCode::Char('Q') | KeyCode::Char('@'));
+        if !macro_key && let Some(keys) = &mut self.macro_recording {
+            keys.push((code, modifiers));
+        }
 
         match self.input_mode {
             InputMode::Insert => match code {
                 KeyCode::Esc => self.input_mode = InputMode::Normal,
+                // Shift+Enter needs the kitty protocol; Alt+Enter and Ctrl+J work everywhere
+                KeyCode::Enter if modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) => {
+                    self.commit_message.insert(self.cursor_pos, '\n');
+                    self.cursor_pos += 1;
+                }
+                KeyCode::Char('j') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.commit_message.insert(self.cursor_pos, '\n');
+                    self.cursor_pos += 1;
+                }
+                KeyCode::Enter if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.commit_empty()?
+                }
+                // Ctrl+Enter needs the kitty protocol; Ctrl+E works everywhere
+                KeyCode::Char('e') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.commit_empty()?
+                }
+                KeyCode::Enter if self.commit_all_tracked && !self.is_amending => {
+                    self.commit_tracked_changes()?
+                }
                 KeyCode::Enter => self.commit()?,
+                KeyCode::Char('a') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.commit_tracked_changes()?
+                }
+                KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.commit_only_marked()?
+                }
+                KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.open_co_author_select()
+                }
+                KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.open_commit_date_input()
+                }
+                KeyCode::Char('f') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.open_commit_file_select()
+                }
+                KeyCode::Char('g') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.suggest_commit_message()
+                }
+                KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.signoff = !self.signoff;
+                    self.message = Some((
+                        if self.signoff {
+                            "Signed-off-by: on".to_string()
+                        } else {
+                            "Signed-off-by: off".to_string()
+                        },
+                        false,
+                    ));
+                }
+                #[allow(clippy::collapsible_match)]
                 KeyCode::Backspace => {
                     if self.cursor_pos > 0 {
                         let prev = self.cursor_prev_char();
@@ -1974,96 +7778,637 @@ impl App {
                         self.cursor_pos = prev;
                     }
                 }
-                KeyCode::Delete => {
-                    if self.cursor_pos < self.commit_message.len() {
-                        self.commit_message.remove(self.cursor_pos);
+                #[allow(clippy::collapsible_match)]
+                KeyCode::Delete => {
+                    if self.cursor_pos < self.commit_message.len() {
+                        self.commit_message.remove(self.cursor_pos);
+                    }
+                }
+                KeyCode::Left => self.cursor_pos = self.cursor_prev_char(),
+                KeyCode::Right => self.cursor_pos = self.cursor_next_char(),
+                KeyCode::Up => self.cursor_pos = self.cursor_vertical(false),
+                KeyCode::Down => self.cursor_pos = self.cursor_vertical(true),
+                KeyCode::Home => self.cursor_pos = self.cursor_line_bounds().0,
+                KeyCode::End => self.cursor_pos = self.cursor_line_bounds().1,
+                KeyCode::Char(c) => {
+                    self.commit_message.insert(self.cursor_pos, c);
+                    self.cursor_pos += c.len_utf8();
+                    self.wrap_body_line();
+                }
+                _ => {}
+            },
+            InputMode::RemoteUrl => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.remote_url.clear();
+                    self.message = Some(("Cancelled".to_string(), false));
+                }
+                KeyCode::Enter => self.add_remote_and_push()?,
+                KeyCode::Backspace => {
+                    self.remote_url.pop();
+                }
+                KeyCode::Char(c) => self.remote_url.push(c),
+                _ => {}
+            },
+            InputMode::RepoSelect => match code {
+                KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Enter => {
+                    if let Some(idx) = self.repo_select_state.selected()
+                        && let Some(path) = self.selector_path_at(idx)
+                    {
+                        if path != self.repo_path {
+                            self.switch_repo(path)?;
+                        } else {
+                            self.input_mode = InputMode::Normal;
+                        }
+                    }
+                }
+                KeyCode::Char('j') | KeyCode::Down => self.repo_select_next(),
+                KeyCode::Char('k') | KeyCode::Up => self.repo_select_prev(),
+                KeyCode::Char('a') => self.open_worktree_type_select(),
+                KeyCode::Char('x') => self.open_worktree_remove_confirm(),
+                _ => {}
+            },
+            InputMode::TagInput => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.tag_input.clear();
+                    self.editing_tag = None;
+                }
+                KeyCode::Enter => self.create_or_update_tag()?,
+                KeyCode::Backspace => {
+                    self.tag_input.pop();
+                }
+                KeyCode::Char(c) => self.tag_input.push(c),
+                _ => {}
+            },
+            InputMode::VersionConfirm => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.pending_version_update = None;
+                    self.tag_input.clear();
+                }
+                // Uncommitted changes are listed in this dialog, so no second prompt
+                KeyCode::Enter => self.do_version_update_and_tag()?,
+                KeyCode::Char('p') => {
+                    if let Some(pending) = &mut self.pending_version_update {
+                        pending.push = !pending.push;
+                    }
+                }
+                _ => {}
+            },
+            InputMode::UncommittedWarning => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.pending_version_update = None;
+                    self.tag_input.clear();
+                }
+                KeyCode::Enter => self.do_version_update_and_tag()?,
+                _ => {}
+            },
+            InputMode::DiscardConfirm => match code {
+                KeyCode::Esc => {
+                    // Return to the hunk view when cancelling a hunk discard
+                    self.input_mode = match self.pending_discard.take() {
+                        Some(PendingDiscardTarget::Hunk(_)) => InputMode::HunkView,
+                        _ => InputMode::Normal,
+                    };
+                }
+                KeyCode::Enter | KeyCode::Char('x') | KeyCode::Char('X') => {
+                    match &self.pending_discard {
+                        Some(PendingDiscardTarget::All(_)) => self.discard_all_changes()?,
+                        Some(PendingDiscardTarget::Single(_)) => self.discard_changes()?,
+                        Some(PendingDiscardTarget::Hunk(_)) => self.discard_hunk()?,
+                        None => {}
+                    }
+                }
+                _ => {}
+            },
+            InputMode::StashInput => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.stash_message_input.clear();
+                }
+                KeyCode::Enter => self.stash_save()?,
+                KeyCode::Backspace => {
+                    self.stash_message_input.pop();
+                }
+                KeyCode::Char(c) => self.stash_message_input.push(c),
+                _ => {}
+            },
+            InputMode::StashDropConfirm => match code {
+                KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Enter => self.stash_drop()?,
+                _ => {}
+            },
+            InputMode::BranchNameInput => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.branch_name_input.clear();
+                    self.renaming_branch = None;
+                }
+                KeyCode::Enter => self.submit_branch_name()?,
+                KeyCode::Backspace => {
+                    self.branch_name_input.pop();
+                }
+                // Branch names can't hold spaces
+                KeyCode::Char(' ') => self.branch_name_input.push('-'),
+                KeyCode::Char(c) => self.branch_name_input.push(c),
+                _ => {}
+            },
+            InputMode::BranchDeleteConfirm => match code {
+                KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Enter => self.delete_selected_branch(true)?,
+                _ => {}
+            },
+            InputMode::GitignoreInput => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.gitignore_input.clear();
+                }
+                KeyCode::Enter => self.add_to_gitignore()?,
+                KeyCode::Backspace => {
+                    self.gitignore_input.pop();
+                }
+                KeyCode::Char(c) => self.gitignore_input.push(c),
+                _ => {}
+            },
+            InputMode::QuickLook => match code {
+                KeyCode::Esc | KeyCode::Char('K') | KeyCode::Char('q') => {
+                    self.quick_look = None;
+                    self.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.select_next();
+                    self.load_quick_look();
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.select_prev();
+                    self.load_quick_look();
+                }
+                KeyCode::Char(' ') => {
+                    self.stage_selected()?;
+                    self.load_quick_look();
+                }
+                // The full diff, for when the first lines aren't enough
+                KeyCode::Enter | KeyCode::Char('d') => {
+                    self.quick_look = None;
+                    self.input_mode = InputMode::Normal;
+                    self.open_hunk_view();
+                }
+                _ => {}
+            },
+            InputMode::Review => match code {
+                // The session keeps running; `B` reopens it
+                KeyCode::Esc | KeyCode::Char('B') | KeyCode::Char('q') => {
+                    self.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('j') | KeyCode::Down => self.select_review_file(1),
+                KeyCode::Char('k') | KeyCode::Up => self.select_review_file(-1),
+                KeyCode::Char('J') | KeyCode::PageDown => self.scroll_review(20),
+                KeyCode::Char('K') | KeyCode::PageUp => self.scroll_review(-20),
+                KeyCode::Char(' ') | KeyCode::Char('a') => self.approve_review_file()?,
+                KeyCode::Char('x') => self.reject_review_file()?,
+                KeyCode::Char('u') => self.undo_review_reject()?,
+                KeyCode::Char('e') => self.end_review(),
+                _ => {}
+            },
+            InputMode::MoveInput => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.move_sources.clear();
+                    self.move_input.clear();
+                }
+                KeyCode::Enter => self.move_files()?,
+                KeyCode::Backspace => {
+                    self.move_input.pop();
+                }
+                KeyCode::Char(c) => self.move_input.push(c),
+                _ => {}
+            },
+            InputMode::AmendPreview => match code {
+                KeyCode::Esc => {
+                    self.amend_preview.clear();
+                    self.input_mode = InputMode::Normal;
+                }
+                KeyCode::Enter => self.confirm_amend(),
+                _ => {}
+            },
+            InputMode::ChangelogInput => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.changelog_input.clear();
+                }
+                KeyCode::Enter => self.add_changelog_fragment()?,
+                KeyCode::Backspace => {
+                    self.changelog_input.pop();
+                }
+                KeyCode::Char(c) => self.changelog_input.push(c),
+                _ => {}
+            },
+            InputMode::CommitWarning => match code {
+                KeyCode::Esc => self.back_out_of_commit(),
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.commit_problems.len();
+                    if len > 0 {
+                        let i = self.commit_problems_state.selected().unwrap_or(0);
+                        self.commit_problems_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.commit_problems.len();
+                    if len > 0 {
+                        let i = self.commit_problems_state.selected().unwrap_or(0);
+                        self.commit_problems_state.select(Some(if i == 0 {
+                            len - 1
+                        } else {
+                            i - 1
+                        }));
+                    }
+                }
+                KeyCode::Char('o') => self.jump_to_commit_problem(),
+                KeyCode::Enter => self.check_size_or_commit()?,
+                _ => {}
+            },
+            InputMode::RecoveryPrompt => match code {
+                KeyCode::Esc => {
+                    self.recovery_actions.clear();
+                    self.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('o') => self.open_output_view(),
+                KeyCode::Char(c) => {
+                    if let Some(action) = self.recovery_actions.iter().find(|a| a.key() == c) {
+                        self.run_recovery(*action);
+                    }
+                }
+                _ => {}
+            },
+            InputMode::CommitTypeSelect => match code {
+                // No prefix this time: type the message as usual
+                KeyCode::Esc => self.input_mode = InputMode::Insert,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.repo_config.conventional.types.len();
+                    if len > 0 {
+                        let i = self.commit_type_state.selected().unwrap_or(0);
+                        self.commit_type_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.repo_config.conventional.types.len();
+                    if len > 0 {
+                        let i = self.commit_type_state.selected().unwrap_or(0);
+                        self.commit_type_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Enter => self.input_mode = InputMode::CommitScopeInput,
+                _ => {}
+            },
+            InputMode::CommitScopeInput => match code {
+                KeyCode::Esc => {
+                    self.commit_scope_input.clear();
+                    self.input_mode = InputMode::CommitTypeSelect;
+                }
+                KeyCode::Enter => self.apply_conventional_prefix(),
+                KeyCode::Tab => self.cycle_commit_scope(),
+                KeyCode::Backspace => {
+                    self.commit_scope_input.pop();
+                }
+                KeyCode::Char(c) if !c.is_whitespace() && c != '(' && c != ')' => {
+                    self.commit_scope_input.push(c)
+                }
+                _ => {}
+            },
+            InputMode::CommitDateInput => match code {
+                KeyCode::Esc => {
+                    self.commit_date_input.clear();
+                    self.input_mode = InputMode::Insert;
+                }
+                KeyCode::Enter => self.apply_commit_date(),
+                KeyCode::Backspace => {
+                    self.commit_date_input.pop();
+                }
+                KeyCode::Char(c) => self.commit_date_input.push(c),
+                _ => {}
+            },
+            InputMode::CoAuthorSelect => match code {
+                KeyCode::Esc => {
+                    self.co_author_candidates.clear();
+                    self.co_author_marked.clear();
+                    self.input_mode = InputMode::Insert;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.co_author_candidates.len();
+                    if len > 0 {
+                        let i = self.co_author_state.selected().unwrap_or(0);
+                        self.co_author_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.co_author_candidates.len();
+                    if len > 0 {
+                        let i = self.co_author_state.selected().unwrap_or(0);
+                        self.co_author_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(i) = self.co_author_state.selected()
+                        && !self.co_author_marked.remove(&i)
+                    {
+                        self.co_author_marked.insert(i);
+                    }
+                }
+                KeyCode::Enter => self.apply_co_authors(),
+                _ => {}
+            },
+            InputMode::ProfileSelect => match code {
+                KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.profiles.len();
+                    if len > 0 {
+                        let i = self.profile_state.selected().unwrap_or(0);
+                        self.profile_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.profiles.len();
+                    if len > 0 {
+                        let i = self.profile_state.selected().unwrap_or(0);
+                        self.profile_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
                     }
                 }
-                KeyCode::Left => self.cursor_pos = self.cursor_prev_char(),
-                KeyCode::Right => self.cursor_pos = self.cursor_next_char(),
-                KeyCode::Home => self.cursor_pos = 0,
-                KeyCode::End => self.cursor_pos = self.commit_message.len(),
-                KeyCode::Char(c) => {
-                    self.commit_message.insert(self.cursor_pos, c);
-                    self.cursor_pos += c.len_utf8();
+                KeyCode::Enter => self.apply_profile(),
+                _ => {}
+            },
+            InputMode::CommitFileSelect => match code {
+                KeyCode::Esc => {
+                    self.commit_file_choices.clear();
+                    self.commit_file_marked.clear();
+                    self.input_mode = InputMode::Insert;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.commit_file_choices.len();
+                    if len > 0 {
+                        let i = self.commit_file_state.selected().unwrap_or(0);
+                        self.commit_file_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.commit_file_choices.len();
+                    if len > 0 {
+                        let i = self.commit_file_state.selected().unwrap_or(0);
+                        self.commit_file_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(i) = self.commit_file_state.selected()
+                        && !self.commit_file_marked.remove(&i)
+                    {
+                        self.commit_file_marked.insert(i);
+                    }
                 }
+                KeyCode::Enter => self.commit_selected_files()?,
                 _ => {}
             },
-            InputMode::RemoteUrl => match code {
+            InputMode::CommitSizeWarning => match code {
+                // Back to the message; split the commit by unstaging files
+                KeyCode::Esc => self.back_out_of_commit(),
+                KeyCode::Enter => self.review_or_commit()?,
+                _ => {}
+            },
+            InputMode::CommitReview => match code {
+                KeyCode::Esc => self.back_out_of_commit(),
+                KeyCode::Enter => {
+                    self.commit_review.clear();
+                    self.commit_unchecked()?;
+                }
+                KeyCode::Char('j') | KeyCode::Down => self.scroll_commit_review(1),
+                KeyCode::Char('k') | KeyCode::Up => self.scroll_commit_review(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_commit_review(20),
+                KeyCode::PageUp => self.scroll_commit_review(-20),
+                _ => {}
+            },
+            InputMode::OutputView => match code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('o') => {
+                    // Back to the recovery prompt if it was opened from there
+                    self.input_mode = if self.recovery_actions.is_empty() {
+                        InputMode::Normal
+                    } else {
+                        InputMode::RecoveryPrompt
+                    };
+                }
+                KeyCode::Char('j') | KeyCode::Down => self.scroll_output(true),
+                KeyCode::Char('k') | KeyCode::Up => self.scroll_output(false),
+                _ => {}
+            },
+            InputMode::HunkView
+                if self
+                    .diff_view
+                    .as_ref()
+                    .is_some_and(|v| v.line_selection.is_some()) =>
+            {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('V') | KeyCode::Char('v') => {
+                        self.toggle_line_selection()
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => self.move_line_cursor(true),
+                    KeyCode::Char('k') | KeyCode::Up => self.move_line_cursor(false),
+                    KeyCode::Char(' ') => self.stage_selected_lines()?,
+                    _ => {}
+                }
+            }
+            InputMode::HunkView => match code {
+                KeyCode::Esc | KeyCode::Char('q') => self.close_hunk_view(),
+                KeyCode::Char('V') => self.toggle_line_selection(),
+                KeyCode::Char('j') | KeyCode::Down => self.hunk_view_next(),
+                KeyCode::Char('k') | KeyCode::Up => self.hunk_view_prev(),
+                KeyCode::Char('x') => self.open_discard_hunk_confirm(),
+                KeyCode::Char(' ') => self.toggle_hunk_stage()?,
+                KeyCode::Char('a') => self.expand_hunk_view(),
+                _ => {}
+            },
+            InputMode::LogSearch => match code {
                 KeyCode::Esc => {
                     self.input_mode = InputMode::Normal;
-                    self.remote_url.clear();
-                    self.message = Some(("Cancelled".to_string(), false));
+                    self.log_search_input.clear();
                 }
-                KeyCode::Enter => self.add_remote_and_push()?,
+                KeyCode::Enter => self.apply_log_search(),
                 KeyCode::Backspace => {
-                    self.remote_url.pop();
+                    self.log_search_input.pop();
                 }
-                KeyCode::Char(c) => self.remote_url.push(c),
+                KeyCode::Char(c) => self.log_search_input.push(c),
                 _ => {}
             },
-            InputMode::RepoSelect => match code {
-                KeyCode::Esc => self.input_mode = InputMode::Normal,
-                KeyCode::Enter => {
-                    if let Some(idx) = self.repo_select_state.selected()
-                        && let Some(path) = self.selector_path_at(idx)
-                    {
-                        if path != self.repo_path {
-                            self.switch_repo(path)?;
-                        } else {
-                            self.input_mode = InputMode::Normal;
+            InputMode::CommitDetail => match code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') | KeyCode::Left => {
+                    self.close_commit_detail()
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.commit_detail.as_ref().map_or(0, |d| d.files.len());
+                    if len > 0 {
+                        let i = self.commit_detail_state.selected().unwrap_or(0);
+                        self.commit_detail_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.commit_detail.as_ref().map_or(0, |d| d.files.len());
+                    if len > 0 {
+                        let i = self.commit_detail_state.selected().unwrap_or(0);
+                        self.commit_detail_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => {
+                    self.open_commit_file_diff()
+                }
+                KeyCode::Char('d') => {
+                    self.commit_detail = None;
+                    self.open_diff_confirm()?
+                }
+                KeyCode::Char(c @ ('1' | '2')) => {
+                    self.jump_to_parent(c.to_digit(10).unwrap_or(1) as usize);
+                    self.open_commit_detail()?
+                }
+                KeyCode::Char('p') => self.cycle_commit_diff_parent()?,
+                KeyCode::Char('y') => self.copy_commit_hash(false)?,
+                _ => {}
+            },
+            InputMode::CommitFileDiff => match code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') | KeyCode::Left => {
+                    self.commit_file_diff = None;
+                    self.input_mode = if self.incoming.is_some() {
+                        InputMode::IncomingView
+                    } else if self.comparison.is_some() {
+                        InputMode::CompareView
+                    } else {
+                        InputMode::CommitDetail
+                    };
+                }
+                _ => {
+                    if let Some(pager) = &mut self.commit_file_diff {
+                        match code {
+                            KeyCode::Char('j') | KeyCode::Down | KeyCode::Char(' ') => {
+                                pager.next_hunk()
+                            }
+                            KeyCode::Char('k') | KeyCode::Up => pager.prev_hunk(),
+                            KeyCode::Char('J') | KeyCode::Tab => pager.next_file(),
+                            KeyCode::Char('K') | KeyCode::BackTab => pager.prev_file(),
+                            _ => {}
                         }
                     }
                 }
-                KeyCode::Char('j') | KeyCode::Down => self.repo_select_next(),
-                KeyCode::Char('k') | KeyCode::Up => self.repo_select_prev(),
-                KeyCode::Char('a') => self.open_worktree_type_select(),
-                KeyCode::Char('x') => self.open_worktree_remove_confirm(),
+            },
+            InputMode::RemoveLockConfirm => match code {
+                KeyCode::Enter | KeyCode::Char('y') => self.remove_index_lock()?,
+                KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                    self.input_mode = InputMode::Normal
+                }
                 _ => {}
             },
-            InputMode::TagInput => match code {
-                KeyCode::Esc => {
+            InputMode::IncomingView => match code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.incoming = None;
                     self.input_mode = InputMode::Normal;
-                    self.tag_input.clear();
-                    self.editing_tag = None;
                 }
-                KeyCode::Enter => self.create_or_update_tag()?,
-                KeyCode::Backspace => {
-                    self.tag_input.pop();
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.incoming.as_ref().map_or(0, |i| i.files.len());
+                    if len > 0 {
+                        let i = self.incoming_state.selected().unwrap_or(0);
+                        self.incoming_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.incoming.as_ref().map_or(0, |i| i.files.len());
+                    if len > 0 {
+                        let i = self.incoming_state.selected().unwrap_or(0);
+                        self.incoming_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('l') => self.open_incoming_file_diff(),
+                KeyCode::Char('p') => {
+                    self.incoming = None;
+                    self.input_mode = InputMode::Normal;
+                    self.pull()?
                 }
-                KeyCode::Char(c) => self.tag_input.push(c),
                 _ => {}
             },
-            InputMode::VersionConfirm => match code {
-                KeyCode::Esc => {
+            InputMode::CompareView => match code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.comparison = None;
                     self.input_mode = InputMode::Normal;
-                    self.pending_version_update = None;
-                    self.tag_input.clear();
                 }
-                KeyCode::Enter => self.check_uncommitted_and_update_version()?,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.comparison.as_ref().map_or(0, |c| c.files.len());
+                    if len > 0 {
+                        let i = self.comparison_state.selected().unwrap_or(0);
+                        self.comparison_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.comparison.as_ref().map_or(0, |c| c.files.len());
+                    if len > 0 {
+                        let i = self.comparison_state.selected().unwrap_or(0);
+                        self.comparison_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('l') => self.open_comparison_file_diff(),
+                KeyCode::Char('y') => self.copy_comparison_command()?,
                 _ => {}
             },
-            InputMode::UncommittedWarning => match code {
-                KeyCode::Esc => {
+            InputMode::ReflogView => match code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.reflog.clear();
                     self.input_mode = InputMode::Normal;
-                    self.pending_version_update = None;
-                    self.tag_input.clear();
                 }
-                KeyCode::Enter => self.do_version_update_and_tag()?,
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.reflog.len();
+                    if len > 0 {
+                        let i = self.reflog_state.selected().unwrap_or(0);
+                        self.reflog_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.reflog.len();
+                    if len > 0 {
+                        let i = self.reflog_state.selected().unwrap_or(0);
+                        self.reflog_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Char('c') => self.checkout_reflog_entry()?,
+                KeyCode::Char('r') => self.open_reflog_reset_confirm(),
                 _ => {}
             },
-            InputMode::DiscardConfirm => match code {
+            InputMode::ReflogResetConfirm => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::ReflogView;
+                    self.pending_reflog_reset = None;
+                }
+                KeyCode::Enter => self.reflog_reset()?,
+                _ => {}
+            },
+            InputMode::CheckoutConfirm => match code {
                 KeyCode::Esc => {
                     self.input_mode = InputMode::Normal;
-                    self.pending_discard = None;
+                    self.pending_checkout = None;
                 }
-                KeyCode::Enter | KeyCode::Char('x') | KeyCode::Char('X') => {
-                    match &self.pending_discard {
-                        Some(PendingDiscardTarget::All(_)) => self.discard_all_changes()?,
-                        Some(PendingDiscardTarget::Single(_)) => self.discard_changes()?,
-                        None => {}
-                    }
+                KeyCode::Enter => self.checkout_commit()?,
+                KeyCode::Char('b') if self.checkout_return.is_some() => {
+                    self.checkout_return_branch()?
+                }
+                _ => {}
+            },
+            InputMode::UndoCommitConfirm => match code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.pending_undo_commit = None;
                 }
+                KeyCode::Enter => self.undo_commit()?,
                 _ => {}
             },
             InputMode::DeleteTagConfirm => match code {
@@ -2075,12 +8420,45 @@ impl App {
                 KeyCode::Char('l') => self.delete_tag(false)?, // Local only
                 _ => {}
             },
+            InputMode::TagDetail => match code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('a') | KeyCode::Enter => {
+                    self.input_mode = InputMode::Normal;
+                    self.tag_details.clear();
+                }
+                _ => {}
+            },
+            InputMode::TagsView => match code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.tags_view.clear();
+                    self.input_mode = InputMode::Normal;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    let len = self.tags_view.len();
+                    if len > 0 {
+                        let i = self.tags_view_state.selected().unwrap_or(0);
+                        self.tags_view_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    let len = self.tags_view.len();
+                    if len > 0 {
+                        let i = self.tags_view_state.selected().unwrap_or(0);
+                        self.tags_view_state
+                            .select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('l') => self.jump_to_tag(),
+                KeyCode::Char('s') => self.toggle_tags_sort(),
+                _ => {}
+            },
             InputMode::DiffConfirm => match code {
                 KeyCode::Esc => {
                     self.input_mode = InputMode::Normal;
                     self.pending_diff_command = None;
                 }
                 KeyCode::Enter => self.copy_diff_command()?,
+                KeyCode::Char('1') => self.copy_parent_diff_command(1)?,
+                KeyCode::Char('2') => self.copy_parent_diff_command(2)?,
                 _ => {}
             },
             InputMode::WorktreeTypeSelect => match code {
@@ -2207,36 +8585,121 @@ impl App {
                 _ => {}
             },
             InputMode::Normal => match code {
+                // Before the tab arms, which take plain `c`
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.running = false;
+                }
                 KeyCode::Char('q') => self.running = false,
                 KeyCode::Tab => self.toggle_tab(),
                 KeyCode::Char('j') | KeyCode::Down => self.select_next(),
                 KeyCode::Char('k') | KeyCode::Up => self.select_prev(),
+                KeyCode::Enter | KeyCode::Char('l')
+                    if self.tab == Tab::Log && self.on_incoming_row() =>
+                {
+                    self.open_incoming()?
+                }
+                KeyCode::Enter | KeyCode::Char('l') if self.tab == Tab::Log => {
+                    self.open_commit_detail()?
+                }
+                KeyCode::Enter if self.tab == Tab::Branches => self.checkout_selected_branch()?,
                 KeyCode::Enter => self.open_diff_confirm()?,
+                KeyCode::Char(' ') if self.tab == Tab::Files && !self.marked_files.is_empty() => {
+                    self.stage_marked()?
+                }
                 KeyCode::Char(' ') if self.tab == Tab::Files => self.stage_selected()?,
+                KeyCode::Char('v') if self.tab == Tab::Files => self.toggle_mark(),
+                KeyCode::Esc if !self.marked_files.is_empty() => self.marked_files.clear(),
                 KeyCode::Char('a') if self.tab == Tab::Files => self.stage_all()?,
-                KeyCode::Char('c') if self.tab == Tab::Files => {
-                    self.input_mode = InputMode::Insert;
-                }
+                KeyCode::Char('c') if self.tab == Tab::Files => self.open_commit_input(),
+                KeyCode::Char('E') if self.tab == Tab::Files => self.editor_requested = true,
                 KeyCode::Char('P') => self.push()?,
                 KeyCode::Char('p') if self.tab == Tab::Log => self.pull()?,
+                KeyCode::Char('f') if self.tab == Tab::Log => self.fetch(),
+                KeyCode::Char('i') if self.tab == Tab::Log => self.open_incoming()?,
+                KeyCode::Char('L') if self.tab == Tab::Log => self.open_reflog()?,
+                KeyCode::Char('d') if self.tab == Tab::Log => self.open_comparison()?,
+                KeyCode::Char('D') if self.tab == Tab::Log => self.cycle_date_format(),
+                KeyCode::Char('v') if self.tab == Tab::Log => self.revert_commit()?,
+                KeyCode::Char('r')
+                    if self.tab == Tab::Log && modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.revert_commit()?
+                }
                 KeyCode::Char('t') if self.tab == Tab::Log => self.open_tag_input(),
                 KeyCode::Char('T') if self.tab == Tab::Log => self.push_tags()?,
+                KeyCode::Char('a') if self.tab == Tab::Log => self.open_tag_detail(),
+                KeyCode::Char('g') if self.tab == Tab::Log => self.open_tags_view()?,
+                KeyCode::Char('x') if self.tab == Tab::Files && !self.marked_files.is_empty() => {
+                    self.open_discard_marked_confirm()
+                }
                 KeyCode::Char('x') if self.tab == Tab::Files => self.open_discard_confirm(),
                 KeyCode::Char('X') if self.tab == Tab::Files => self.open_discard_all_confirm(),
+                KeyCode::Char('D') if self.tab == Tab::Files => {
+                    self.open_delete_untracked_confirm()
+                }
+                KeyCode::Char('d') if self.tab == Tab::Files => self.open_hunk_view(),
+                KeyCode::Char('K') if self.tab == Tab::Files => self.open_quick_look(),
+                KeyCode::Char('B') if self.tab == Tab::Files => self.open_review()?,
+                KeyCode::Char('s') if self.tab == Tab::Files => self.open_stash_input(),
+                KeyCode::Char('i') if self.tab == Tab::Files => self.open_gitignore_input(),
+                KeyCode::Char('n') if self.tab == Tab::Files => self.open_move_input(),
+                KeyCode::Char('F') if self.tab == Tab::Files => self.open_changelog_input(),
+                KeyCode::Char('u') if self.tab == Tab::Files => self.restore_deleted()?,
+                KeyCode::Char('W') if self.tab == Tab::Files => self.wip_commit()?,
+                KeyCode::Char('U') if self.tab == Tab::Files => self.undo_wip_commit()?,
+                KeyCode::Char('g') if self.tab == Tab::Files => self.toggle_generated()?,
+                KeyCode::Char('N') if self.tab == Tab::Files => self.toggle_intent_to_add()?,
+                KeyCode::Char('H') if self.tab == Tab::Files => self.toggle_skip_worktree()?,
+                KeyCode::Char('S') if self.tab == Tab::Files => self.cycle_file_sort()?,
+                KeyCode::Char('f') if self.tab == Tab::Files => self.cycle_status_filter()?,
+                KeyCode::Esc
+                    if self.tab == Tab::Files && self.status_filter != StatusFilter::All =>
+                {
+                    self.status_filter = StatusFilter::All;
+                    self.refresh_status()?;
+                }
+                KeyCode::Char('a') if self.tab == Tab::Stash => self.stash_apply(false)?,
+                KeyCode::Char('p') if self.tab == Tab::Stash => self.stash_apply(true)?,
+                KeyCode::Char('x') if self.tab == Tab::Stash => self.open_stash_drop_confirm(),
+                KeyCode::Char('n') if self.tab == Tab::Branches => self.open_branch_create_input(),
+                KeyCode::Char('e') if self.tab == Tab::Branches => self.open_branch_rename_input(),
+                KeyCode::Char('x') if self.tab == Tab::Branches => {
+                    self.open_branch_delete_confirm()?
+                }
                 KeyCode::Char('x') if self.tab == Tab::Log => self.open_delete_tag_confirm(),
                 KeyCode::Char('e') if self.tab == Tab::Log => self.start_amend()?,
-                KeyCode::Char('y') if self.tab == Tab::Log => self.copy_commit_hash()?,
+                KeyCode::Char('u') if self.tab == Tab::Log => self.open_undo_commit_confirm(),
+                KeyCode::Char('c') if self.tab == Tab::Log => self.open_checkout_confirm()?,
+                KeyCode::Char('/') if self.tab == Tab::Log => self.open_log_search(),
+                KeyCode::Char('n') if self.tab == Tab::Log => self.search_log(true, false),
+                KeyCode::Char('N') if self.tab == Tab::Log => self.search_log(false, false),
+                KeyCode::Esc if self.tab == Tab::Log && self.log_search.is_some() => {
+                    self.log_search = None
+                }
+                KeyCode::Char('1') if self.tab == Tab::Log => self.jump_to_parent(1),
+                KeyCode::Char('2') if self.tab == Tab::Log => self.jump_to_parent(2),
+                KeyCode::Char('y') if self.tab == Tab::Files => self.copy_file_path()?,
+                KeyCode::Char('y') if self.tab == Tab::Log => self.copy_commit_hash(false)?,
+                KeyCode::Char('Y') if self.tab == Tab::Log => self.copy_commit_hash(true)?,
+                KeyCode::Char('s') if self.tab == Tab::Log => self.copy_commit_line()?,
+                KeyCode::Char('A') if self.tab == Tab::Log => self.toggle_all_branches()?,
+                KeyCode::Char(' ') if self.tab == Tab::Log => self.toggle_log_mark(),
+                KeyCode::Char('C') if self.tab == Tab::Log => self.cherry_pick_selected()?,
                 KeyCode::Char('C') => self.open_cherry_pick_input(),
                 KeyCode::Char('m') => self.open_branch_select(BranchSelectOp::Merge),
                 KeyCode::Char('b') => self.open_branch_select(BranchSelectOp::Rebase),
                 KeyCode::Char('r') => self.open_repo_select(),
+                KeyCode::Char('o') => self.open_output_view(),
+                KeyCode::Char('M') => self.toggle_mouse_capture(),
+                KeyCode::Char('I') => self.open_profile_select(),
+                KeyCode::Char('!') => self.open_remove_lock_confirm(),
+                KeyCode::Char('Q') => self.toggle_macro_recording(),
+                KeyCode::Char('@') => self.replay_macro()?,
+                KeyCode::Char('^') => self.toggle_parent_repo()?,
                 KeyCode::Char('R') => {
                     self.refresh()?;
                     self.message = Some(("Refreshed".to_string(), false));
                 }
-                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
-                    self.running = false;
-                }
                 _ => {}
             },
         }
@@ -2271,6 +8734,60 @@ impl App {
             .unwrap_or(self.commit_message.len())
     }
 
+    /// Byte range of the message line holding the cursor
+    fn cursor_line_bounds(&self) -> (usize, usize) {
+        let text = &self.commit_message;
+        let start = text[..self.cursor_pos].rfind('\n').map_or(0, |i| i + 1);
+        let end = text[self.cursor_pos..]
+            .find('\n')
+            .map_or(text.len(), |i| self.cursor_pos + i);
+        (start, end)
+    }
+
+    /// `[commit] wrap_body`: once the body line being typed passes the width,
+    /// turn its last space that fits into a line break (the subject never wraps)
+    fn wrap_body_line(&mut self) {
+        let Some(width) = self.wrap_body_width else {
+            return;
+        };
+        let (start, end) = self.cursor_line_bounds();
+        if start == 0 {
+            return;
+        }
+        if let Some(offset) = wrap_point(&self.commit_message[start..end], width) {
+            // A space and a newline are both one byte: the cursor stays put
+            self.commit_message
+                .replace_range(start + offset..start + offset + 1, "\n");
+        }
+    }
+
+    /// Same column on the previous or next line of the message (for Up/Down)
+    fn cursor_vertical(&self, down: bool) -> usize {
+        let text = &self.commit_message;
+        let (start, end) = self.cursor_line_bounds();
+        let column = text[start..self.cursor_pos].chars().count();
+        let (line_start, line_end) = if down {
+            if end >= text.len() {
+                return self.cursor_pos;
+            }
+            let next = end + 1;
+            (
+                next,
+                text[next..].find('\n').map_or(text.len(), |i| next + i),
+            )
+        } else {
+            if start == 0 {
+                return self.cursor_pos;
+            }
+            let prev_end = start - 1;
+            (text[..prev_end].rfind('\n').map_or(0, |i| i + 1), prev_end)
+        };
+        text[line_start..line_end]
+            .char_indices()
+            .nth(column)
+            .map_or(line_end, |(i, _)| line_start + i)
+    }
+
     // ========================================================================
     // List navigation helpers
     // ========================================================================
@@ -2279,6 +8796,8 @@ impl App {
         match self.tab {
             Tab::Files => self.visual_list.len(),
             Tab::Log => self.commits.len(),
+            Tab::Stash => self.stashes.len(),
+            Tab::Branches => self.branches.len(),
         }
     }
 
@@ -2286,10 +8805,27 @@ impl App {
         match self.tab {
             Tab::Files => &mut self.files_state,
             Tab::Log => &mut self.commits_state,
+            Tab::Stash => &mut self.stash_state,
+            Tab::Branches => &mut self.branches_state,
         }
     }
 
     fn select_next(&mut self) {
+        // Off the ↓ row onto the top commit
+        if self.tab == Tab::Log && self.incoming_row_selected {
+            self.incoming_row_selected = false;
+            if !self.commits.is_empty() {
+                self.commits_state.select(Some(0));
+            }
+            return;
+        }
+        // Past the last loaded commit: walk the next batch instead of wrapping
+        if self.tab == Tab::Log
+            && self.log_has_more
+            && self.commits_state.selected() == Some(self.commits.len().saturating_sub(1))
+        {
+            self.load_more_commits();
+        }
         let len = self.current_list_len();
         if len > 0 {
             let i = self.current_state().selected().unwrap_or(0);
@@ -2297,7 +8833,45 @@ impl App {
         }
     }
 
+    /// `A` in the Log tab: switch between HEAD's history and every branch's
+    fn toggle_all_branches(&mut self) -> Result<()> {
+        self.log_all_branches = !self.log_all_branches;
+        self.refresh_log_local()?;
+        self.commits_state
+            .select((!self.commits.is_empty()).then_some(0));
+        self.message = Some((
+            if self.log_all_branches {
+                "Log: all branches".to_string()
+            } else {
+                format!("Log: {} only", self.branch_name)
+            },
+            false,
+        ));
+        Ok(())
+    }
+
+    fn load_more_commits(&mut self) {
+        self.log_limit += LOG_BATCH;
+        match self.refresh_log_local() {
+            Ok(()) => {
+                self.message = Some((format!("Loaded {} commits", self.commits.len()), false))
+            }
+            Err(e) => self.message = Some((format!("Failed to load commits: {}", e), true)),
+        }
+    }
+
     fn select_prev(&mut self) {
+        // Above the top commit sits the ↓ row while the branch is behind
+        if self.tab == Tab::Log {
+            if self.incoming_row_selected {
+                self.incoming_row_selected = false;
+            } else if self.incoming_row().is_some()
+                && self.commits_state.selected().unwrap_or(0) == 0
+            {
+                self.select_incoming_row();
+                return;
+            }
+        }
         let len = self.current_list_len();
         if len > 0 {
             let i = self.current_state().selected().unwrap_or(0);
@@ -2308,10 +8882,69 @@ impl App {
 
     fn select_index(&mut self, index: usize) {
         if index < self.current_list_len() {
+            if self.tab == Tab::Log {
+                self.incoming_row_selected = false;
+            }
             self.current_state().select(Some(index));
         }
     }
 
+    pub fn is_recording_macro(&self) -> bool {
+        self.macro_recording.is_some()
+    }
+
+    /// `Q`: start recording keys, or stop and keep them for `@`
+    fn toggle_macro_recording(&mut self) {
+        match self.macro_recording.take() {
+            None => {
+                self.macro_recording = Some(Vec::new());
+                self.message = Some(("Recording keys (Q to stop)".to_string(), false));
+            }
+            Some(keys) if keys.is_empty() => {
+                self.message = Some(("Nothing recorded".to_string(), false));
+            }
+            Some(keys) => {
+                self.message = Some((format!("Recorded {} keys (@ to replay)", keys.len()), false));
+                self.recorded_macro = keys;
+            }
+        }
+    }
+
+    /// `@`: feed the recorded keys back in, stopping at the first error
+    fn replay_macro(&mut self) -> Result<()> {
+        if self.replaying_macro {
+            return Ok(());
+        }
+        if self.macro_recording.is_some() {
+            self.message = Some(("Stop recording (Q) before replaying".to_string(), true));
+            return Ok(());
+        }
+        if self.recorded_macro.is_empty() {
+            self.message = Some(("No keys recorded (Q to record)".to_string(), true));
+            return Ok(());
+        }
+        self.replaying_macro = true;
+        let mut result = Ok(());
+        for (code, modifiers) in self.recorded_macro.clone() {
+            result = self.handle_key(code, modifiers);
+            if result.is_err() || matches!(self.message, Some((_, true))) || !self.running {
+                break;
+            }
+        }
+        self.replaying_macro = false;
+        result
+    }
+
+    fn toggle_mouse_capture(&mut self) {
+        self.mouse_capture = !self.mouse_capture;
+        let msg = if self.mouse_capture {
+            "Mouse capture on"
+        } else {
+            "Mouse capture off: select text with the terminal"
+        };
+        self.message = Some((msg.to_string(), false));
+    }
+
     pub fn handle_mouse(&mut self, event: MouseEvent) -> Result<()> {
         match event.kind {
             MouseEventKind::ScrollDown => self.select_next(),
@@ -2327,8 +8960,14 @@ impl App {
     fn toggle_tab(&mut self) {
         self.tab = match self.tab {
             Tab::Files => Tab::Log,
-            Tab::Log => Tab::Files,
+            Tab::Log => Tab::Stash,
+            Tab::Stash => Tab::Branches,
+            Tab::Branches => Tab::Files,
         };
+        // The auto-refresh skips branches on the other tabs
+        if self.tab == Tab::Branches {
+            self.refresh_branches();
+        }
     }
 
     fn handle_click(&mut self, _x: u16, y: u16) -> Result<()> {
@@ -2346,22 +8985,37 @@ impl App {
 
         match self.tab {
             Tab::Files => {
-                if y >= 8 {
-                    let clicked_row = (y - 8) as usize;
+                // The commit box grows with multi-line messages
+                let extra = self
+                    .commit_message
+                    .split('\n')
+                    .count()
+                    .min(MAX_MESSAGE_LINES)
+                    - 1;
+                let list_top = 8 + extra as u16;
+                if y >= list_top {
+                    let clicked_row = (y - list_top) as usize;
                     let staged_count = self
                         .visual_list
                         .iter()
                         .filter(|&&idx| self.files.get(idx).is_some_and(|f| f.staged))
                         .count();
 
+                    // CHANGES header, plus the directory summary row when shown
+                    let changes_rows = if self.changes_directory_totals().is_some() {
+                        2
+                    } else {
+                        1
+                    };
+
                     let visual_index = if clicked_row == 0 {
                         None
                     } else if clicked_row <= staged_count {
                         Some(clicked_row - 1)
-                    } else if clicked_row == staged_count + 1 {
+                    } else if clicked_row <= staged_count + changes_rows {
                         None
                     } else {
-                        Some(staged_count + (clicked_row - staged_count - 2))
+                        Some(staged_count + (clicked_row - staged_count - 1 - changes_rows))
                     };
 
                     if let Some(idx) = visual_index
@@ -2371,7 +9025,9 @@ impl App {
                     }
                 }
             }
-            Tab::Log => {
+            // The row above the commits is the ↓ row when the branch is behind
+            Tab::Log if y == 5 && self.incoming_row().is_some() => self.select_incoming_row(),
+            Tab::Log | Tab::Stash | Tab::Branches => {
                 if y >= 6 {
                     let clicked_row = (y - 6) as usize;
                     self.select_index(clicked_row / 2);
@@ -2382,51 +9038,126 @@ impl App {
     }
 }
 
-/// Copy text to clipboard (cross-platform)
-#[allow(clippy::needless_return)]
-fn copy_to_clipboard(text: &str) -> Result<()> {
+/// Copy text to the clipboard with the configured backend
+///
+/// `Auto` prefers OSC 52 over SSH, where local tools would only reach the
+/// remote machine's clipboard, and falls back to it when no tool works.
+fn copy_to_clipboard(text: &str, backend: ClipboardBackend) -> Result<()> {
+    let over_ssh =
+        std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+    match backend {
+        ClipboardBackend::Osc52 => copy_with_osc52(text),
+        ClipboardBackend::System => copy_with_system_tool(text),
+        ClipboardBackend::Auto if over_ssh => copy_with_osc52(text),
+        ClipboardBackend::Auto => copy_with_system_tool(text).or_else(|_| copy_with_osc52(text)),
+    }
+}
+
+fn copy_with_system_tool(text: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let tools: &[(&str, &[&str])] = &[("pbcopy", &[])];
+    #[cfg(target_os = "linux")]
+    let tools: &[(&str, &[&str])] = &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let tools: &[(&str, &[&str])] = &[];
+
+    if tools
+        .iter()
+        .any(|(program, args)| pipe_to_clipboard_tool(program, args, text))
+    {
+        Ok(())
+    } else {
+        anyhow::bail!("No working clipboard tool found")
+    }
+}
+
+/// Feed `text` to a clipboard command; false if it is missing or fails
+/// (e.g. xclip without a display)
+fn pipe_to_clipboard_tool(program: &str, args: &[&str], text: &str) -> bool {
     use std::io::Write;
     use std::process::{Command, Stdio};
 
-    #[cfg(target_os = "macos")]
-    {
-        let mut child = Command::new("pbcopy").stdin(Stdio::piped()).spawn()?;
-        if let Some(stdin) = child.stdin.as_mut() {
-            stdin.write_all(text.as_bytes())?;
-        }
-        child.wait()?;
-        return Ok(());
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|status| status.success()) && written
+}
+
+/// Ask the terminal to set its clipboard; works through SSH
+fn copy_with_osc52(text: &str) -> Result<()> {
+    use std::io::Write;
+
+    let in_tmux = std::env::var_os("TMUX").is_some();
+    let mut out = std::io::stdout();
+    out.write_all(osc52_sequence(text, in_tmux).as_bytes())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// OSC 52 escape sequence, wrapped in tmux's passthrough when needed
+fn osc52_sequence(text: &str, in_tmux: bool) -> String {
+    let osc = format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()));
+    if in_tmux {
+        format!("\x1bPtmux;{}\x1b\\", osc.replace('\x1b', "\x1b\x1b"))
+    } else {
+        osc
     }
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        if let Ok(mut child) = Command::new("xclip")
-            .args(["-selection", "clipboard"])
-            .stdin(Stdio::piped())
-            .spawn()
-        {
-            if let Some(stdin) = child.stdin.as_mut() {
-                stdin.write_all(text.as_bytes())?;
-            }
-            child.wait()?;
-            return Ok(());
-        }
-        if let Ok(mut child) = Command::new("xsel")
-            .args(["--clipboard", "--input"])
-            .stdin(Stdio::piped())
-            .spawn()
-        {
-            if let Some(stdin) = child.stdin.as_mut() {
-                stdin.write_all(text.as_bytes())?;
+/// Standard base64 with padding
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
             }
-            child.wait()?;
-            return Ok(());
         }
-        anyhow::bail!("No clipboard tool found (xclip or xsel)");
     }
+    out
+}
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-    anyhow::bail!("Clipboard not supported on this platform")
+fn delta_path(delta: &git2::DiffDelta) -> String {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn delta_status_char(status: git2::Delta) -> char {
+    match status {
+        git2::Delta::Added => 'A',
+        git2::Delta::Deleted => 'D',
+        git2::Delta::Renamed => 'R',
+        git2::Delta::Copied => 'C',
+        git2::Delta::Typechange => 'T',
+        _ => 'M',
+    }
 }
 
 fn command_error(output: &std::process::Output, default: &str) -> String {
@@ -2444,15 +9175,17 @@ fn command_error(output: &std::process::Output, default: &str) -> String {
     }
 }
 
-pub fn execute_pending_discard_with<FRestore, FTrash>(
+pub fn execute_pending_discard_with<FRestore, FTrash, FDelete>(
     repo_path: &Path,
     pending: &PendingDiscard,
     mut restore: FRestore,
     mut trash: FTrash,
+    mut delete: FDelete,
 ) -> std::result::Result<String, String>
 where
     FRestore: FnMut(&Path, &str) -> std::result::Result<(), String>,
-    FTrash: FnMut(&Path, &str) -> std::result::Result<(), String>,
+    FTrash: FnMut(&Path, &str) -> std::result::Result<String, String>,
+    FDelete: FnMut(&Path, &str) -> std::result::Result<(), String>,
 {
     match pending.action {
         PendingDiscardAction::RestoreTracked => {
@@ -2460,8 +9193,12 @@ where
             Ok(format!("Discarded: {}", pending.path))
         }
         PendingDiscardAction::TrashUntracked => {
-            trash(repo_path, &pending.path)?;
-            Ok(format!("Moved to trash: {}", pending.path))
+            let location = trash(repo_path, &pending.path)?;
+            Ok(format!("Moved to {}: {}", location, pending.path))
+        }
+        PendingDiscardAction::DeleteUntracked => {
+            delete(repo_path, &pending.path)?;
+            Ok(format!("Deleted: {}", pending.path))
         }
     }
 }
@@ -2483,9 +9220,372 @@ fn run_restore_command(repo_path: &Path, path: &str) -> std::result::Result<(),
     }
 }
 
-fn move_to_trash(repo_path: &Path, path: &str) -> std::result::Result<(), String> {
+/// Discard one unstaged hunk by applying its reverse to the working tree.
+/// `hunk_index` counts hunks in the same order as `diff_index_to_workdir` produces them;
+/// `force_text` must match how those hunks were loaded.
+fn discard_hunk_in_workdir(
+    repo: &Repository,
+    path: &str,
+    hunk_index: usize,
+    force_text: bool,
+) -> std::result::Result<(), String> {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path).reverse(true).force_text(force_text);
+    repo.diff_index_to_workdir(None, Some(&mut opts))
+        .and_then(|diff| apply_single_hunk(repo, &diff, git2::ApplyLocation::WorkDir, hunk_index))
+        .map_err(|e| format!("Discard hunk failed: {}", e.message()))
+}
+
+/// Apply one unstaged hunk of `path` to the index
+fn stage_hunk_in_index(
+    repo: &Repository,
+    path: &str,
+    hunk_index: usize,
+    force_text: bool,
+) -> std::result::Result<(), String> {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path).force_text(force_text);
+    repo.diff_index_to_workdir(None, Some(&mut opts))
+        .and_then(|diff| apply_single_hunk(repo, &diff, git2::ApplyLocation::Index, hunk_index))
+        .map_err(|e| format!("Stage hunk failed: {}", e.message()))
+}
+
+/// Files changed between two trees with their +/− counts, renames detected
+fn diff_file_stats(
+    repo: &Repository,
+    old: &git2::Tree,
+    new: &git2::Tree,
+) -> std::result::Result<Vec<CommitDetailFile>, git2::Error> {
+    let mut diff = repo.diff_tree_to_tree(Some(old), Some(new), None)?;
+    let _ = diff.find_similar(None);
+    Ok((0..diff.deltas().len())
+        .filter_map(|idx| git2::Patch::from_diff(&diff, idx).ok().flatten())
+        .map(|patch| {
+            let binary = patch.delta().flags().is_binary();
+            CommitDetailFile {
+                path: delta_path(&patch.delta()),
+                status: delta_status_char(patch.delta().status()),
+                stats: patch
+                    .line_stats()
+                    .ok()
+                    .filter(|_| !binary)
+                    .map(|(_, add, del)| (add, del)),
+            }
+        })
+        .collect())
+}
+
+/// Byte offset of the space to break `line` at so the first part fits in
+/// `width` columns; None when it already fits or has no such space
+pub fn wrap_point(line: &str, width: usize) -> Option<usize> {
+    use unicode_width::UnicodeWidthStr;
+    if line.width() <= width {
+        return None;
+    }
+    line.char_indices()
+        .rev()
+        .find(|&(i, c)| c == ' ' && i > 0 && line[..i].width() <= width)
+        .map(|(i, _)| i)
+}
+
+/// "TO" for "Takuma Ogura": first letters of the first and last words, or the
+/// first two letters of a single word
+pub fn author_initials(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let initials: String = match words.as_slice() {
+        [] => "?".to_string(),
+        [word] => word.chars().take(2).collect(),
+        [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+    };
+    initials.to_uppercase()
+}
+
+/// Look up `refs/tags/<name>`: annotated tags carry their own tagger, date and
+/// message, lightweight ones only point at a commit
+pub fn tag_detail(repo: &Repository, name: &str, pushed: bool) -> Option<TagDetail> {
+    let reference = repo.find_reference(&format!("refs/tags/{}", name)).ok()?;
+    let commit = reference.peel_to_commit().ok()?;
+    let date = |seconds: i64| {
+        format!(
+            "{} ({})",
+            date::format_utc(seconds),
+            format_relative_time(seconds)
+        )
+    };
+    let target = format!("{:.7} {}", commit.id(), commit.summary().unwrap_or(""));
+    let annotated = reference.peel_to_tag().ok();
+    let (tagger, when, annotation) = match annotated {
+        Some(tag) => (
+            tag.tagger().map(|sig| {
+                format!(
+                    "{} <{}>",
+                    sig.name().unwrap_or("?"),
+                    sig.email().unwrap_or("?")
+                )
+            }),
+            tag.tagger()
+                .map_or(commit.time().seconds(), |sig| sig.when().seconds()),
+            tag.message()
+                .and_then(|m| m.lines().find(|l| !l.trim().is_empty()))
+                .map(|l| l.trim().to_string()),
+        ),
+        None => (None, commit.time().seconds(), None),
+    };
+    Some(TagDetail {
+        name: name.to_string(),
+        target_id: commit.id(),
+        target,
+        tagger,
+        date: date(when),
+        timestamp: when,
+        annotation,
+        pushed,
+    })
+}
+
+/// Revert one staged hunk of `path` in the index, leaving the working tree alone
+fn unstage_hunk_in_index(
+    repo: &Repository,
+    path: &str,
+    hunk_index: usize,
+    force_text: bool,
+) -> std::result::Result<(), String> {
+    let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    let mut opts = DiffOptions::new();
+    opts.pathspec(path).reverse(true).force_text(force_text);
+    repo.diff_tree_to_index(head.as_ref(), None, Some(&mut opts))
+        .and_then(|diff| apply_single_hunk(repo, &diff, git2::ApplyLocation::Index, hunk_index))
+        .map_err(|e| format!("Unstage hunk failed: {}", e.message()))
+}
+
+/// Apply only the `hunk_index`-th hunk of `diff`
+fn apply_single_hunk(
+    repo: &Repository,
+    diff: &git2::Diff,
+    location: git2::ApplyLocation,
+    hunk_index: usize,
+) -> std::result::Result<(), git2::Error> {
+    let mut current = 0usize;
+    let mut apply_opts = git2::ApplyOptions::new();
+    apply_opts.hunk_callback(|_| {
+        let keep = current == hunk_index;
+        current += 1;
+        keep
+    });
+    repo.apply(diff, location, Some(&mut apply_opts))
+}
+
+/// Marked entries split into paths to stage and paths to unstage.
+/// Hidden (skip-worktree) entries are left alone.
+pub fn split_marked_for_staging(
+    files: &[FileEntry],
+    marked: &HashSet<(String, bool)>,
+) -> (Vec<String>, Vec<String>) {
+    let mut to_stage = Vec::new();
+    let mut to_unstage = Vec::new();
+    for file in files {
+        if file.hidden.is_some() || !marked.contains(&(file.path.clone(), file.staged)) {
+            continue;
+        }
+        if file.staged {
+            to_unstage.push(file.path.clone());
+        } else {
+            to_stage.push(file.path.clone());
+        }
+    }
+    (to_stage, to_unstage)
+}
+
+/// Sort rank for `FileSort::Status`: conflicts first, untracked last
+fn status_rank(status: FileStatus) -> u8 {
+    match status {
+        FileStatus::Conflicted => 0,
+        FileStatus::Modified => 1,
+        FileStatus::Added => 2,
+        FileStatus::Deleted => 3,
+        FileStatus::Untracked => 4,
+    }
+}
+
+/// Conflict regions left in a file: lines opening a `<<<<<<<` marker
+fn count_conflict_markers(path: &Path) -> usize {
+    std::fs::read(path)
+        .map(|content| {
+            content
+                .split(|&b| b == b'\n')
+                .filter(|line| line.starts_with(b"<<<<<<< ") || *line == b"<<<<<<<")
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Which hide flag (if any) is set on an index entry
+fn hidden_flag(entry: &git2::IndexEntry) -> Option<HiddenFlag> {
+    let extended = git2::IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended);
+    if extended.contains(git2::IndexEntryExtendedFlag::SKIP_WORKTREE) {
+        Some(HiddenFlag::SkipWorktree)
+    } else if git2::IndexEntryFlag::from_bits_truncate(entry.flags)
+        .contains(git2::IndexEntryFlag::VALID)
+    {
+        Some(HiddenFlag::AssumeUnchanged)
+    } else {
+        None
+    }
+}
+
+/// Whether an index entry was added with `git add -N`
+fn is_intent_to_add(entry: &git2::IndexEntry) -> bool {
+    git2::IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended)
+        .contains(git2::IndexEntryExtendedFlag::INTENT_TO_ADD)
+}
+
+/// Remove a single untracked file or directory (`git clean` for one path)
+fn run_clean_command(repo_path: &Path, path: &str) -> std::result::Result<(), String> {
+    let output = std::process::Command::new("git")
+        .current_dir(repo_path)
+        .args(["clean", "-f", "-d", "--", path])
+        .output()
+        .map_err(|e| format!("Delete failed: {e}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Delete failed: {}",
+            command_error(&output, "git clean failed")
+        ))
+    }
+}
+
+/// Ok names where the file went, for the status message
+fn move_to_trash(repo_path: &Path, path: &str) -> std::result::Result<String, String> {
     let full_path = repo_path.join(path);
-    trash::delete(&full_path).map_err(|e| format!("Move to trash failed: {e}"))
+    // Headless machines and some network filesystems have no OS trash
+    match trash::delete(&full_path) {
+        Ok(()) => Ok("the system trash".to_string()),
+        Err(e) => move_to_repo_trash(repo_path, path)
+            .map(|bin| {
+                bin.strip_prefix(repo_path)
+                    .unwrap_or(&bin)
+                    .display()
+                    .to_string()
+            })
+            .map_err(|fallback| format!("Move to trash failed: {e}; {fallback}")),
+    }
+}
+
+/// Fallback trash inside the repository: `.git/siori/trash/<unix time>/<path>`,
+/// with `-2`, `-3`... after the time when that second already holds the path.
+/// Ok holds the time-stamped directory the path went into
+fn move_to_repo_trash(repo_path: &Path, path: &str) -> std::result::Result<PathBuf, String> {
+    let git_dir = Repository::open(repo_path)
+        .map(|repo| repo.path().to_path_buf())
+        .unwrap_or_else(|_| repo_path.join(".git"));
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = path.trim_end_matches('/');
+    let trash = git_dir.join("siori").join("trash");
+    let bin = (1..)
+        .map(|n| match n {
+            1 => trash.join(stamp.to_string()),
+            _ => trash.join(format!("{}-{}", stamp, n)),
+        })
+        .find(|bin| bin.join(path).symlink_metadata().is_err())
+        .expect("some suffix is free");
+    let dest = bin.join(path);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("can't create {}: {e}", parent.display()))?;
+    }
+    std::fs::rename(repo_path.join(path), &dest)
+        .map_err(|e| format!("can't move to {}: {e}", dest.display()))?;
+    Ok(bin)
+}
+
+/// Sum (files, insertions, deletions) over file entries
+pub fn diff_totals<'a>(files: impl Iterator<Item = &'a FileEntry>) -> (usize, usize, usize) {
+    files.fold((0, 0, 0), |(n, add, del), f| {
+        let (a, d) = f.diff_stats.unwrap_or((0, 0));
+        (n + 1, add + a, del + d)
+    })
+}
+
+/// Blob id of a worktree file without writing it (a symlink hashes its target); None when missing
+fn worktree_hash(path: &Path) -> Option<git2::Oid> {
+    let meta = path.symlink_metadata().ok()?;
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(path).ok()?;
+        return git2::Oid::hash_object(
+            git2::ObjectType::Blob,
+            target.as_os_str().as_encoded_bytes(),
+        )
+        .ok();
+    }
+    git2::Oid::hash_file(git2::ObjectType::Blob, path).ok()
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// A diff as unified patch text, file headers included
+fn patch_text(diff: &git2::Diff) -> String {
+    let mut text = String::new();
+    let _ = diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            text.push(line.origin());
+        }
+        text.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    });
+    text
+}
+
+/// Insertions/deletions grouped by top-level directory ("./" for files at
+/// the root), largest first
+pub fn directory_totals<'a>(
+    files: impl Iterator<Item = &'a FileEntry>,
+) -> Vec<(String, usize, usize)> {
+    let mut totals: Vec<(String, usize, usize)> = Vec::new();
+    for file in files {
+        let dir = match file.path.split_once('/') {
+            Some((top, _)) => format!("{}/", top),
+            None => "./".to_string(),
+        };
+        let (add, del) = file.diff_stats.unwrap_or((0, 0));
+        match totals.iter_mut().find(|(d, _, _)| *d == dir) {
+            Some(entry) => {
+                entry.1 += add;
+                entry.2 += del;
+            }
+            None => totals.push((dir, add, del)),
+        }
+    }
+    totals.sort_by(|a, b| (b.1 + b.2).cmp(&(a.1 + a.2)).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+/// Append a pattern line to .gitignore content.
+/// Returns None when the exact pattern is already listed.
+pub fn append_gitignore_entry(content: &str, pattern: &str) -> Option<String> {
+    if content.lines().any(|line| line.trim() == pattern) {
+        return None;
+    }
+    let mut updated = content.to_string();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(pattern);
+    updated.push('\n');
+    Some(updated)
 }
 
 /// Normalize full-width ASCII characters to half-width (for Japanese IME support)
@@ -2499,21 +9599,151 @@ pub fn normalize_fullwidth(c: char) -> char {
     }
 }
 
+/// Tidy a typed message for `git commit -m`: no trailing whitespace, no blank
+/// lines at either end, and exactly one blank line between subject and body
+pub fn normalize_commit_message(message: &str) -> String {
+    let lines: Vec<&str> = message.lines().map(str::trim_end).collect();
+    let mut lines = lines.iter().copied().skip_while(|l| l.is_empty());
+    let Some(subject) = lines.next() else {
+        return String::new();
+    };
+    let mut body: Vec<&str> = lines.skip_while(|l| l.is_empty()).collect();
+    while body.last().is_some_and(|l| l.is_empty()) {
+        body.pop();
+    }
+    if body.is_empty() {
+        subject.trim_start().to_string()
+    } else {
+        format!("{}\n\n{}", subject.trim_start(), body.join("\n"))
+    }
+}
+
+/// Characters in the subject line, as counted by the 50/72 convention
+pub fn subject_length(message: &str) -> usize {
+    message.lines().next().unwrap_or("").chars().count()
+}
+
+/// `feat` + `ui` → "feat(ui): ", an empty scope leaves the parentheses out
+pub fn conventional_prefix(kind: &str, scope: &str) -> String {
+    let scope = scope.trim();
+    if scope.is_empty() {
+        format!("{}: ", kind)
+    } else {
+        format!("{}({}): ", kind, scope)
+    }
+}
+
+/// "Jane Doe <jane@example.com>" → "jane@example.com" (the whole string without brackets)
+fn person_email(person: &str) -> &str {
+    person
+        .rsplit_once('<')
+        .and_then(|(_, rest)| rest.strip_suffix('>'))
+        .unwrap_or(person)
+}
+
+/// Add trailer lines at the end of a commit message, after a blank line unless the
+/// message already ends with a trailer block; trailers already present are skipped
+pub fn append_trailers(message: &str, trailers: &[String]) -> String {
+    let message = message.trim_end();
+    let new: Vec<&String> = trailers
+        .iter()
+        .filter(|t| !message.lines().any(|l| l.trim() == t.as_str()))
+        .collect();
+    if new.is_empty() {
+        return message.to_string();
+    }
+    let is_trailer = |line: &str| {
+        line.split_once(": ")
+            .is_some_and(|(token, _)| !token.is_empty() && !token.contains(' '))
+    };
+    let last_paragraph: Vec<&str> = message
+        .rsplit("\n\n")
+        .next()
+        .unwrap_or("")
+        .lines()
+        .collect();
+    // A lone subject line like "fix: typo" is not a trailer block
+    let ends_with_trailers = message.contains("\n\n")
+        && !last_paragraph.is_empty()
+        && last_paragraph.iter().all(|l| is_trailer(l));
+    let mut out = message.to_string();
+    out.push_str(if ends_with_trailers { "\n" } else { "\n\n" });
+    out.push_str(
+        &new.iter()
+            .map(|t| t.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    out
+}
+
+/// Drop `#` comment lines and everything below a `git commit -v` scissors line
+pub fn strip_comment_lines(text: &str) -> String {
+    text.lines()
+        .take_while(|l| !l.starts_with("# ------------------------ >8 ------------------------"))
+        .filter(|l| !l.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Files of a merge's combined diff (`git show --cc`): those that differ from
+/// every parent, with lines added or removed against any of them
+fn combined_diff_files(text: &str) -> Vec<CommitDetailFile> {
+    diff_viewer::parse_diff_files(text)
+        .into_iter()
+        .map(|file| {
+            let (mut add, mut del) = (0, 0);
+            for hunk in &file.hunks {
+                for line in &hunk.lines {
+                    match diff_viewer::change_marker(hunk, line) {
+                        Some('+') => add += 1,
+                        Some('-') => del += 1,
+                        _ => {}
+                    }
+                }
+            }
+            CommitDetailFile {
+                path: file.path,
+                status: 'M',
+                stats: Some((add, del)),
+            }
+        })
+        .collect()
+}
+
+/// Files changed, insertions and deletions of a commit against its first parent
+fn commit_diff_stats(repo: &Repository, id: git2::Oid) -> Option<DiffStats> {
+    let commit = repo.find_commit(id).ok()?;
+    let parent = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let diff = repo
+        .diff_tree_to_tree(parent.as_ref(), Some(&commit.tree().ok()?), None)
+        .ok()?;
+    let stats = diff.stats().ok()?;
+    Some((stats.files_changed(), stats.insertions(), stats.deletions()))
+}
+
+/// Modification time of FETCH_HEAD (shared by all worktrees), None before the first fetch
+fn last_fetch_time(repo: &Repository) -> Option<i64> {
+    let modified = std::fs::metadata(repo.commondir().join("FETCH_HEAD"))
+        .ok()?
+        .modified()
+        .ok()?;
+    modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
 pub fn format_relative_time(timestamp: i64) -> String {
+    format_relative_time_in(timestamp, i18n::Locale::current())
+}
+
+fn format_relative_time_in(timestamp: i64, locale: i18n::Locale) -> String {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_secs() as i64)
         .unwrap_or(0);
-    let diff = now - timestamp;
-    if diff < 60 {
-        "just now".to_string()
-    } else if diff < 3600 {
-        format!("{} min ago", diff / 60)
-    } else if diff < 86400 {
-        format!("{} hours ago", diff / 3600)
-    } else {
-        format!("{} days ago", diff / 86400)
-    }
+    i18n::humanize_ago(now - timestamp, locale)
 }
 
 // ============================================================================
@@ -2642,49 +9872,337 @@ pub fn detect_repos(base: &std::path::Path) -> Vec<PathBuf> {
         }
     }
 
-    repos.sort();
-    repos
-}
+    repos.sort();
+    repos
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_default() {
+        assert_eq!(Tab::default(), Tab::Files);
+    }
+
+    #[test]
+    fn test_input_mode_default() {
+        assert_eq!(InputMode::default(), InputMode::Normal);
+    }
+
+    #[test]
+    fn test_format_relative_time() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let en = |timestamp| format_relative_time_in(timestamp, i18n::Locale::En);
+        assert_eq!(en(now), "just now");
+        assert_eq!(en(now - 120), "2 min ago");
+        assert_eq!(en(now - 7200), "2 hours ago");
+        assert_eq!(en(now - 172800), "2 days ago");
+        assert_eq!(
+            format_relative_time_in(now - 120, i18n::Locale::Ja),
+            "2分前"
+        );
+    }
+
+    #[test]
+    fn test_means_now() {
+        assert!(means_now("now"));
+        assert!(means_now(" Now "));
+        assert!(means_now(""));
+        assert!(means_now("0 seconds ago"));
+        assert!(!means_now("yesterdy"));
+        assert!(!means_now("10 seconds ago"));
+    }
+
+    #[test]
+    fn test_file_status_display() {
+        let file = FileEntry {
+            path: "test.rs".to_string(),
+            status: FileStatus::Added,
+            staged: true,
+            diff_stats: Some((10, 5)),
+            text_issues: Vec::new(),
+            intent_to_add: false,
+            hidden: None,
+            mode_change: None,
+            binary_size: None,
+            symlink_target: None,
+            nested_repo: false,
+            generated: false,
+            conflicts: None,
+        };
+        assert_eq!(file.path, "test.rs");
+        assert!(file.staged);
+    }
+
+    #[test]
+    fn test_diff_totals() {
+        let entry = |stats| FileEntry {
+            path: "f".to_string(),
+            status: FileStatus::Modified,
+            staged: true,
+            diff_stats: stats,
+            text_issues: Vec::new(),
+            intent_to_add: false,
+            hidden: None,
+            mode_change: None,
+            binary_size: None,
+            symlink_target: None,
+            nested_repo: false,
+            generated: false,
+            conflicts: None,
+        };
+        let files = [entry(Some((3, 1))), entry(Some((2, 0))), entry(None)];
+        assert_eq!(diff_totals(files.iter()), (3, 5, 1));
+    }
+
+    #[test]
+    fn test_directory_totals() {
+        let entry = |path: &str, stats| FileEntry {
+            path: path.to_string(),
+            status: FileStatus::Modified,
+            staged: false,
+            diff_stats: stats,
+            text_issues: Vec::new(),
+            intent_to_add: false,
+            hidden: None,
+            mode_change: None,
+            binary_size: None,
+            symlink_target: None,
+            nested_repo: false,
+            generated: false,
+            conflicts: None,
+        };
+        let files = [
+            entry("README.md", Some((1, 1))),
+            entry("src/app.rs", Some((10, 2))),
+            entry("src/ui/mod.rs", Some((5, 0))),
+            entry("test/", None),
+        ];
+        assert_eq!(
+            directory_totals(files.iter()),
+            vec![
+                ("src/".to_string(), 15, 2),
+                ("./".to_string(), 1, 1),
+                ("test/".to_string(), 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_summary_line() {
+        let rejected = "To github.com:me/repo.git\n ! [rejected]        main -> main (non-fast-forward)\nerror: failed to push some refs";
+        assert_eq!(
+            error_summary_line(rejected),
+            "! [rejected]        main -> main (non-fast-forward)"
+        );
+        let clean = "On branch main\nnothing to commit, working tree clean\n";
+        assert_eq!(
+            error_summary_line(clean),
+            "nothing to commit, working tree clean"
+        );
+        assert_eq!(error_summary_line(""), "");
+        assert_eq!(summary_line("Push failed: x\nraw output"), "Push failed: x");
+    }
+
+    #[test]
+    fn test_normalize_commit_message() {
+        assert_eq!(normalize_commit_message("  fix: typo  "), "fix: typo");
+        assert_eq!(
+            normalize_commit_message("feat: x\nbody line\n"),
+            "feat: x\n\nbody line"
+        );
+        assert_eq!(
+            normalize_commit_message("\nfeat: x\n\n\n- a  \n\n- b\n\n"),
+            "feat: x\n\n- a\n\n- b"
+        );
+        assert_eq!(normalize_commit_message(" \n "), "");
+    }
+
+    #[test]
+    fn test_subject_length() {
+        assert_eq!(subject_length("fix: typo\n\nbody"), 9);
+        assert_eq!(subject_length("修正"), 2);
+        assert_eq!(subject_length(""), 0);
+    }
+
+    #[test]
+    fn test_conventional_prefix() {
+        assert_eq!(conventional_prefix("feat", "ui"), "feat(ui): ");
+        assert_eq!(conventional_prefix("fix", " "), "fix: ");
+    }
+
+    #[test]
+    fn test_append_trailers() {
+        let jane = "Co-authored-by: Jane <jane@example.com>".to_string();
+        let bob = "Co-authored-by: Bob <bob@example.com>".to_string();
+        assert_eq!(
+            append_trailers("fix: typo", std::slice::from_ref(&jane)),
+            format!("fix: typo\n\n{}", jane)
+        );
+        assert_eq!(
+            append_trailers(
+                &format!("feat: x\n\nbody\n\n{}\n", jane),
+                &[jane.clone(), bob.clone()]
+            ),
+            format!("feat: x\n\nbody\n\n{}\n{}", jane, bob)
+        );
+        assert_eq!(person_email("Jane <jane@example.com>"), "jane@example.com");
+    }
+
+    #[test]
+    fn test_strip_comment_lines() {
+        assert_eq!(
+            strip_comment_lines("feat: x\n# On branch main\n\nbody\n#\tmodified: a.rs\n"),
+            "feat: x\n\nbody"
+        );
+        assert_eq!(
+            strip_comment_lines(
+                "fix\n# ------------------------ >8 ------------------------\ndiff --git a b\n"
+            ),
+            "fix"
+        );
+        assert_eq!(strip_comment_lines("# only comments\n"), "");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(
+            base64_encode("src/日本.rs".as_bytes()),
+            "c3JjL+aXpeacrC5ycw=="
+        );
+        assert_eq!(osc52_sequence("foo", false), "\x1b]52;c;Zm9v\x07");
+        assert_eq!(
+            osc52_sequence("foo", true),
+            "\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_suggest_recovery() {
+        let rejected = "! [rejected]        main -> main (non-fast-forward)";
+        assert_eq!(
+            suggest_recovery(&Processing::Pushing, rejected, false),
+            vec![
+                RecoveryAction::PullRebaseThenPush,
+                RecoveryAction::ForceWithLease
+            ]
+        );
+        assert!(suggest_recovery(&Processing::Pushing, "fatal: no route", false).is_empty());
+
+        let hook = "lint failed: src/main.rs";
+        assert_eq!(
+            suggest_recovery(&Processing::Committing, hook, true),
+            vec![RecoveryAction::CommitNoVerify]
+        );
+        assert!(suggest_recovery(&Processing::Committing, hook, false).is_empty());
+        assert!(suggest_recovery(&Processing::Committing, "nothing to commit", true).is_empty());
+        assert_eq!(
+            suggest_recovery(&Processing::Releasing, rejected, false),
+            vec![RecoveryAction::RollbackRelease]
+        );
+    }
+
+    #[test]
+    fn test_append_gitignore_entry() {
+        assert_eq!(
+            append_gitignore_entry("", "dist/"),
+            Some("dist/\n".to_string())
+        );
+        assert_eq!(
+            append_gitignore_entry("target/", "*.log"),
+            Some("target/\n*.log\n".to_string())
+        );
+        assert_eq!(append_gitignore_entry("target/\n*.log\n", "*.log"), None);
+    }
 
-// ============================================================================
-// Tests
-// ============================================================================
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_move_to_repo_trash() {
+        let dir = std::env::temp_dir().join(format!("siori-trash-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::create_dir_all(dir.join("build/out")).unwrap();
+        std::fs::write(dir.join("notes.txt"), "x").unwrap();
+
+        move_to_repo_trash(&dir, "notes.txt").unwrap();
+        move_to_repo_trash(&dir, "build/").unwrap();
+        assert!(!dir.join("notes.txt").exists());
+        assert!(!dir.join("build").exists());
+        let stamps: Vec<_> = std::fs::read_dir(dir.join(".git/siori/trash"))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert!(stamps.iter().any(|s| s.path().join("notes.txt").exists()));
+        assert!(stamps.iter().any(|s| s.path().join("build/out").is_dir()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
     #[test]
-    fn test_tab_default() {
-        assert_eq!(Tab::default(), Tab::Files);
+    fn test_move_to_repo_trash_same_path_twice() {
+        let dir = std::env::temp_dir().join(format!("siori-trash-twice-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        let mut bins = Vec::new();
+        for text in ["first", "second"] {
+            std::fs::write(dir.join("notes.txt"), text).unwrap();
+            std::fs::create_dir_all(dir.join("build")).unwrap();
+            std::fs::write(dir.join("build/out.txt"), text).unwrap();
+            let bin = move_to_repo_trash(&dir, "notes.txt").unwrap();
+            assert_eq!(move_to_repo_trash(&dir, "build/").unwrap(), bin);
+            bins.push(bin);
+        }
+        // Same second or not, neither discard replaces the other
+        assert_ne!(bins[0], bins[1]);
+        for (bin, text) in bins.iter().zip(["first", "second"]) {
+            assert_eq!(
+                std::fs::read_to_string(bin.join("notes.txt")).unwrap(),
+                text
+            );
+            assert_eq!(
+                std::fs::read_to_string(bin.join("build/out.txt")).unwrap(),
+                text
+            );
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn test_input_mode_default() {
-        assert_eq!(InputMode::default(), InputMode::Normal);
+    fn test_format_age() {
+        assert_eq!(format_age(5), "5s old");
+        assert_eq!(format_age(180), "3 min old");
+        assert_eq!(format_age(7200), "2 h old");
+        assert!(HealthIssue::IndexLock(None).blocks_writes());
+        assert!(!HealthIssue::Unfinished("Merge", "merge").blocks_writes());
+        assert!(HealthIssue::ReadOnly.blocks_writes());
+        assert!(!HealthIssue::NoGitCli.blocks_writes());
     }
 
     #[test]
-    fn test_format_relative_time() {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        assert_eq!(format_relative_time(now), "just now");
-        assert_eq!(format_relative_time(now - 120), "2 min ago");
-        assert_eq!(format_relative_time(now - 7200), "2 hours ago");
-        assert_eq!(format_relative_time(now - 172800), "2 days ago");
+    fn test_author_initials() {
+        assert_eq!(author_initials("Takuma Ogura"), "TO");
+        assert_eq!(author_initials("Jane Q. Public"), "JP");
+        assert_eq!(author_initials("dependabot"), "DE");
+        assert_eq!(author_initials(""), "?");
     }
 
     #[test]
-    fn test_file_status_display() {
-        let file = FileEntry {
-            path: "test.rs".to_string(),
-            status: FileStatus::Added,
-            staged: true,
-            diff_stats: Some((10, 5)),
-        };
-        assert_eq!(file.path, "test.rs");
-        assert!(file.staged);
+    fn test_wrap_point() {
+        assert_eq!(wrap_point("short line", 72), None);
+        assert_eq!(wrap_point("aaa bbb ccc", 8), Some(7));
+        // A space landing exactly on the width breaks there
+        assert_eq!(wrap_point("aaa bbb ", 7), Some(7));
+        assert_eq!(wrap_point("https://example.com/long", 8), None);
+        assert_eq!(wrap_point(" indented", 4), None);
     }
 
     #[test]
@@ -2726,4 +10244,991 @@ mod tests {
         assert_eq!(unpushed_tag.name, "v2.0.0");
         assert!(!unpushed_tag.pushed);
     }
+
+    /// A repository in the temp dir with `files` committed, for tests that drive `App`
+    fn temp_repo(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("siori-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .current_dir(&dir)
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "commit.gpgsign", "false"]);
+        for (path, text) in files {
+            std::fs::write(dir.join(path), text).unwrap();
+        }
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "init"]);
+        dir
+    }
+
+    /// Run git in a test repository and return its trimmed stdout
+    fn git_in(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Start a review session in `dir`; changes made before this are the baseline
+    fn start_review(dir: &Path) -> App {
+        let mut app = App::open_in(dir.to_path_buf()).unwrap();
+        app.refresh().unwrap();
+        app.open_review().unwrap();
+        assert!(app.review.is_some(), "{:?}", app.message);
+        app
+    }
+
+    fn review_files(app: &App) -> Vec<(String, FileStatus, bool)> {
+        app.review
+            .as_ref()
+            .unwrap()
+            .files
+            .iter()
+            .map(|f| (f.path.clone(), f.status, f.approved))
+            .collect()
+    }
+
+    fn select_review_path(app: &mut App, path: &str) {
+        let session = app.review.as_mut().unwrap();
+        session.selected = session.files.iter().position(|f| f.path == path).unwrap();
+    }
+
+    #[test]
+    fn test_review_lists_changes_since_start() {
+        let dir = temp_repo("review-list", &[("a.txt", "a\n"), ("b.txt", "b\n")]);
+        // Already changed before the session: part of the baseline
+        std::fs::write(dir.join("a.txt"), "a before\n").unwrap();
+        std::fs::write(dir.join("old.txt"), "untracked before\n").unwrap();
+        let mut app = start_review(&dir);
+        assert!(review_files(&app).is_empty());
+
+        std::fs::write(dir.join("b.txt"), "b after\n").unwrap();
+        std::fs::write(dir.join("new.txt"), "new\n").unwrap();
+        app.refresh().unwrap();
+        assert_eq!(
+            review_files(&app),
+            vec![
+                ("b.txt".to_string(), FileStatus::Modified, false),
+                ("new.txt".to_string(), FileStatus::Added, false),
+            ]
+        );
+
+        // Back to the baseline content: nothing left to review
+        std::fs::write(dir.join("b.txt"), "b\n").unwrap();
+        std::fs::remove_file(dir.join("new.txt")).unwrap();
+        app.refresh().unwrap();
+        assert!(review_files(&app).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_review_reject_and_undo() {
+        let dir = temp_repo(
+            "review-reject",
+            &[("a.txt", "a\n"), ("gone.txt", "keep me\n")],
+        );
+        std::fs::write(dir.join("a.txt"), "a before\n").unwrap();
+        let mut app = start_review(&dir);
+
+        std::fs::write(dir.join("a.txt"), "a after\n").unwrap();
+        std::fs::write(dir.join("added.txt"), "added\n").unwrap();
+        std::fs::remove_file(dir.join("gone.txt")).unwrap();
+        app.refresh().unwrap();
+        assert_eq!(
+            review_files(&app),
+            vec![
+                ("a.txt".to_string(), FileStatus::Modified, false),
+                ("added.txt".to_string(), FileStatus::Added, false),
+                ("gone.txt".to_string(), FileStatus::Deleted, false),
+            ]
+        );
+
+        for path in ["a.txt", "added.txt", "gone.txt"] {
+            select_review_path(&mut app, path);
+            app.reject_review_file().unwrap();
+        }
+        // The baseline, not HEAD: a.txt keeps the change made before the session
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "a before\n"
+        );
+        assert!(!dir.join("added.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.join("gone.txt")).unwrap(),
+            "keep me\n"
+        );
+        assert!(review_files(&app).is_empty());
+
+        // `u` brings back the rejected versions, most recent first
+        app.undo_review_reject().unwrap();
+        assert!(!dir.join("gone.txt").exists());
+        app.undo_review_reject().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("added.txt")).unwrap(),
+            "added\n"
+        );
+        app.undo_review_reject().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "a after\n"
+        );
+        assert_eq!(review_files(&app).len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_review_approve_stages_until_edited_again() {
+        let dir = temp_repo("review-approve", &[("a.txt", "a\n")]);
+        let mut app = start_review(&dir);
+
+        std::fs::write(dir.join("a.txt"), "a approved\n").unwrap();
+        app.refresh().unwrap();
+        select_review_path(&mut app, "a.txt");
+        app.approve_review_file().unwrap();
+        assert_eq!(
+            review_files(&app),
+            vec![("a.txt".to_string(), FileStatus::Modified, true)]
+        );
+        let index = app.repo.index().unwrap();
+        let staged = index.get_path(Path::new("a.txt"), 0).unwrap();
+        let content = app.repo.find_blob(staged.id).unwrap().content().to_vec();
+        assert_eq!(content, b"a approved\n");
+
+        std::fs::write(dir.join("a.txt"), "a edited again\n").unwrap();
+        app.refresh().unwrap();
+        assert_eq!(
+            review_files(&app),
+            vec![("a.txt".to_string(), FileStatus::Modified, false)]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_branches_merged_split() {
+        let dir = temp_repo("branches-merged", &[("a.txt", "a\n")]);
+        let git = |args: &[&str]| git_in(&dir, args);
+        git(&["branch", "done"]);
+        git(&["checkout", "-q", "-b", "topic"]);
+        std::fs::write(dir.join("a.txt"), "topic\n").unwrap();
+        git(&["commit", "-q", "-am", "topic work"]);
+        git(&["checkout", "-q", "-"]);
+
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        let merged = |name: &str| {
+            app.branches
+                .iter()
+                .find(|b| b.name == name)
+                .map(|b| b.merged)
+                .unwrap()
+        };
+        // `x` deletes merged branches with -d; topic has a commit HEAD lacks, so -D
+        assert!(merged("done"));
+        assert!(!merged("topic"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_branch_delete_checks_upstream() {
+        let dir = temp_repo("branches-upstream", &[("a.txt", "a\n")]);
+        let git = |args: &[&str]| git_in(&dir, args);
+        // feature is merged into HEAD, but its upstream is still one commit behind
+        git(&["remote", "add", "origin", "/nonexistent"]);
+        git(&["update-ref", "refs/remotes/origin/feature", "HEAD"]);
+        git(&["checkout", "-q", "-b", "feature"]);
+        git(&["branch", "--set-upstream-to=origin/feature"]);
+        std::fs::write(dir.join("a.txt"), "feature\n").unwrap();
+        git(&["commit", "-q", "-am", "feature work"]);
+        git(&["checkout", "-q", "-"]);
+        git(&["merge", "-q", "--ff-only", "feature"]);
+
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        let select = |app: &mut App| {
+            let idx = app.branches.iter().position(|b| b.name == "feature");
+            app.branches_state.select(idx);
+        };
+        select(&mut app);
+        // git branch -d would refuse it, so `x` asks first
+        assert!(!app.selected_branch().unwrap().merged);
+        app.open_branch_delete_confirm().unwrap();
+        assert_eq!(app.input_mode, InputMode::BranchDeleteConfirm);
+
+        // A refused -d leads to the same confirmation instead of a dead end
+        app.delete_selected_branch(false).unwrap();
+        assert_eq!(app.input_mode, InputMode::BranchDeleteConfirm);
+        assert!(app.message.as_ref().is_some_and(|(_, error)| *error));
+        app.delete_selected_branch(true).unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.branches.iter().all(|b| b.name != "feature"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stage_all_without_git_cli() {
+        let dir = temp_repo("no-git-cli", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "changed\n").unwrap();
+        // PATH is shared by every test thread, so git goes missing only in a child process
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "app::tests::stage_all_without_git_cli_child",
+                "--ignored",
+            ])
+            .env("PATH", dir.join("no-bin"))
+            .env("SIORI_TEST_REPO", &dir)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "{}", stdout);
+        assert!(stdout.contains("1 passed"), "{}", stdout);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[ignore = "run by test_stage_all_without_git_cli with git off PATH"]
+    fn stage_all_without_git_cli_child() {
+        let dir = PathBuf::from(std::env::var_os("SIORI_TEST_REPO").unwrap());
+        let mut app = App::open_in(dir).unwrap();
+        app.refresh().unwrap();
+        app.stage_all().unwrap();
+        assert_eq!(app.message, Some((HealthIssue::NoGitCli.describe(), true)));
+        assert!(app.files.iter().all(|f| !f.staged));
+    }
+
+    /// A release of v1.0.0 in a fresh repository: the bump commit on top of
+    /// `previous`, tagged. Returns the repository and the rollback for it
+    fn temp_release(name: &str) -> (PathBuf, ReleaseRollback) {
+        let dir = temp_repo(name, &[("VERSION", "0.9.0\n")]);
+        let previous = git_in(&dir, &["rev-parse", "HEAD"]);
+        std::fs::write(dir.join("VERSION"), "1.0.0\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "bump to 1.0.0"]);
+        git_in(&dir, &["tag", "v1.0.0"]);
+        let bump = git_in(&dir, &["rev-parse", "HEAD"]);
+        let rollback = ReleaseRollback {
+            tag_name: "v1.0.0".to_string(),
+            previous: git2::Oid::from_str(&previous).unwrap(),
+            bump_commit: git2::Oid::from_str(&bump).unwrap(),
+        };
+        (dir, rollback)
+    }
+
+    fn roll_back(dir: &Path, rollback: ReleaseRollback) -> String {
+        let mut app = App::open_in(dir.to_path_buf()).unwrap();
+        app.refresh().unwrap();
+        app.release_rollback = Some(rollback);
+        app.rollback_release().unwrap();
+        assert!(git_in(dir, &["tag", "-l", "v1.0.0"]).is_empty());
+        app.message.unwrap().0
+    }
+
+    #[test]
+    fn test_rollback_release_drops_bump_commit() {
+        let (dir, rollback) = temp_release("rollback-head");
+        let previous = rollback.previous.to_string();
+        assert_eq!(roll_back(&dir, rollback), "Rolled back release v1.0.0");
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), previous);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("VERSION")).unwrap(),
+            "0.9.0\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rollback_release_keeps_commit_after_head_moved() {
+        let (dir, rollback) = temp_release("rollback-moved");
+        std::fs::write(dir.join("later.txt"), "later\n").unwrap();
+        git_in(&dir, &["add", "later.txt"]);
+        git_in(&dir, &["commit", "-q", "-m", "later work"]);
+        let head = git_in(&dir, &["rev-parse", "HEAD"]);
+        assert_eq!(
+            roll_back(&dir, rollback),
+            "Deleted tag v1.0.0; HEAD moved since the bump, commit kept"
+        );
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), head);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rollback_release_keeps_pushed_commit() {
+        let (dir, rollback) = temp_release("rollback-pushed");
+        let remote = dir.with_extension("remote");
+        let _ = std::fs::remove_dir_all(&remote);
+        git_in(&dir, &["init", "-q", "--bare", remote.to_str().unwrap()]);
+        git_in(&dir, &["remote", "add", "origin", remote.to_str().unwrap()]);
+        git_in(&dir, &["push", "-q", "origin", "HEAD"]);
+        let bump = rollback.bump_commit.to_string();
+        assert_eq!(
+            roll_back(&dir, rollback),
+            "Deleted tag v1.0.0; bump commit is already on the remote and was kept"
+        );
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), bump);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&remote).unwrap();
+    }
+
+    /// Undo the HEAD commit the way the Log tab does, returning whether it was flagged as pushed
+    fn undo_head_commit(dir: &Path) -> bool {
+        let mut app = App::open_in(dir.to_path_buf()).unwrap();
+        app.refresh().unwrap();
+        app.commits_state.select(Some(0));
+        app.open_undo_commit_confirm();
+        assert_eq!(app.input_mode, InputMode::UndoCommitConfirm);
+        let pushed = app.pending_undo_commit.as_ref().unwrap().1;
+        app.undo_commit().unwrap();
+        assert_eq!(app.commit_message, "second");
+        pushed
+    }
+
+    #[test]
+    fn test_undo_unpushed_commit() {
+        let dir = temp_repo("undo-unpushed", &[("a.txt", "a\n")]);
+        let first = git_in(&dir, &["rev-parse", "HEAD"]);
+        std::fs::write(dir.join("a.txt"), "second\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "second"]);
+
+        assert!(!undo_head_commit(&dir));
+        // Soft reset: the commit's changes are back in the index
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), first);
+        assert_eq!(git_in(&dir, &["diff", "--cached", "--name-only"]), "a.txt");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_undo_pushed_commit_warns() {
+        let dir = temp_repo("undo-pushed", &[("a.txt", "a\n")]);
+        // Tracking a remote that isn't origin, under another branch name
+        let remote = dir.with_extension("remote");
+        let _ = std::fs::remove_dir_all(&remote);
+        git_in(&dir, &["init", "-q", "--bare", remote.to_str().unwrap()]);
+        git_in(
+            &dir,
+            &["remote", "add", "upstream", remote.to_str().unwrap()],
+        );
+        std::fs::write(dir.join("a.txt"), "second\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "second"]);
+        git_in(&dir, &["push", "-q", "-u", "upstream", "HEAD:shared"]);
+
+        assert!(undo_head_commit(&dir));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&remote).unwrap();
+    }
+
+    /// Open `dir` with the commit whose subject is `message` selected in the Log tab
+    fn app_at_commit(dir: &Path, message: &str) -> App {
+        let mut app = App::open_in(dir.to_path_buf()).unwrap();
+        app.log_all_branches = true;
+        app.refresh().unwrap();
+        let idx = app.commits.iter().position(|c| c.message == message);
+        assert!(idx.is_some(), "no commit {:?}", message);
+        app.commits_state.select(idx);
+        app
+    }
+
+    #[test]
+    fn test_revert_commit_stages_inverse() {
+        let dir = temp_repo("revert-plain", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "changed\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "change a"]);
+        let id = git_in(&dir, &["rev-parse", "HEAD"]);
+
+        let mut app = app_at_commit(&dir, "change a");
+        app.revert_commit().unwrap();
+        assert_eq!(
+            app.commit_message,
+            format!("Revert \"change a\"\n\nThis reverts commit {}.", id)
+        );
+        assert_eq!(app.tab, Tab::Files);
+        // Nothing is committed yet: the inverse waits in the index
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), id);
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "a\n");
+        assert_eq!(git_in(&dir, &["diff", "--cached", "--name-only"]), "a.txt");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_revert_merge_commit_against_first_parent() {
+        let dir = temp_repo("revert-merge", &[("a.txt", "a\n")]);
+        git_in(&dir, &["checkout", "-q", "-b", "topic"]);
+        std::fs::write(dir.join("topic.txt"), "topic\n").unwrap();
+        git_in(&dir, &["add", "topic.txt"]);
+        git_in(&dir, &["commit", "-q", "-m", "topic work"]);
+        git_in(&dir, &["checkout", "-q", "-"]);
+        std::fs::write(dir.join("main.txt"), "main\n").unwrap();
+        git_in(&dir, &["add", "main.txt"]);
+        git_in(&dir, &["commit", "-q", "-m", "main work"]);
+        git_in(
+            &dir,
+            &["merge", "-q", "--no-ff", "-m", "Merge topic", "topic"],
+        );
+
+        let mut app = app_at_commit(&dir, "Merge topic");
+        app.revert_commit().unwrap();
+        assert!(app.commit_message.starts_with("Revert \"Merge topic\""));
+        // -m 1: what the merge brought in from topic goes, main's own work stays
+        assert!(!dir.join("topic.txt").exists());
+        assert!(dir.join("main.txt").exists());
+        assert_eq!(
+            git_in(&dir, &["diff", "--cached", "--name-status"]),
+            "D\ttopic.txt"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cherry_pick_conflict_lists_conflicts_first() {
+        let dir = temp_repo("cherry-pick-conflict", &[("a.txt", "base\n")]);
+        git_in(&dir, &["checkout", "-q", "-b", "topic"]);
+        std::fs::write(dir.join("a.txt"), "topic\n").unwrap();
+        std::fs::write(dir.join("d.txt"), "d\n").unwrap();
+        git_in(&dir, &["add", "-A"]);
+        git_in(&dir, &["commit", "-q", "-m", "topic change"]);
+        git_in(&dir, &["checkout", "-q", "-"]);
+        std::fs::write(dir.join("a.txt"), "main\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "main change"]);
+        // Untracked files don't block the pick, and sort ahead of a.txt by name
+        std::fs::write(dir.join("0-notes.txt"), "notes\n").unwrap();
+
+        let mut app = app_at_commit(&dir, "topic change");
+        app.cherry_pick_selected().unwrap();
+        assert_eq!(app.tab, Tab::Files);
+        assert!(app.commit_message.starts_with("topic change"));
+        let (message, error) = app.message.clone().unwrap();
+        assert!(error);
+        assert!(
+            message.starts_with("Cherry-pick stopped on 1 conflict"),
+            "{}",
+            message
+        );
+
+        let listed: Vec<(&str, FileStatus)> = app
+            .visual_list
+            .iter()
+            .map(|&i| (app.files[i].path.as_str(), app.files[i].status))
+            .collect();
+        // The clean part of the pick is staged; among the rest the conflict leads
+        assert_eq!(
+            listed,
+            vec![
+                ("d.txt", FileStatus::Added),
+                ("a.txt", FileStatus::Conflicted),
+                ("0-notes.txt", FileStatus::Untracked),
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Wait for the background operation to finish and take its result
+    fn finish_processing(app: &mut App) {
+        for _ in 0..500 {
+            app.check_processing().unwrap();
+            if !app.processing.is_active() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        panic!("operation didn't finish: {:?}", app.message);
+    }
+
+    #[test]
+    fn test_commit_tracked_checks_before_touching_index() {
+        let dir = temp_repo("commit-tracked-check", &[("a.txt", "a\n")]);
+        std::fs::write(
+            dir.join("a.txt"),
+            "<<<<<<< ours\na\n=======\nb\n>>>>>>> theirs\n",
+        )
+        .unwrap();
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        app.commit_message = "with markers".to_string();
+        app.commit_tracked_changes().unwrap();
+        assert_eq!(app.input_mode, InputMode::CommitWarning);
+        assert!(!app.processing.is_active());
+        assert!(git_in(&dir, &["diff", "--cached", "--name-only"]).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_commit_tracked_skips_untracked() {
+        let dir = temp_repo("commit-tracked", &[("a.txt", "a\n"), ("b.txt", "b\n")]);
+        std::fs::write(dir.join("a.txt"), "changed\n").unwrap();
+        std::fs::remove_file(dir.join("b.txt")).unwrap();
+        std::fs::write(dir.join("new.txt"), "new\n").unwrap();
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        app.commit_message = "tracked only".to_string();
+        app.commit_tracked_changes().unwrap();
+        finish_processing(&mut app);
+
+        assert_eq!(git_in(&dir, &["log", "-1", "--format=%s"]), "tracked only");
+        assert_eq!(
+            git_in(&dir, &["show", "--format=", "--name-status", "HEAD"]),
+            "M\ta.txt\nD\tb.txt"
+        );
+        assert_eq!(git_in(&dir, &["status", "--porcelain"]), "?? new.txt");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_checkout_commit_detaches_head() {
+        let dir = temp_repo("checkout-detach", &[("a.txt", "a\n")]);
+        let first = git_in(&dir, &["rev-parse", "HEAD"]);
+        std::fs::write(dir.join("a.txt"), "second\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "second"]);
+
+        let mut app = app_at_commit(&dir, "init");
+        app.confirmations = Confirmations::All;
+        app.open_checkout_confirm().unwrap();
+        assert_eq!(app.input_mode, InputMode::CheckoutConfirm);
+        app.checkout_commit().unwrap();
+        let head = app.repo.head().unwrap();
+        assert!(!head.is_branch());
+        assert_eq!(head.target().unwrap().to_string(), first);
+        assert!(app.detached_at.is_some());
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "a\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_checkout_commit_refused_with_changes() {
+        let dir = temp_repo("checkout-dirty", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "second\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "second"]);
+        let head = git_in(&dir, &["rev-parse", "HEAD"]);
+        std::fs::write(dir.join("a.txt"), "edited\n").unwrap();
+
+        let mut app = app_at_commit(&dir, "init");
+        app.open_checkout_confirm().unwrap();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.pending_checkout.is_none());
+        assert_eq!(
+            app.message,
+            Some((
+                "Commit or stash your changes before checking out".to_string(),
+                true
+            ))
+        );
+        assert!(app.repo.head().unwrap().is_branch());
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "edited\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Open `dir` and start a move of the changed file `path` to `target`
+    fn move_file(dir: &Path, path: &str, target: &str) -> App {
+        let mut app = App::open_in(dir.to_path_buf()).unwrap();
+        app.refresh().unwrap();
+        let idx = app
+            .visual_list
+            .iter()
+            .position(|&i| app.files[i].path == path);
+        app.files_state.select(idx);
+        app.open_move_input();
+        assert_eq!(app.input_mode, InputMode::MoveInput);
+        app.move_input = target.to_string();
+        app.move_files().unwrap();
+        app
+    }
+
+    #[test]
+    fn test_move_file_stages_rename() {
+        let dir = temp_repo("move-rename", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "a\nmore\n").unwrap();
+
+        let app = move_file(&dir, "a.txt", "docs/b.txt");
+        assert_eq!(
+            app.message,
+            Some(("Moved a.txt → docs/b.txt".to_string(), false))
+        );
+        assert_eq!(
+            git_in(&dir, &["diff", "--cached", "-M", "--name-status"]),
+            "R100\ta.txt\tdocs/b.txt"
+        );
+        // The unstaged edit moves along with the file
+        assert_eq!(
+            std::fs::read_to_string(dir.join("docs/b.txt")).unwrap(),
+            "a\nmore\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_move_file_refuses_existing_target() {
+        let dir = temp_repo("move-existing", &[("a.txt", "a\n"), ("c.txt", "c\n")]);
+        std::fs::write(dir.join("a.txt"), "a\nmore\n").unwrap();
+
+        let app = move_file(&dir, "a.txt", "c.txt");
+        assert_eq!(
+            app.message,
+            Some(("Not moved: c.txt already exists".to_string(), true))
+        );
+        assert!(git_in(&dir, &["diff", "--cached", "--name-only"]).is_empty());
+        assert_eq!(std::fs::read_to_string(dir.join("c.txt")).unwrap(), "c\n");
+        assert!(dir.join("a.txt").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_switch_repo_ends_review() {
+        let dir = temp_repo("switch-review", &[("a.txt", "a\n")]);
+        let other = temp_repo("switch-review-other", &[("b.txt", "b\n")]);
+        let mut app = start_review(&dir);
+
+        app.processing = Processing::Pushing;
+        app.switch_repo(other.clone()).unwrap();
+        assert_eq!(app.repo_path, dir);
+        assert!(app.review.is_some());
+
+        app.processing = Processing::None;
+        app.marked_files.insert(("a.txt".to_string(), false));
+        app.log_mark = app.repo.head().unwrap().target();
+        app.detached_at = Some("1234567".to_string());
+        app.switch_repo(other.clone()).unwrap();
+        assert_eq!(app.repo_path, other);
+        assert!(app.review.is_none());
+        assert!(app.marked_files.is_empty());
+        assert!(app.log_mark.is_none());
+        assert!(app.detached_at.is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&other).unwrap();
+    }
+
+    #[test]
+    fn test_suggestion_keeps_message_typed_meanwhile() {
+        let dir = temp_repo("suggest-typed", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "changed\n").unwrap();
+        git_in(&dir, &["add", "a.txt"]);
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        app.suggest_command = Some("echo 'suggested subject'".to_string());
+
+        app.commit_message = "my draft".to_string();
+        app.suggest_commit_message();
+        app.commit_message = "my draft, edited".to_string();
+        finish_processing(&mut app);
+        assert_eq!(app.commit_message, "my draft, edited");
+        assert_eq!(app.last_output.as_ref().unwrap().text, "suggested subject");
+
+        // Only taken when asked for
+        app.suggest_commit_message();
+        assert_eq!(app.commit_message, "suggested subject");
+        assert!(app.pending_suggestion.is_none());
+
+        // Left untouched while it ran: replaced right away
+        app.commit_message = "my draft".to_string();
+        app.suggest_commit_message();
+        finish_processing(&mut app);
+        assert_eq!(app.commit_message, "suggested subject");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_discard_middle_hunk() {
+        let original: String = (1..=30).map(|n| format!("line {}\n", n)).collect();
+        let dir = temp_repo("discard-hunk-middle", &[("f.txt", &original)]);
+        let edited = original
+            .replace("line 2\n", "line 2 edited\n")
+            .replace("line 15\n", "line 15 edited\n")
+            .replace("line 28\n", "line 28 edited\n");
+        std::fs::write(dir.join("f.txt"), &edited).unwrap();
+
+        let repo = Repository::open(&dir).unwrap();
+        discard_hunk_in_workdir(&repo, "f.txt", 1, false).unwrap();
+        // Only the middle hunk goes back; the ones around it keep their edits
+        assert_eq!(
+            std::fs::read_to_string(dir.join("f.txt")).unwrap(),
+            edited.replace("line 15 edited\n", "line 15\n")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_discard_hunk_without_trailing_newline() {
+        let dir = temp_repo("discard-hunk-eof", &[("f.txt", "a\nb\nc")]);
+        std::fs::write(dir.join("f.txt"), "a\nb\nc edited").unwrap();
+
+        let repo = Repository::open(&dir).unwrap();
+        discard_hunk_in_workdir(&repo, "f.txt", 0, false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("f.txt")).unwrap(),
+            "a\nb\nc"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stash_pop_onto_conflicting_changes() {
+        let dir = temp_repo("stash-pop-conflict", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "stashed\n").unwrap();
+        git_in(&dir, &["stash", "-q"]);
+        std::fs::write(dir.join("a.txt"), "local\n").unwrap();
+
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        app.stash_state.select(Some(0));
+        app.stash_apply(true).unwrap();
+        assert_eq!(
+            app.message,
+            Some((
+                "Conflicts with local changes: commit or stash them first (stash@{0} kept)"
+                    .to_string(),
+                true
+            ))
+        );
+        assert_eq!(app.stashes.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "local\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn select_file(app: &mut App, path: &str) {
+        let idx = app
+            .visual_list
+            .iter()
+            .position(|&i| app.files[i].path == path);
+        assert!(idx.is_some(), "{} not listed", path);
+        app.files_state.select(idx);
+    }
+
+    #[test]
+    fn test_intent_to_add_stays_out_of_commits() {
+        let dir = temp_repo("intent-to-add", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("new.txt"), "new\n").unwrap();
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        select_file(&mut app, "new.txt");
+        app.toggle_intent_to_add().unwrap();
+        let listed = |app: &App| {
+            app.files
+                .iter()
+                .find(|f| f.path == "new.txt")
+                .map(|f| (f.status, f.staged, f.intent_to_add))
+                .unwrap()
+        };
+        assert_eq!(listed(&app), (FileStatus::Added, false, true));
+
+        // Only the intent recorded: nothing is staged yet
+        app.commit_message = "just intent".to_string();
+        app.commit().unwrap();
+        assert!(!app.processing.is_active());
+        assert!(
+            app.message
+                .as_ref()
+                .unwrap()
+                .0
+                .starts_with("Nothing staged")
+        );
+
+        // Committed alongside staged work, the file itself stays out
+        std::fs::write(dir.join("a.txt"), "changed\n").unwrap();
+        git_in(&dir, &["add", "a.txt"]);
+        app.refresh().unwrap();
+        app.commit_message = "staged work".to_string();
+        app.commit().unwrap();
+        finish_processing(&mut app);
+        assert_eq!(
+            git_in(&dir, &["show", "--format=", "--name-only", "HEAD"]),
+            "a.txt"
+        );
+        assert_eq!(listed(&app), (FileStatus::Added, false, true));
+
+        select_file(&mut app, "new.txt");
+        app.toggle_intent_to_add().unwrap();
+        assert_eq!(listed(&app), (FileStatus::Untracked, false, false));
+        assert_eq!(git_in(&dir, &["status", "--porcelain"]), "?? new.txt");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_skip_worktree_round_trip() {
+        let dir = temp_repo("skip-worktree", &[("config.toml", "debug = false\n")]);
+        std::fs::write(dir.join("config.toml"), "debug = true\n").unwrap();
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        let listed = |app: &App| {
+            app.files
+                .iter()
+                .filter(|f| f.path == "config.toml")
+                .map(|f| (f.status, f.hidden, f.diff_stats))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            listed(&app),
+            vec![(FileStatus::Modified, None, Some((1, 1)))]
+        );
+
+        select_file(&mut app, "config.toml");
+        app.toggle_skip_worktree().unwrap();
+        assert_eq!(
+            listed(&app),
+            vec![(FileStatus::Modified, Some(HiddenFlag::SkipWorktree), None)]
+        );
+        assert!(git_in(&dir, &["status", "--porcelain"]).is_empty());
+
+        // Shown again with the edit still there, not reset to HEAD
+        select_file(&mut app, "config.toml");
+        app.toggle_skip_worktree().unwrap();
+        assert_eq!(
+            listed(&app),
+            vec![(FileStatus::Modified, None, Some((1, 1)))]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("config.toml")).unwrap(),
+            "debug = true\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_enter_on_incoming_row_opens_incoming_view() {
+        let dir = temp_repo("incoming-row", &[("a.txt", "a\n")]);
+        std::fs::write(dir.join("a.txt"), "upstream\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "upstream work"]);
+        let branch = git_in(&dir, &["rev-parse", "--abbrev-ref", "HEAD"]);
+        git_in(
+            &dir,
+            &[
+                "update-ref",
+                &format!("refs/remotes/origin/{}", branch),
+                "HEAD",
+            ],
+        );
+        git_in(&dir, &["reset", "-q", "--hard", "HEAD~1"]);
+
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        app.tab = Tab::Log;
+        assert_eq!(app.incoming_row(), Some(1));
+        assert_eq!(app.commits_state.selected(), Some(0));
+
+        // k from the top commit lands on the ↓ row, with no commit selected
+        app.handle_key(KeyCode::Char('k'), KeyModifiers::NONE)
+            .unwrap();
+        assert!(app.on_incoming_row());
+        assert_eq!(app.commits_state.selected(), None);
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE).unwrap();
+        assert_eq!(app.input_mode, InputMode::IncomingView);
+        let incoming = app.incoming.as_ref().unwrap();
+        assert_eq!(incoming.commits.len(), 1);
+        assert_eq!(incoming.commits[0].1, "upstream work");
+
+        // j goes back down to the top commit, where Enter shows its details
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+        app.handle_key(KeyCode::Char('j'), KeyModifiers::NONE)
+            .unwrap();
+        assert!(!app.on_incoming_row());
+        assert_eq!(app.commits_state.selected(), Some(0));
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE).unwrap();
+        assert_eq!(app.input_mode, InputMode::CommitDetail);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// A repository whose last commit was thrown away by `git reset --hard`,
+    /// with the app's reflog view on the entry that still points at it
+    fn app_at_lost_commit(name: &str) -> (PathBuf, App, String) {
+        let dir = temp_repo(name, &[("a.txt", "a\n"), ("b.txt", "b\n")]);
+        std::fs::write(dir.join("a.txt"), "changed\n").unwrap();
+        git_in(&dir, &["commit", "-q", "-am", "change a"]);
+        let lost = git_in(&dir, &["rev-parse", "HEAD"]);
+        git_in(&dir, &["reset", "-q", "--hard", "HEAD~1"]);
+
+        let mut app = App::open_in(dir.clone()).unwrap();
+        app.refresh().unwrap();
+        app.open_reflog().unwrap();
+        let idx = app.reflog.iter().position(|e| e.id.to_string() == lost);
+        assert_eq!(idx, Some(1), "{:?}", app.message);
+        app.reflog_state.select(idx);
+        (dir, app, lost)
+    }
+
+    #[test]
+    fn test_reflog_reset_recovers_lost_commit() {
+        let (dir, mut app, lost) = app_at_lost_commit("reflog-reset");
+        // Local changes the reset doesn't touch come along
+        std::fs::write(dir.join("b.txt"), "local\n").unwrap();
+
+        app.open_reflog_reset_confirm();
+        assert_eq!(app.input_mode, InputMode::ReflogResetConfirm);
+        app.reflog_reset().unwrap();
+        assert!(!app.message.as_ref().unwrap().1, "{:?}", app.message);
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), lost);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "changed\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("b.txt")).unwrap(),
+            "local\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reflog_reset_refuses_to_overwrite_local_changes() {
+        let (dir, mut app, _) = app_at_lost_commit("reflog-reset-dirty");
+        let head = git_in(&dir, &["rev-parse", "HEAD"]);
+        // The entry changes a.txt too: --keep refuses rather than lose this
+        std::fs::write(dir.join("a.txt"), "local edit\n").unwrap();
+
+        app.open_reflog_reset_confirm();
+        app.reflog_reset().unwrap();
+        let (message, is_error) = app.message.clone().unwrap();
+        assert!(is_error, "{}", message);
+        assert!(message.starts_with("Reset failed"), "{}", message);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(git_in(&dir, &["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("a.txt")).unwrap(),
+            "local edit\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use crate::config::ChangelogConfig;
+
+/// A changelog fragment file (e.g. `.changes/123.feature.md`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fragment {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Fragment type from the file name ("feature", "bugfix", ...)
+    pub kind: String,
+    pub text: String,
+}
+
+/// Fragment types in the order their sections appear in CHANGELOG.md
+const SECTIONS: [(&str, &str); 5] = [
+    ("feature", "Features"),
+    ("bugfix", "Bug Fixes"),
+    ("doc", "Documentation"),
+    ("removal", "Removals"),
+    ("misc", "Misc"),
+];
+
+/// Collect fragments from the configured directory, sorted by file name
+pub fn collect_fragments(repo_path: &Path, config: &ChangelogConfig) -> Vec<Fragment> {
+    let Ok(entries) = std::fs::read_dir(repo_path.join(&config.dir)) else {
+        return Vec::new();
+    };
+    let mut fragments: Vec<Fragment> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            // Keep placeholder files like .gitkeep out of the changelog
+            if name.starts_with('.') {
+                return None;
+            }
+            let text = std::fs::read_to_string(e.path()).ok()?;
+            let text = text.trim();
+            if text.is_empty() {
+                return None;
+            }
+            Some(Fragment {
+                path: format!("{}/{}", config.dir.trim_end_matches('/'), name),
+                kind: fragment_kind(&name),
+                text: text.to_string(),
+            })
+        })
+        .collect();
+    fragments.sort_by(|a, b| a.path.cmp(&b.path));
+    fragments
+}
+
+/// `123.feature.md` → "feature"; names without a known type go under "misc"
+fn fragment_kind(file_name: &str) -> String {
+    file_name
+        .split('.')
+        .skip(1)
+        .find(|part| SECTIONS.iter().any(|(kind, _)| kind == part))
+        .unwrap_or("misc")
+        .to_string()
+}
+
+/// File name for a new fragment, unique per second
+pub fn fragment_file_name(kind: &str, timestamp: u64) -> String {
+    format!("{}.{}.md", timestamp, kind)
+}
+
+/// Parse "feature: Added X" into (kind, text); the type defaults to "misc"
+pub fn parse_fragment_input(input: &str) -> Option<(&'static str, &str)> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    if let Some((prefix, text)) = input.split_once(':')
+        && let Some((kind, _)) = SECTIONS.iter().find(|(kind, _)| *kind == prefix.trim())
+        && !text.trim().is_empty()
+    {
+        return Some((kind, text.trim()));
+    }
+    Some(("misc", input))
+}
+
+/// Build the release section for `version` from the fragments
+pub fn render_release(heading: &str, version: &str, date: &str, fragments: &[Fragment]) -> String {
+    let mut out = heading
+        .replace("{version}", version)
+        .replace("{date}", date);
+    out.push('\n');
+    for (kind, title) in SECTIONS {
+        let items: Vec<&Fragment> = fragments.iter().filter(|f| f.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {}\n\n", title));
+        for fragment in items {
+            // Continuation lines of multi-line fragments stay inside the bullet
+            let text = fragment.text.replace('\n', "\n  ");
+            out.push_str(&format!("- {}\n", text));
+        }
+    }
+    out
+}
+
+/// Insert a release section below the changelog title (or at the top)
+pub fn insert_release(changelog: &str, release: &str) -> String {
+    if changelog.trim().is_empty() {
+        return format!("# Changelog\n\n{}", release);
+    }
+    let mut lines = changelog.lines();
+    match lines.next() {
+        Some(title) if title.starts_with("# ") => {
+            let rest: Vec<&str> = lines.collect();
+            let rest = rest.join("\n");
+            format!(
+                "{}\n\n{}\n{}\n",
+                title,
+                release,
+                rest.trim_start_matches('\n').trim_end()
+            )
+        }
+        _ => format!("{}\n{}", release, changelog),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(kind: &str, text: &str) -> Fragment {
+        Fragment {
+            path: format!(".changes/1.{kind}.md"),
+            kind: kind.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fragment_kind() {
+        assert_eq!(fragment_kind("123.feature.md"), "feature");
+        assert_eq!(fragment_kind("fix-login.bugfix.md"), "bugfix");
+        assert_eq!(fragment_kind("notes.md"), "misc");
+    }
+
+    #[test]
+    fn test_parse_fragment_input() {
+        assert_eq!(
+            parse_fragment_input("feature: Add stash tab"),
+            Some(("feature", "Add stash tab"))
+        );
+        assert_eq!(
+            parse_fragment_input("Fix typo: in help"),
+            Some(("misc", "Fix typo: in help"))
+        );
+        assert_eq!(parse_fragment_input("  "), None);
+    }
+
+    #[test]
+    fn test_render_and_insert_release() {
+        let fragments = [
+            fragment("bugfix", "Fix crash"),
+            fragment("feature", "Add stash tab"),
+        ];
+        let release = render_release("## {version} ({date})", "1.2.0", "2026-01-02", &fragments);
+        assert_eq!(
+            release,
+            "## 1.2.0 (2026-01-02)\n\n### Features\n\n- Add stash tab\n\n### Bug Fixes\n\n- Fix crash\n"
+        );
+
+        let existing = "# Changelog\n\n## 1.1.0\n\n- Old\n";
+        assert_eq!(
+            insert_release(existing, &release),
+            format!("# Changelog\n\n{}\n## 1.1.0\n\n- Old\n", release)
+        );
+        assert_eq!(
+            insert_release("", &release),
+            format!("# Changelog\n\n{}", release)
+        );
+    }
+}
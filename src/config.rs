@@ -11,6 +11,152 @@ pub struct Config {
     pub ui: UiConfig,
     #[serde(default)]
     pub editor: EditorConfig,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub commit: CommitConfig,
+    #[serde(default)]
+    pub startup: StartupConfig,
+    #[serde(default)]
+    pub pager: PagerConfig,
+    /// Identities to switch between with `I` (`[[profiles]]`)
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+}
+
+/// A commit identity (work, personal, ...) written to the repo's local git config
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct Profile {
+    /// Label shown in the switcher
+    pub name: String,
+    /// `user.name`
+    pub user_name: String,
+    /// `user.email`
+    pub email: String,
+    /// `user.signingkey`, left untouched when unset
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    /// Remote host this identity belongs to (e.g. "github.example.com"); commits
+    /// to repos whose origin is on that host need this profile's email
+    #[serde(default)]
+    pub host: Option<String>,
+}
+
+/// The profile whose `host` appears in the remote URL
+pub fn profile_for_remote<'a>(profiles: &'a [Profile], url: &str) -> Option<&'a Profile> {
+    profiles.iter().find(|p| {
+        p.host
+            .as_deref()
+            .is_some_and(|host| !host.is_empty() && remote_host(url) == Some(host))
+    })
+}
+
+/// Host part of an https / ssh / scp-style remote URL
+fn remote_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = authority.split(':').next().unwrap_or(authority);
+    (!host.is_empty()).then_some(host)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitConfig {
+    /// Show the whole staged diff for a final look before committing (like `git commit -v`)
+    #[serde(default)]
+    pub review: bool,
+    /// Enter stages every tracked change first, like `git commit -a` (Ctrl+A does it once)
+    #[serde(default)]
+    pub all: bool,
+    /// Collaborators offered by the Co-authored-by picker ("Name <email>")
+    #[serde(default)]
+    pub co_authors: Vec<String>,
+    /// Subject length shown as a warning (default: 50)
+    #[serde(default = "default_subject_limit")]
+    pub subject_limit: usize,
+    /// Subject length shown as an error (default: 72)
+    #[serde(default = "default_line_limit")]
+    pub subject_max: usize,
+    /// Body line length before it is flagged (default: 72)
+    #[serde(default = "default_line_limit")]
+    pub body_width: usize,
+    /// Refuse to commit a subject longer than `subject_max` (default: false)
+    #[serde(default)]
+    pub block_long_subject: bool,
+    /// Break body lines at `body_width` while typing (default: false)
+    #[serde(default)]
+    pub wrap_body: bool,
+    /// Command fed the staged diff on stdin; its output fills the message (Ctrl+G)
+    #[serde(default)]
+    pub suggest_command: Option<String>,
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        Self {
+            review: false,
+            all: false,
+            co_authors: Vec::new(),
+            subject_limit: default_subject_limit(),
+            subject_max: default_line_limit(),
+            body_width: default_line_limit(),
+            block_long_subject: false,
+            wrap_body: false,
+            suggest_command: None,
+        }
+    }
+}
+
+fn default_subject_limit() -> usize {
+    50
+}
+
+fn default_line_limit() -> usize {
+    72
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ClipboardConfig {
+    #[serde(default)]
+    pub backend: ClipboardBackend,
+}
+
+/// How yanked text reaches the clipboard
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    /// System tool (pbcopy / wl-copy / xclip / xsel), OSC 52 over SSH or as fallback
+    #[default]
+    Auto,
+    /// System tool only
+    System,
+    /// OSC 52 terminal escape sequence only
+    Osc52,
+}
+
+/// What siori shows first (`[startup]`)
+#[derive(Debug, Default, Deserialize)]
+pub struct StartupConfig {
+    #[serde(default)]
+    pub tab: StartTab,
+    /// Start typing the commit message right away (Files tab only)
+    #[serde(default)]
+    pub commit_focus: bool,
+    /// Open the repository switcher when the directory holds several repositories
+    #[serde(default)]
+    pub repo_switcher: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StartTab {
+    #[default]
+    Files,
+    Log,
+    Stash,
+    Branches,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -28,11 +174,176 @@ impl EditorConfig {
     }
 }
 
+/// External pager for commit views and long output (`[pager]`); unset keeps the built-in viewer
+#[derive(Debug, Default, Deserialize)]
+pub struct PagerConfig {
+    /// Program and arguments, e.g. "delta --side-by-side", "bat --paging=always", "moar"
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+impl PagerConfig {
+    /// The command split into program and arguments; None when unset or blank
+    pub fn argv(&self) -> Option<Vec<String>> {
+        let argv: Vec<String> = self
+            .command
+            .as_deref()?
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        (!argv.is_empty()).then_some(argv)
+    }
+}
+
 /// Repository-specific config (.siori.toml)
 #[derive(Debug, Default, Deserialize)]
 pub struct RepoConfig {
     #[serde(default)]
     pub version: VersionConfig,
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
+    #[serde(default)]
+    pub conventional: ConventionalConfig,
+    #[serde(default)]
+    pub generated: GeneratedConfig,
+    #[serde(default)]
+    pub commit: RepoCommitConfig,
+}
+
+/// Team commit rules: size limits (generated files don't count) and DCO sign-off
+#[derive(Debug, Default, Deserialize)]
+pub struct RepoCommitConfig {
+    /// Most staged files in one commit before warning (default: no limit)
+    #[serde(default)]
+    pub max_files: Option<usize>,
+
+    /// Most changed lines (added + removed) before warning (default: no limit)
+    #[serde(default)]
+    pub max_lines: Option<usize>,
+
+    /// Add `Signed-off-by:` to every commit (`git commit -s`); Ctrl+S toggles it per commit
+    #[serde(default)]
+    pub signoff: bool,
+}
+
+impl RepoCommitConfig {
+    /// Whether a commit of `files` files and `lines` changed lines goes over a limit
+    pub fn exceeded(&self, files: usize, lines: usize) -> bool {
+        self.max_files.is_some_and(|max| files > max)
+            || self.max_lines.is_some_and(|max| lines > max)
+    }
+}
+
+/// Generated / vendored files: dimmed, collapsed in the Files tab, left out of diff totals
+#[derive(Debug, Deserialize)]
+pub struct GeneratedConfig {
+    /// Glob patterns (`*` wildcard); patterns without `/` match the file name.
+    /// Files with the `linguist-generated` attribute always count as generated.
+    #[serde(default = "default_generated_paths")]
+    pub paths: Vec<String>,
+}
+
+impl Default for GeneratedConfig {
+    fn default() -> Self {
+        Self {
+            paths: default_generated_paths(),
+        }
+    }
+}
+
+fn default_generated_paths() -> Vec<String> {
+    [
+        "Cargo.lock",
+        "package-lock.json",
+        "yarn.lock",
+        "pnpm-lock.yaml",
+        "poetry.lock",
+        "Gemfile.lock",
+        "composer.lock",
+        "go.sum",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
+/// Conventional Commits prefix picker shown when starting a commit message
+#[derive(Debug, Deserialize)]
+pub struct ConventionalConfig {
+    /// Ask for `type(scope): ` before typing the message (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Commit types offered in the picker
+    #[serde(default = "default_conventional_types")]
+    pub types: Vec<String>,
+
+    /// Scopes cycled with Tab in the scope input (free text is always allowed)
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+impl Default for ConventionalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            types: default_conventional_types(),
+            scopes: Vec::new(),
+        }
+    }
+}
+
+fn default_conventional_types() -> Vec<String> {
+    [
+        "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore",
+        "revert",
+    ]
+    .iter()
+    .map(|t| t.to_string())
+    .collect()
+}
+
+/// Towncrier-style changelog fragments assembled at version bump time
+#[derive(Debug, Deserialize)]
+pub struct ChangelogConfig {
+    /// Collect fragments into the changelog on release (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fragment directory (default: ".changes")
+    #[serde(default = "default_changes_dir")]
+    pub dir: String,
+
+    /// Changelog file (default: "CHANGELOG.md")
+    #[serde(default = "default_changelog_file")]
+    pub file: String,
+
+    /// Release heading template (default: "## {version} ({date})")
+    #[serde(default = "default_changelog_heading")]
+    pub heading: String,
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_changes_dir(),
+            file: default_changelog_file(),
+            heading: default_changelog_heading(),
+        }
+    }
+}
+
+fn default_changes_dir() -> String {
+    ".changes".to_string()
+}
+
+fn default_changelog_file() -> String {
+    "CHANGELOG.md".to_string()
+}
+
+fn default_changelog_heading() -> String {
+    "## {version} ({date})".to_string()
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,6 +360,14 @@ pub struct VersionConfig {
     #[serde(default = "default_tag_format")]
     pub tag_format: String,
 
+    /// Push the bump commit and tag right after creating them (default: false)
+    #[serde(default)]
+    pub push: bool,
+
+    /// Per-branch tag formats, first match wins (e.g. "v{version}-beta" on develop)
+    #[serde(default)]
+    pub branch_tag_formats: Vec<BranchTagFormat>,
+
     /// Additional version files to update
     #[serde(default)]
     pub additional_files: Vec<VersionFileConfig>,
@@ -62,6 +381,19 @@ pub struct VersionConfig {
 pub struct VersionFileConfig {
     pub path: String,
     pub pattern: String,
+    /// Shell command run after the version is written (`{version}` / $SIORI_VERSION)
+    #[serde(default)]
+    pub post_update: Option<String>,
+    /// Files regenerated by `post_update`, committed with the bump
+    #[serde(default)]
+    pub outputs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BranchTagFormat {
+    /// Branch name, or a glob with `*` (e.g. "release/*")
+    pub branch: String,
+    pub format: String,
 }
 
 fn default_commit_message() -> String {
@@ -78,12 +410,24 @@ impl Default for VersionConfig {
             confirm: true,
             commit_message: default_commit_message(),
             tag_format: default_tag_format(),
+            push: false,
+            branch_tag_formats: Vec::new(),
             additional_files: Vec::new(),
             ignore: Vec::new(),
         }
     }
 }
 
+impl VersionConfig {
+    /// Tag format for the given branch, falling back to `tag_format`
+    pub fn tag_format_for(&self, branch: &str) -> &str {
+        self.branch_tag_formats
+            .iter()
+            .find(|f| crate::version::branch_matches(&f.branch, branch))
+            .map_or(self.tag_format.as_str(), |f| f.format.as_str())
+    }
+}
+
 impl RepoConfig {
     pub fn load(repo_path: &Path) -> Self {
         let config_path = repo_path.join(".siori.toml");
@@ -102,11 +446,176 @@ impl RepoConfig {
 pub struct UiConfig {
     #[serde(default = "default_true")]
     pub show_hints: bool,
+    /// Files tab ordering, cycled with `S` (saved back to this file)
+    #[serde(default)]
+    pub file_sort: FileSort,
+    /// Capture mouse events; disable to keep the terminal's own text selection
+    #[serde(default = "default_true")]
+    pub mouse: bool,
+    /// No spinner animation; redraw only when something changes
+    #[serde(default)]
+    pub reduced_motion: bool,
+    /// Plain ASCII glyphs, spelled-out states and the cursor on the selected row
+    #[serde(default)]
+    pub screen_reader: bool,
+    /// Language for relative times ("auto" follows LANG)
+    #[serde(default)]
+    pub language: Language,
+    /// Per top-level directory +/− summary line under CHANGES
+    #[serde(default)]
+    pub directory_stats: bool,
+    /// Remote branch labels in the Log tab: all of them, or only the upstream
+    #[serde(default)]
+    pub log_decorations: LogDecorations,
+    /// Log tab ordering: by date within the graph's constraints, or `git log --topo-order`
+    #[serde(default)]
+    pub log_order: LogOrder,
+    /// Commit dates in the Log tab: "3 days ago", "2024-05-03 14:22", or both
+    #[serde(default)]
+    pub date_format: DateFormat,
+    /// Who made each commit, in a column before the Log tab subject
+    #[serde(default)]
+    pub author_column: AuthorColumn,
+    /// Which dialogs ask before acting
+    #[serde(default)]
+    pub confirmations: Confirmations,
+    /// Minutes after the last fetch before ↑/↓ is flagged as stale (0 never flags it)
+    #[serde(default = "default_fetch_stale_minutes")]
+    pub fetch_stale_minutes: u64,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Confirmations {
+    #[default]
+    All,
+    /// Skip dialogs for actions that lose nothing (copying the diff command,
+    /// checking out a commit); discards, deletes and resets still ask
+    Minimal,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogOrder {
+    /// Newest first, parents still after their children (`--date-order`)
+    #[default]
+    Date,
+    /// Each branch's commits kept together (`--topo-order`)
+    Topo,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    #[default]
+    Relative,
+    /// In the commit's own time zone, like `git log --date=iso`
+    Absolute,
+    /// "2024-05-03 14:22 (3 days ago)"
+    Both,
+}
+
+impl DateFormat {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Relative => Self::Absolute,
+            Self::Absolute => Self::Both,
+            Self::Both => Self::Relative,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Relative => "relative",
+            Self::Absolute => "absolute",
+            Self::Both => "both",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorColumn {
+    #[default]
+    Off,
+    /// Author name, cut to a fixed width
+    Name,
+    /// "TO" for Takuma Ogura
+    Initials,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogDecorations {
+    #[default]
+    All,
+    /// Only the current branch's upstream; the rest are counted as "+N"
+    Upstream,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Auto,
+    En,
+    Ja,
 }
 
 impl Default for UiConfig {
     fn default() -> Self {
-        Self { show_hints: true }
+        Self {
+            show_hints: true,
+            file_sort: FileSort::default(),
+            mouse: true,
+            reduced_motion: false,
+            screen_reader: false,
+            language: Language::Auto,
+            directory_stats: false,
+            log_decorations: LogDecorations::All,
+            log_order: LogOrder::Date,
+            date_format: DateFormat::Relative,
+            author_column: AuthorColumn::Off,
+            confirmations: Confirmations::All,
+            fetch_stale_minutes: default_fetch_stale_minutes(),
+        }
+    }
+}
+
+fn default_fetch_stale_minutes() -> u64 {
+    60
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSort {
+    #[default]
+    Path,
+    Status,
+    /// Total added + deleted lines, largest first
+    Size,
+    /// Working tree modification time, newest first
+    Modified,
+}
+
+impl FileSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Path => Self::Status,
+            Self::Status => Self::Size,
+            Self::Size => Self::Modified,
+            Self::Modified => Self::Path,
+        }
+    }
+
+    /// Name used in config.toml and the status message
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Status => "status",
+            Self::Size => "size",
+            Self::Modified => "modified",
+        }
     }
 }
 
@@ -135,6 +644,57 @@ impl Config {
     }
 }
 
+/// Write a single `key = "value"` into a section of the global config,
+/// creating ~/.config/siori/config.toml if there is none yet
+pub fn save_setting(section: &str, key: &str, value: &str) -> std::io::Result<()> {
+    let path = config_path()
+        .or_else(|| {
+            std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config/siori/config.toml"))
+        })
+        .ok_or_else(|| std::io::Error::other("HOME is not set"))?;
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, set_toml_value(&content, section, key, value))
+}
+
+/// Line-based edit that keeps the rest of the file (comments, order) untouched
+fn set_toml_value(content: &str, section: &str, key: &str, value: &str) -> String {
+    let header = format!("[{}]", section);
+    let new_line = format!("{} = \"{}\"", key, value);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    let Some(start) = lines.iter().position(|l| l.trim() == header) else {
+        if !lines.is_empty() && !lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(new_line);
+        return lines.join("\n") + "\n";
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let existing = lines[start + 1..end].iter().position(|l| {
+        l.split_once('=')
+            .is_some_and(|(k, _)| k.trim() == key && !l.trim_start().starts_with('#'))
+    });
+    match existing {
+        Some(i) => lines[start + 1 + i] = new_line,
+        None => {
+            // Keep trailing blank lines after the section's last entry
+            let mut insert_at = end;
+            while insert_at > start + 1 && lines[insert_at - 1].trim().is_empty() {
+                insert_at -= 1;
+            }
+            lines.insert(insert_at, new_line);
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
 fn config_path() -> Option<PathBuf> {
     // 1. XDG準拠: ~/.config/siori/config.toml (Linux/macOS共通)
     if let Some(home) = std::env::var_os("HOME") {
@@ -185,3 +745,130 @@ pub fn get_color(opt: &Option<String>, default: Color) -> Color {
         .map(|s| parse_color(s, default))
         .unwrap_or(default)
 }
+
+/// Match `text` against a config pattern where `*` matches any run of characters
+/// (`/` included), e.g. `[generated] paths` or a branch in `branch_tag_formats`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let regex_pattern = format!("^{}$", regex::escape(pattern).replace(r"\*", ".*"));
+    regex::Regex::new(&regex_pattern)
+        .map(|re| re.is_match(text))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("Cargo.lock", "Cargo.lock"));
+        assert!(!glob_match("Cargo.lock", "Cargo.lock.bak"));
+        assert!(glob_match("*.min.js", "app.min.js"));
+        assert!(glob_match("dist/*", "dist/js/app.js"));
+        assert!(!glob_match("dist/*", "src/dist/app.js"));
+    }
+
+    #[test]
+    fn test_profile_for_remote() {
+        let config: Config = toml::from_str(
+            "[[profiles]]\nname = \"work\"\nuser_name = \"Jane Doe\"\nemail = \"jane@corp.example\"\nhost = \"git.corp.example\"\n\n\
+             [[profiles]]\nname = \"personal\"\nuser_name = \"Jane\"\nemail = \"jane@mail.example\"\nhost = \"github.com\"\n",
+        )
+        .unwrap();
+        let name = |url| profile_for_remote(&config.profiles, url).map(|p| p.name.as_str());
+        assert_eq!(name("git@git.corp.example:team/app.git"), Some("work"));
+        assert_eq!(name("https://github.com/jane/dots.git"), Some("personal"));
+        assert_eq!(
+            name("ssh://git@github.com:22/jane/dots.git"),
+            Some("personal")
+        );
+        assert_eq!(name("https://gitlab.com/jane/x.git"), None);
+    }
+
+    #[test]
+    fn test_set_toml_value() {
+        assert_eq!(
+            set_toml_value("", "ui", "file_sort", "size"),
+            "[ui]\nfile_sort = \"size\"\n"
+        );
+
+        let content = "[ui]\nshow_hints = true\n\n[colors]\ntext = \"white\"\n";
+        assert_eq!(
+            set_toml_value(content, "ui", "file_sort", "size"),
+            "[ui]\nshow_hints = true\nfile_sort = \"size\"\n\n[colors]\ntext = \"white\"\n"
+        );
+
+        let content = "[ui]\n# file_sort = \"path\"\nfile_sort = \"path\"\n";
+        assert_eq!(
+            set_toml_value(content, "ui", "file_sort", "status"),
+            "[ui]\n# file_sort = \"path\"\nfile_sort = \"status\"\n"
+        );
+
+        let parsed: Config =
+            toml::from_str(&set_toml_value("", "ui", "file_sort", "modified")).unwrap();
+        assert_eq!(parsed.ui.file_sort, FileSort::Modified);
+    }
+
+    #[test]
+    fn test_commit_size_limits() {
+        let config: RepoConfig = toml::from_str("[commit]\nmax_files = 10\n").unwrap();
+        assert!(!config.commit.exceeded(10, 5000));
+        assert!(config.commit.exceeded(11, 0));
+        assert!(!RepoConfig::default().commit.exceeded(1000, 100_000));
+        assert!(!config.commit.signoff);
+    }
+
+    #[test]
+    fn test_startup_config() {
+        let config: Config =
+            toml::from_str("[startup]\ntab = \"log\"\nrepo_switcher = true\n").unwrap();
+        assert_eq!(config.startup.tab, StartTab::Log);
+        assert!(config.startup.repo_switcher);
+        assert!(!config.startup.commit_focus);
+        assert_eq!(Config::default().startup.tab, StartTab::Files);
+        let config: Config = toml::from_str("[startup]\ntab = \"branches\"\n").unwrap();
+        assert_eq!(config.startup.tab, StartTab::Branches);
+    }
+
+    #[test]
+    fn test_confirmations() {
+        let config: Config = toml::from_str("[ui]\nconfirmations = \"minimal\"\n").unwrap();
+        assert_eq!(config.ui.confirmations, Confirmations::Minimal);
+        assert_eq!(Config::default().ui.confirmations, Confirmations::All);
+    }
+
+    #[test]
+    fn test_date_format() {
+        let config: Config = toml::from_str("[ui]\ndate_format = \"both\"\n").unwrap();
+        assert_eq!(config.ui.date_format, DateFormat::Both);
+        assert_eq!(DateFormat::Both.next(), DateFormat::Relative);
+    }
+
+    #[test]
+    fn test_author_column() {
+        let config: Config = toml::from_str("[ui]\nauthor_column = \"initials\"\n").unwrap();
+        assert_eq!(config.ui.author_column, AuthorColumn::Initials);
+        assert_eq!(Config::default().ui.author_column, AuthorColumn::Off);
+    }
+
+    #[test]
+    fn test_fetch_stale_minutes() {
+        let config: Config = toml::from_str("[ui]\nfetch_stale_minutes = 0\n").unwrap();
+        assert_eq!(config.ui.fetch_stale_minutes, 0);
+        let config: Config = toml::from_str("[ui]\nmouse = false\n").unwrap();
+        assert_eq!(config.ui.fetch_stale_minutes, 60);
+    }
+
+    #[test]
+    fn test_pager_command() {
+        let config: Config =
+            toml::from_str("[pager]\ncommand = \"delta --side-by-side\"\n").unwrap();
+        assert_eq!(
+            config.pager.argv(),
+            Some(vec!["delta".to_string(), "--side-by-side".to_string()])
+        );
+        let config: Config = toml::from_str("[pager]\ncommand = \"  \"\n").unwrap();
+        assert_eq!(config.pager.argv(), None);
+        assert_eq!(Config::default().pager.argv(), None);
+    }
+}
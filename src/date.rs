@@ -0,0 +1,87 @@
+//! Calendar dates from Unix time, without a date crate
+
+/// Today's date as YYYY-MM-DD (UTC)
+pub fn today() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let (y, m, d) = civil_from_days(secs.div_euclid(86400));
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Current time as "YYYY-MM-DD HH:MM UTC"
+pub fn now_utc() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    format_utc(secs)
+}
+
+pub fn format_utc(secs: i64) -> String {
+    let (y, m, d) = civil_from_days(secs.div_euclid(86400));
+    let minutes = secs.rem_euclid(86400) / 60;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        y,
+        m,
+        d,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// "YYYY-MM-DD HH:MM" in the given UTC offset, e.g. a commit's own time zone
+pub fn format_local(secs: i64, offset_minutes: i32) -> String {
+    let secs = secs + i64::from(offset_minutes) * 60;
+    let (y, m, d) = civil_from_days(secs.div_euclid(86400));
+    let minutes = secs.rem_euclid(86400) / 60;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        y,
+        m,
+        d,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Days since 1970-01-01 → (year, month, day), proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_utc(1_709_222_340), "2024-02-29 15:59 UTC");
+    }
+
+    #[test]
+    fn test_format_local() {
+        assert_eq!(format_local(1_709_222_340, 0), "2024-02-29 15:59");
+        assert_eq!(format_local(1_709_222_340, 540), "2024-03-01 00:59");
+        assert_eq!(format_local(0, -300), "1969-12-31 19:00");
+    }
+}
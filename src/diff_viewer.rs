@@ -1,27 +1,587 @@
 //! Diff viewer for commit details
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
-/// Run diff viewer for a commit
-pub fn run_commit(repo_path: &Path, commit_ref: &str) -> Result<()> {
-    let show_output = Command::new("git")
+/// `git show` of a commit (merges show the combined diff), or with `parent`
+/// a merge against one of its parents (1 = the branch merged into)
+pub fn commit_output(repo_path: &Path, commit_ref: &str, parent: Option<usize>) -> Result<String> {
+    let args = match parent {
+        Some(n) => vec![
+            "diff".to_string(),
+            "--color=always".to_string(),
+            "--stat".to_string(),
+            "--patch".to_string(),
+            format!("{}^{}", commit_ref, n),
+            commit_ref.to_string(),
+        ],
+        None => vec![
+            "show".to_string(),
+            "--color=always".to_string(),
+            commit_ref.to_string(),
+        ],
+    };
+    let output = Command::new("git")
         .current_dir(repo_path)
-        .args(["show", "--color=always", commit_ref])
+        .args(&args)
         .output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
 
-    // Use less as pager for commit view
-    let mut child = Command::new("less")
-        .arg("-R")
-        .stdin(std::process::Stdio::piped())
-        .spawn()?;
+/// Pipe `text` into an external pager (`[pager] command`, or `less -R`) and wait for it
+pub fn run_pager(argv: &[String], text: &str) -> Result<()> {
+    use std::io::Write;
 
-    if let Some(stdin) = child.stdin.as_mut() {
-        use std::io::Write;
-        stdin.write_all(&show_output.stdout)?;
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("Empty pager command"))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to launch {}", program))?;
+    // Taken so it closes: delta and bat wait for the end of input
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit early on `q`; a broken pipe is fine
+        let _ = stdin.write_all(text.as_bytes());
     }
     child.wait()?;
-
     Ok(())
 }
+
+/// A single hunk of a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffHunk {
+    /// Hunk header line (e.g. "@@ -1,3 +1,4 @@ fn main()")
+    pub header: String,
+    /// Hunk body lines, each prefixed with ' ', '+' or '-'
+    pub lines: Vec<String>,
+}
+
+/// Parse unified diff text into hunks. File headers (diff/index/---/+++) are skipped.
+pub fn parse_diff(text: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut in_hunk = false;
+    for line in text.lines() {
+        if line.starts_with("@@") {
+            hunks.push(DiffHunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+            in_hunk = true;
+        } else if line.starts_with("diff ") {
+            // Next file header starts
+            in_hunk = false;
+        } else if in_hunk
+            && let Some(hunk) = hunks.last_mut()
+            && (line.starts_with([' ', '+', '-', '\\']) || line.is_empty())
+        {
+            hunk.lines.push(line.to_string());
+        }
+    }
+    hunks
+}
+
+/// Hunks of one file in a multi-file diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffFile {
+    pub path: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Patch carrying only the changes in `hunk.lines[first..=last]`, to apply to
+/// the index. Staging keeps unpicked removals as context and drops unpicked
+/// additions; `reverse` (unstaging a staged hunk) does the opposite and
+/// inverts the result. None when the range holds no change.
+pub fn line_patch(
+    path: &str,
+    hunk: &DiffHunk,
+    first: usize,
+    last: usize,
+    reverse: bool,
+) -> Option<String> {
+    let picked = |i: usize| (first..=last).contains(&i);
+    let has_change = hunk
+        .lines
+        .iter()
+        .enumerate()
+        .any(|(i, line)| picked(i) && line.starts_with(['+', '-']));
+    if !has_change {
+        return None;
+    }
+    // "@@ -old_start[,n] +new_start[,n] @@"
+    let mut ranges = hunk.header.split(' ').skip(1);
+    let start = |range: Option<&str>, sign: char| -> Option<usize> {
+        range?.strip_prefix(sign)?.split(',').next()?.parse().ok()
+    };
+    let mut old_start = start(ranges.next(), '-')?;
+    let mut new_start = start(ranges.next(), '+')?;
+    if reverse {
+        std::mem::swap(&mut old_start, &mut new_start);
+    }
+
+    let (mut body, mut old, mut new) = (String::new(), 0, 0);
+    let mut emitted = false;
+    for (i, line) in hunk.lines.iter().enumerate() {
+        let kept = match line.chars().next() {
+            Some('+') if picked(i) => Some(line.clone()),
+            Some('-') if picked(i) => Some(line.clone()),
+            Some('+') if reverse => Some(format!(" {}", &line[1..])),
+            Some('-') if !reverse => Some(format!(" {}", &line[1..])),
+            Some('+') | Some('-') => None,
+            // "\ No newline at end of file" belongs to the line before it
+            Some('\\') if !emitted => None,
+            _ => Some(line.clone()),
+        };
+        emitted = kept.is_some();
+        let Some(kept) = kept else {
+            continue;
+        };
+        let kept = match kept.chars().next() {
+            Some('+') if reverse => format!("-{}", &kept[1..]),
+            Some('-') if reverse => format!("+{}", &kept[1..]),
+            _ => kept,
+        };
+        match kept.chars().next() {
+            Some('+') => new += 1,
+            Some('-') => old += 1,
+            Some('\\') => {}
+            _ => {
+                old += 1;
+                new += 1;
+            }
+        }
+        body.push_str(&kept);
+        body.push('\n');
+    }
+    Some(format!(
+        "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n@@ -{old_start},{old} +{new_start},{new} @@\n{body}"
+    ))
+}
+
+/// Split unified diff text into files (`diff --git` / `--- +++` headers) and
+/// their hunks, for `siori pager` / `siori difftool`
+pub fn parse_diff_files(text: &str) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    let mut chunk = String::new();
+    let mut path = String::new();
+    let mut flush = |path: &mut String, chunk: &mut String| {
+        let hunks = parse_diff(chunk);
+        if !hunks.is_empty() {
+            files.push(DiffFile {
+                path: std::mem::take(path),
+                hunks,
+            });
+        }
+        chunk.clear();
+    };
+    for line in text.lines() {
+        if line.starts_with("diff ") {
+            flush(&mut path, &mut chunk);
+            // "diff --git a/x b/y": the new side, until +++ says otherwise
+            path = line.rsplit_once(" b/").map_or(line, |(_, p)| p).to_string();
+        } else if let Some(new) = line.strip_prefix("+++ ")
+            && !chunk.contains("\n@@")
+        {
+            let new = new.split('\t').next().unwrap_or(new);
+            if new != "/dev/null" {
+                path = new.strip_prefix("b/").unwrap_or(new).to_string();
+            } else if let Some(old) = chunk
+                .lines()
+                .find_map(|l| l.strip_prefix("--- "))
+                .map(|o| o.split('\t').next().unwrap_or(o))
+            {
+                path = old.strip_prefix("a/").unwrap_or(old).to_string();
+            }
+        }
+        chunk.push_str(line);
+        chunk.push('\n');
+    }
+    flush(&mut path, &mut chunk);
+    files
+}
+
+/// Drop ANSI color sequences, for diffs piped in by git with color on
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            // Parameters end at the first letter (m, K, ...)
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Position in a standalone diff viewer: one hunk selected across all files
+#[derive(Debug)]
+pub struct DiffPager {
+    pub title: String,
+    pub files: Vec<DiffFile>,
+    pub file: usize,
+    pub hunk: usize,
+}
+
+impl DiffPager {
+    pub fn new(title: String, files: Vec<DiffFile>) -> Self {
+        Self {
+            title,
+            files,
+            file: 0,
+            hunk: 0,
+        }
+    }
+
+    /// Next hunk, moving on to the next file at the end of one
+    pub fn next_hunk(&mut self) {
+        let Some(file) = self.files.get(self.file) else {
+            return;
+        };
+        if self.hunk + 1 < file.hunks.len() {
+            self.hunk += 1;
+        } else if self.file + 1 < self.files.len() {
+            self.file += 1;
+            self.hunk = 0;
+        }
+    }
+
+    pub fn prev_hunk(&mut self) {
+        if self.hunk > 0 {
+            self.hunk -= 1;
+        } else if self.file > 0 {
+            self.file -= 1;
+            self.hunk = self.files[self.file].hunks.len().saturating_sub(1);
+        }
+    }
+
+    pub fn next_file(&mut self) {
+        if self.file + 1 < self.files.len() {
+            self.file += 1;
+            self.hunk = 0;
+        }
+    }
+
+    pub fn prev_file(&mut self) {
+        if self.file > 0 {
+            self.file -= 1;
+        }
+        self.hunk = 0;
+    }
+}
+
+/// Text problems in a file that tend to produce noisy diffs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextIssue {
+    MixedLineEndings,
+    EncodingChanged,
+}
+
+impl TextIssue {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MixedLineEndings => "mixed line endings (LF + CRLF)",
+            Self::EncodingChanged => "encoding changed (no longer UTF-8)",
+        }
+    }
+}
+
+/// Detect line ending mixes and UTF-8 → other encoding changes.
+/// Binary content (containing NUL bytes) is never reported.
+pub fn detect_text_issues(old: Option<&[u8]>, new: &[u8]) -> Vec<TextIssue> {
+    let mut issues = Vec::new();
+    if new.contains(&0) {
+        return issues;
+    }
+
+    let crlf = new.windows(2).filter(|w| w == b"\r\n").count();
+    let lf = new.iter().filter(|&&b| b == b'\n').count();
+    if crlf > 0 && lf > crlf {
+        issues.push(TextIssue::MixedLineEndings);
+    }
+
+    if let Some(old) = old
+        && !old.contains(&0)
+        && std::str::from_utf8(old).is_ok()
+        && std::str::from_utf8(new).is_err()
+    {
+        issues.push(TextIssue::EncodingChanged);
+    }
+    issues
+}
+
+/// Git file mode of a symbolic link
+pub const SYMLINK_MODE: u32 = 0o120000;
+
+/// (old, new) git file mode of a changed file
+pub type ModeChange = (u32, u32);
+
+/// Describe a file mode change, e.g. "100644 → 100755 (+x)"
+pub fn mode_change_label(old: u32, new: u32) -> String {
+    let note = if new == SYMLINK_MODE {
+        " (now a symlink)"
+    } else if old == SYMLINK_MODE {
+        " (no longer a symlink)"
+    } else if new & 0o111 != 0 && old & 0o111 == 0 {
+        " (+x)"
+    } else if old & 0o111 != 0 && new & 0o111 == 0 {
+        " (-x)"
+    } else {
+        ""
+    };
+    format!("{:06o} → {:06o}{}", old, new, note)
+}
+
+/// Human-readable byte count: "512 B", "1.5 KB", "3.2 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Size delta of a binary file, e.g. "bin +1.5 KB"
+pub fn binary_delta_label(old: u64, new: u64) -> String {
+    let sign = if new >= old { '+' } else { '-' };
+    format!("bin {}{}", sign, format_size(new.abs_diff(old)))
+}
+
+/// '+' or '-' when the line adds or removes something. Combined diffs of
+/// merges (`@@@` headers) have one marker column per parent.
+pub fn change_marker(hunk: &DiffHunk, line: &str) -> Option<char> {
+    let columns = hunk
+        .header
+        .chars()
+        .take_while(|&c| c == '@')
+        .count()
+        .saturating_sub(1)
+        .max(1);
+    let markers: String = line.chars().take(columns).collect();
+    if markers.contains('+') {
+        Some('+')
+    } else if markers.contains('-') {
+        Some('-')
+    } else {
+        None
+    }
+}
+
+/// Whether the hunk adds a leftover merge conflict marker
+pub fn has_conflict_markers(hunk: &DiffHunk) -> bool {
+    hunk.lines
+        .iter()
+        .filter_map(|l| l.strip_prefix('+'))
+        .any(|l| l.starts_with("<<<<<<< ") || l.starts_with(">>>>>>> ") || l == "=======")
+}
+
+/// Whether the hunk only changes trailing whitespace
+pub fn is_trailing_whitespace_only(hunk: &DiffHunk) -> bool {
+    let removed: Vec<&str> = hunk
+        .lines
+        .iter()
+        .filter_map(|l| l.strip_prefix('-'))
+        .map(str::trim_end)
+        .collect();
+    let added: Vec<&str> = hunk
+        .lines
+        .iter()
+        .filter_map(|l| l.strip_prefix('+'))
+        .map(str::trim_end)
+        .collect();
+    !added.is_empty() && removed == added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_marker() {
+        let hunk = DiffHunk {
+            header: "@@ -1,2 +1,2 @@".to_string(),
+            lines: Vec::new(),
+        };
+        assert_eq!(change_marker(&hunk, "+added"), Some('+'));
+        assert_eq!(change_marker(&hunk, "-removed"), Some('-'));
+        assert_eq!(change_marker(&hunk, " +context"), None);
+        let combined = DiffHunk {
+            header: "@@@ -1,2 -1,2 +1,3 @@@".to_string(),
+            lines: Vec::new(),
+        };
+        assert_eq!(change_marker(&combined, " +from parent 1"), Some('+'));
+        assert_eq!(change_marker(&combined, "- dropped"), Some('-'));
+        assert_eq!(change_marker(&combined, "  -context"), None);
+    }
+
+    #[test]
+    fn test_line_patch() {
+        let hunk = DiffHunk {
+            header: "@@ -3,3 +3,3 @@".to_string(),
+            lines: ["-a", "-b", "+A", "+B", " c"].map(String::from).to_vec(),
+        };
+        // Stage "-b" and "+A" only: "a" stays, "B" is left in the work tree
+        let patch = line_patch("f", &hunk, 1, 2, false).unwrap();
+        assert!(patch.ends_with("@@ -3,3 +3,3 @@\n a\n-b\n+A\n c\n"));
+        // Unstage "+B" only: the other staged lines are context in the index
+        let patch = line_patch("f", &hunk, 3, 3, true).unwrap();
+        assert!(patch.ends_with("@@ -3,3 +3,2 @@\n A\n-B\n c\n"));
+        assert_eq!(line_patch("f", &hunk, 4, 4, false), None);
+    }
+
+    #[test]
+    fn test_parse_diff_hunks() {
+        let text = "diff --git a/f.txt b/f.txt
+index 1111111..2222222 100644
+--- a/f.txt
++++ b/f.txt
+@@ -1,2 +1,2 @@
+-old
++new
+ same
+@@ -10 +10,2 @@ fn main()
+ ctx
++added
+";
+        let hunks = parse_diff(text);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header, "@@ -1,2 +1,2 @@");
+        assert_eq!(hunks[0].lines, vec!["-old", "+new", " same"]);
+        assert_eq!(hunks[1].header, "@@ -10 +10,2 @@ fn main()");
+        assert_eq!(hunks[1].lines.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_diff_files() {
+        let text = "diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1 +1 @@
+-old
++new
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/mode.sh b/mode.sh
+old mode 100644
+new mode 100755
+";
+        let files = parse_diff_files(text);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/a.rs", "gone.txt"]);
+        assert_eq!(files[1].hunks[0].lines, vec!["-bye"]);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[32m+new\x1b[m"), "+new");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn test_diff_pager_navigation() {
+        let hunk = DiffHunk {
+            header: "@@ -1 +1 @@".to_string(),
+            lines: Vec::new(),
+        };
+        let file = |path: &str, n: usize| DiffFile {
+            path: path.to_string(),
+            hunks: vec![hunk.clone(); n],
+        };
+        let mut pager = DiffPager::new("t".to_string(), vec![file("a", 2), file("b", 1)]);
+        pager.next_hunk();
+        pager.next_hunk();
+        assert_eq!((pager.file, pager.hunk), (1, 0));
+        pager.next_hunk();
+        assert_eq!((pager.file, pager.hunk), (1, 0));
+        pager.prev_hunk();
+        assert_eq!((pager.file, pager.hunk), (0, 1));
+        pager.prev_file();
+        assert_eq!((pager.file, pager.hunk), (0, 0));
+    }
+
+    #[test]
+    fn test_detect_text_issues() {
+        assert!(detect_text_issues(None, b"a\nb\n").is_empty());
+        assert!(detect_text_issues(None, b"a\r\nb\r\n").is_empty());
+        assert_eq!(
+            detect_text_issues(None, b"a\r\nb\n"),
+            vec![TextIssue::MixedLineEndings]
+        );
+        assert_eq!(
+            detect_text_issues(Some("caf\u{e9}\n".as_bytes()), b"caf\xe9\n"),
+            vec![TextIssue::EncodingChanged]
+        );
+        // Binary content is ignored
+        assert!(detect_text_issues(Some(b"a"), b"\0\xff\r\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_commit_guards() {
+        let hunk = |lines: &[&str]| DiffHunk {
+            header: "@@ -1 +1 @@".to_string(),
+            lines: lines.iter().map(|l| l.to_string()).collect(),
+        };
+        assert!(has_conflict_markers(&hunk(&[
+            "+<<<<<<< HEAD",
+            "+a",
+            "+=======",
+            "+b"
+        ])));
+        assert!(!has_conflict_markers(&hunk(&[" <<<<<<< HEAD", "+a"])));
+        assert!(is_trailing_whitespace_only(&hunk(&["-foo  ", "+foo"])));
+        assert!(!is_trailing_whitespace_only(&hunk(&["-foo", "+bar"])));
+        assert!(!is_trailing_whitespace_only(&hunk(&["-foo"])));
+    }
+
+    #[test]
+    fn test_mode_change_label() {
+        assert_eq!(
+            mode_change_label(0o100644, 0o100755),
+            "100644 → 100755 (+x)"
+        );
+        assert_eq!(
+            mode_change_label(0o100755, 0o100644),
+            "100755 → 100644 (-x)"
+        );
+        assert_eq!(
+            mode_change_label(0o100644, SYMLINK_MODE),
+            "100644 → 120000 (now a symlink)"
+        );
+    }
+
+    #[test]
+    fn test_binary_size_labels() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+        assert_eq!(binary_delta_label(1024, 2560), "bin +1.5 KB");
+        assert_eq!(binary_delta_label(2048, 1024), "bin -1.0 KB");
+    }
+
+    #[test]
+    fn test_parse_diff_empty() {
+        assert!(parse_diff("").is_empty());
+    }
+}
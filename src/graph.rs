@@ -0,0 +1,184 @@
+//! Lane layout for the Log tab's commit graph
+//!
+//! Commits arrive children first (a topological revwalk). Each lane waits for
+//! one commit id; a commit takes the lane expecting it, hands it on to its first
+//! parent and opens or joins lanes for the other parents.
+
+/// Graph text drawn beside one commit: two characters per lane
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphRow {
+    /// Lane of the commit's node
+    pub column: usize,
+    /// Node line left of the node symbol
+    pub before: String,
+    /// Node line right of the node symbol (lanes closing into this commit)
+    pub after: String,
+    /// Second line: lanes continuing down, branching out to merge parents
+    pub links: String,
+}
+
+/// Builds rows one commit at a time, so more history can be appended later
+#[derive(Debug, Default)]
+pub struct GraphBuilder<T> {
+    lanes: Vec<Option<T>>,
+}
+
+impl<T: Copy + PartialEq> GraphBuilder<T> {
+    pub fn new() -> Self {
+        Self { lanes: Vec::new() }
+    }
+
+    /// Place the next commit (after all of its children) and return its row
+    pub fn push(&mut self, id: T, parents: &[T]) -> GraphRow {
+        let column = match self.lanes.iter().position(|l| *l == Some(id)) {
+            Some(column) => column,
+            None => self.free_lane(),
+        };
+        // Other branches whose next commit is this one end here
+        let closing: Vec<usize> = (0..self.lanes.len())
+            .filter(|&i| i != column && self.lanes[i] == Some(id))
+            .collect();
+
+        let mut node = self.cells();
+        for &lane in &closing {
+            let glyph = if lane > column { '╯' } else { '╰' };
+            draw_horizontal(&mut node, &self.lanes, column, lane, glyph);
+            self.lanes[lane] = None;
+        }
+
+        // The first parent continues straight down
+        self.lanes[column] = parents.first().copied();
+        let mut joins: Vec<(usize, bool)> = Vec::new();
+        for &parent in parents.iter().skip(1) {
+            match self.lanes.iter().position(|l| *l == Some(parent)) {
+                Some(lane) => joins.push((lane, false)),
+                None => {
+                    let lane = self.free_lane();
+                    self.lanes[lane] = Some(parent);
+                    joins.push((lane, true));
+                }
+            }
+        }
+
+        let mut links = self.cells();
+        for &(lane, opened) in &joins {
+            if lane == column {
+                continue;
+            }
+            let glyph = match (lane > column, opened) {
+                (true, true) => '╮',
+                (false, true) => '╭',
+                (true, false) => '┤',
+                (false, false) => '├',
+            };
+            draw_horizontal(&mut links, &self.lanes, column, lane, glyph);
+            let branch = if lane > column { '├' } else { '┤' };
+            links[column * 2] = match links[column * 2] {
+                '├' | '┤' if links[column * 2] != branch => '┼',
+                '│' => branch,
+                other => other,
+            };
+        }
+
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+
+        let before: String = node[..column * 2].iter().collect();
+        let after: String = node[column * 2 + 1..].iter().collect();
+        GraphRow {
+            column,
+            before,
+            after: after.trim_end().to_string(),
+            links: links.iter().collect::<String>().trim_end().to_string(),
+        }
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(|l| l.is_none()) {
+            Some(lane) => lane,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+
+    /// A vertical bar for every open lane
+    fn cells(&self) -> Vec<char> {
+        self.lanes
+            .iter()
+            .flat_map(|l| [if l.is_some() { '│' } else { ' ' }, ' '])
+            .collect()
+    }
+}
+
+/// Horizontal line from the node's lane to `lane`, crossing the lanes between
+fn draw_horizontal<T>(
+    row: &mut [char],
+    lanes: &[Option<T>],
+    column: usize,
+    lane: usize,
+    end: char,
+) {
+    let (from, to) = (column.min(lane), column.max(lane));
+    for i in from..to {
+        row[i * 2 + 1] = '─';
+        if i > from {
+            row[i * 2] = if lanes[i].is_some() { '┼' } else { '─' };
+        }
+    }
+    row[lane * 2] = end;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(commits: &[(u32, &[u32])]) -> Vec<String> {
+        let mut builder = GraphBuilder::new();
+        commits
+            .iter()
+            .flat_map(|(id, parents)| {
+                let row = builder.push(*id, parents);
+                [format!("{}*{}", row.before, row.after), row.links]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_history_is_one_lane() {
+        assert_eq!(
+            layout(&[(3, &[2]), (2, &[1]), (1, &[])]),
+            ["*", "│", "*", "│", "*", ""]
+        );
+    }
+
+    #[test]
+    fn test_merge_opens_and_closes_a_lane() {
+        // 4 merges 3 (on a side branch) into 2; both come from 1
+        let rows = layout(&[(4, &[2, 3]), (3, &[1]), (2, &[1]), (1, &[])]);
+        assert_eq!(rows, ["*", "├─╮", "│ *", "│ │", "* │", "│ │", "*─╯", ""]);
+    }
+
+    #[test]
+    fn test_merge_into_existing_lane() {
+        // 5 and 4 are both on screen when 5 merges 4's history
+        let rows = layout(&[(6, &[5]), (7, &[4]), (5, &[3, 4])]);
+        assert_eq!(rows[2], "│ *");
+        assert_eq!(rows[3], "│ │");
+        let rows = layout(&[(7, &[4]), (6, &[5]), (5, &[3, 4])]);
+        assert_eq!(rows[4], "│ *");
+        assert_eq!(rows[5], "├─┤");
+    }
+
+    #[test]
+    fn test_branch_row_crosses_open_lanes() {
+        let mut builder = GraphBuilder::new();
+        builder.push(10, &[1]);
+        builder.push(20, &[2]);
+        let row = builder.push(1, &[0, 9]);
+        assert_eq!(row.column, 0);
+        assert_eq!(row.links, "├─┼─╮");
+    }
+}
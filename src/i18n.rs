@@ -0,0 +1,103 @@
+//! Minimal localization for the few strings that are built from numbers
+
+use std::sync::OnceLock;
+
+use crate::config::{Config, Language};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// `ui.language` from config, or the LC_ALL / LC_MESSAGES / LANG environment
+    pub fn current() -> Self {
+        static LOCALE: OnceLock<Locale> = OnceLock::new();
+        *LOCALE.get_or_init(|| match Config::load().ui.language {
+            Language::En => Locale::En,
+            Language::Ja => Locale::Ja,
+            Language::Auto => Self::from_env(),
+        })
+    }
+
+    fn from_env() -> Self {
+        // The first non-empty variable wins, as in setlocale(3)
+        let lang = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        if lang.starts_with("ja") {
+            Locale::Ja
+        } else {
+            Locale::En
+        }
+    }
+}
+
+/// "3 hours ago" / "3時間前" for a duration in seconds
+pub fn humanize_ago(secs: i64, locale: Locale) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+
+    // Clock skew can put commits slightly in the future
+    if secs < MINUTE {
+        return match locale {
+            Locale::En => "just now".to_string(),
+            Locale::Ja => "たった今".to_string(),
+        };
+    }
+    let (n, en, ja) = if secs < HOUR {
+        (secs / MINUTE, "min", "分")
+    } else if secs < DAY {
+        (secs / HOUR, "hour", "時間")
+    } else if secs < 7 * DAY {
+        (secs / DAY, "day", "日")
+    } else if secs < 30 * DAY {
+        (secs / (7 * DAY), "week", "週間")
+    } else if secs < 365 * DAY {
+        (secs / (30 * DAY), "month", "か月")
+    } else {
+        (secs / (365 * DAY), "year", "年")
+    };
+    match locale {
+        // "min" reads the same in singular and plural
+        Locale::En if n == 1 || en == "min" => format!("{} {} ago", n, en),
+        Locale::En => format!("{} {}s ago", n, en),
+        Locale::Ja => format!("{}{}前", n, ja),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_ago_en() {
+        let en = |secs| humanize_ago(secs, Locale::En);
+        assert_eq!(en(-5), "just now");
+        assert_eq!(en(59), "just now");
+        assert_eq!(en(60), "1 min ago");
+        assert_eq!(en(150), "2 min ago");
+        assert_eq!(en(3600), "1 hour ago");
+        assert_eq!(en(7200), "2 hours ago");
+        assert_eq!(en(86400), "1 day ago");
+        assert_eq!(en(8 * 86400), "1 week ago");
+        assert_eq!(en(21 * 86400), "3 weeks ago");
+        assert_eq!(en(61 * 86400), "2 months ago");
+        assert_eq!(en(365 * 86400), "1 year ago");
+        assert_eq!(en(800 * 86400), "2 years ago");
+    }
+
+    #[test]
+    fn test_humanize_ago_ja() {
+        let ja = |secs| humanize_ago(secs, Locale::Ja);
+        assert_eq!(ja(10), "たった今");
+        assert_eq!(ja(180), "3分前");
+        assert_eq!(ja(3600), "1時間前");
+        assert_eq!(ja(14 * 86400), "2週間前");
+        assert_eq!(ja(45 * 86400), "1か月前");
+    }
+}
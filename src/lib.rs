@@ -1,5 +1,9 @@
 pub mod app;
+pub mod changelog;
 pub mod config;
+pub mod date;
 pub mod diff_viewer;
+pub mod graph;
+pub mod i18n;
 pub mod ui;
 pub mod version;
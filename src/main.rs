@@ -1,8 +1,14 @@
 use anyhow::{Context, Result};
 use crossterm::{
     ExecutableCommand,
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+        supports_keyboard_enhancement,
+    },
 };
 use git2::{Repository, Status, StatusOptions};
 use siori::{app, config, diff_viewer, ui};
@@ -12,12 +18,24 @@ use std::process::Command;
 use std::time::{Duration, Instant};
 
 fn run() -> Result<()> {
+    let mut app = app::App::new()?;
+
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
-    stdout().execute(EnableMouseCapture)?;
+    // Kitty keyboard protocol: reports Shift+Enter / Ctrl+Enter as distinct chords.
+    // Legacy terminals don't answer the query and keep plain key events.
+    app.keyboard_enhanced = supports_keyboard_enhancement().unwrap_or(false);
+    if app.keyboard_enhanced {
+        stdout().execute(PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES,
+        ))?;
+    }
+    let mut mouse_captured = app.mouse_capture;
+    if mouse_captured {
+        stdout().execute(EnableMouseCapture)?;
+    }
     let mut terminal = ratatui::Terminal::new(ratatui::prelude::CrosstermBackend::new(stdout()))?;
 
-    let mut app = app::App::new()?;
     let mut last_activity = Instant::now();
     let mut last_refresh = Instant::now();
 
@@ -32,18 +50,24 @@ fn run() -> Result<()> {
         }
 
         if app.processing.is_active() {
-            if last_spinner_tick.elapsed() >= Duration::from_millis(80) {
+            if !app.reduced_motion && last_spinner_tick.elapsed() >= Duration::from_millis(80) {
                 app.tick_spinner();
                 last_spinner_tick = Instant::now();
                 needs_redraw = true;
             }
+            // Redraw when an operation finishes, even if a queued one starts right away
+            let finished = app.operation_results.len();
             app.check_processing()?;
-            if !app.processing.is_active() {
+            if !app.processing.is_active() || app.operation_results.len() != finished {
                 needs_redraw = true;
             }
         }
 
-        let poll_timeout = if app.processing.is_active() {
+        if app.poll_commit_stats() {
+            needs_redraw = true;
+        }
+
+        let poll_timeout = if app.processing.is_active() || app.commit_stats_loading() {
             Duration::from_millis(80)
         } else {
             Duration::from_millis(500)
@@ -51,19 +75,34 @@ fn run() -> Result<()> {
 
         if event::poll(poll_timeout)? {
             match event::read()? {
+                // Input stays enabled while a background operation runs; actions that
+                // write the index wait for it (App::ensure_writable)
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    if !app.processing.is_active() {
-                        app.handle_key(key.code, key.modifiers)?;
-                        last_activity = Instant::now();
-                        needs_redraw = true;
+                    app.handle_key(key.code, key.modifiers)?;
+                    if app.editor_requested {
+                        app.editor_requested = false;
+                        compose_commit_in_editor(&mut app, mouse_captured)?;
+                        terminal.clear()?;
+                    }
+                    if let Some(text) = app.pager_requested.take() {
+                        show_in_pager(&mut app, &text, mouse_captured)?;
+                        terminal.clear()?;
                     }
+                    if app.mouse_capture != mouse_captured {
+                        mouse_captured = app.mouse_capture;
+                        if mouse_captured {
+                            stdout().execute(EnableMouseCapture)?;
+                        } else {
+                            stdout().execute(DisableMouseCapture)?;
+                        }
+                    }
+                    last_activity = Instant::now();
+                    needs_redraw = true;
                 }
                 Event::Mouse(mouse) => {
-                    if !app.processing.is_active() {
-                        app.handle_mouse(mouse)?;
-                        last_activity = Instant::now();
-                        needs_redraw = true;
-                    }
+                    app.handle_mouse(mouse)?;
+                    last_activity = Instant::now();
+                    needs_redraw = true;
                 }
                 Event::Resize(..) => {
                     needs_redraw = true;
@@ -83,12 +122,98 @@ fn run() -> Result<()> {
         }
     }
 
+    if app.keyboard_enhanced {
+        stdout().execute(PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
-    stdout().execute(DisableMouseCapture)?;
+    if mouse_captured {
+        stdout().execute(DisableMouseCapture)?;
+    }
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
 
+/// Suspend the TUI, edit COMMIT_EDITMSG in the user's editor, then commit the result
+fn compose_commit_in_editor(app: &mut app::App, mouse_captured: bool) -> Result<()> {
+    let path = match app.write_commit_template() {
+        Ok(path) => path,
+        Err(e) => {
+            app.message = Some((format!("Failed to write COMMIT_EDITMSG: {}", e), true));
+            return Ok(());
+        }
+    };
+    let editor_cmd = app.commit_editor();
+    let parts: Vec<&str> = editor_cmd.split_whitespace().collect();
+    let Some((cmd, extra_args)) = parts.split_first() else {
+        app.message = Some(("Empty editor command".to_string(), true));
+        return Ok(());
+    };
+
+    suspend_tui(app, mouse_captured)?;
+    let status = Command::new(cmd)
+        .args(extra_args)
+        .arg(&path)
+        .current_dir(&app.repo_path)
+        .status();
+    resume_tui(app, mouse_captured)?;
+
+    match status {
+        Ok(status) if status.success() => app.commit_from_editor(&path)?,
+        Ok(_) => {
+            app.message = Some((
+                "Editor exited with an error; commit aborted".to_string(),
+                true,
+            ));
+        }
+        Err(e) => {
+            app.message = Some((format!("Failed to launch {}: {}", cmd, e), true));
+        }
+    }
+    Ok(())
+}
+
+/// Suspend the TUI and page `text` through `[pager] command` (`o`: operation output)
+fn show_in_pager(app: &mut app::App, text: &str, mouse_captured: bool) -> Result<()> {
+    let Some(argv) = app.pager.clone() else {
+        return Ok(());
+    };
+    suspend_tui(app, mouse_captured)?;
+    let result = diff_viewer::run_pager(&argv, text);
+    resume_tui(app, mouse_captured)?;
+    if let Err(e) = result {
+        app.message = Some((format!("{:#}", e), true));
+    }
+    Ok(())
+}
+
+/// Hand the terminal to a child program: normal screen, cooked mode, no mouse reports
+fn suspend_tui(app: &app::App, mouse_captured: bool) -> Result<()> {
+    if app.keyboard_enhanced {
+        stdout().execute(PopKeyboardEnhancementFlags)?;
+    }
+    if mouse_captured {
+        stdout().execute(DisableMouseCapture)?;
+    }
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+    Ok(())
+}
+
+/// Take the terminal back after `suspend_tui`
+fn resume_tui(app: &app::App, mouse_captured: bool) -> Result<()> {
+    stdout().execute(EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    if mouse_captured {
+        stdout().execute(EnableMouseCapture)?;
+    }
+    if app.keyboard_enhanced {
+        stdout().execute(PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES,
+        ))?;
+    }
+    Ok(())
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
@@ -101,12 +226,46 @@ fn main() {
         return;
     }
 
+    // Standalone diff viewer: `git difftool -x "siori difftool"` / GIT_PAGER="siori pager"
+    if args.len() >= 2 && (args[1] == "difftool" || args[1] == "pager") {
+        let result = if args[1] == "difftool" {
+            difftool_mode(&args[2..])
+        } else {
+            pager_mode()
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     if args.iter().any(|a| a == "--check") {
-        match check_mode() {
-            Ok(_) => {
+        let strict = args.iter().any(|a| a == "--strict");
+        let remote = args.iter().any(|a| a == "--remote");
+        match check_mode(remote) {
+            Ok(problems) if problems.is_empty() => {
                 println!("siori: All checks passed!");
                 std::process::exit(0);
             }
+            Ok(problems) => {
+                // One `problem<TAB>kind<TAB>message` line each, for scripts to pick out
+                for (kind, message) in &problems {
+                    println!("problem\t{}\t{}", kind, message);
+                }
+                // Only --strict turns what was found into a failing exit code
+                let count = format!(
+                    "{} problem{}",
+                    problems.len(),
+                    if problems.len() == 1 { "" } else { "s" }
+                );
+                if strict {
+                    eprintln!("siori: Check failed: {}", count);
+                    std::process::exit(1);
+                }
+                println!("siori: Found: {}", count);
+                std::process::exit(0);
+            }
             Err(e) => {
                 eprintln!("siori: Check failed: {:#}", e);
                 std::process::exit(1);
@@ -114,23 +273,88 @@ fn main() {
         }
     }
 
+    if args.iter().any(|a| a == "--demo") {
+        if let Err(e) = demo_mode() {
+            eprintln!("siori: Demo failed: {:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.iter().any(|a| a == "--profile-startup") {
+        if let Err(e) = profile_startup() {
+            eprintln!("siori: Profiling failed: {:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     if args.iter().any(|a| a == "--help" || a == "-h") {
         println!("siori - minimal git TUI");
         println!();
         println!("Usage: siori [OPTIONS]");
         println!("       siori diff [-C <path>] <commit>              Show diff for commit");
+        println!("       siori diff [-C <path>] <commit> --parent <n> Merge commit vs parent n");
         println!("       siori diff [-C <path>] --file <path>         Show file diff (unstaged)");
         println!("       siori diff [-C <path>] --file <path> --staged Show file diff (staged)");
+        println!("       siori difftool <old> <new>                  Browse the diff of two files");
+        println!(
+            "       <git command> | siori pager                 Browse a diff from git (GIT_PAGER)"
+        );
         println!();
         println!("Options:");
         println!("  --check    Run checks without starting TUI");
+        println!("  --check --strict  Also exit 1 on unpushed tags or a merge/rebase in progress");
+        println!("  --check --remote  Also ask origin which tags it has (git ls-remote)");
+        println!("  --profile-startup  Print time spent in each startup phase");
+        println!("  --demo     Open a throwaway sample repository (deleted on exit)");
         println!("  --help     Show this help message");
         println!();
         println!("Keybindings (Files tab):");
-        println!("  Enter      Copy diff command to clipboard");
+        println!("  Enter      Copy diff command to clipboard (nested repo: switch into it)");
         println!("  Space      Stage/unstage file");
-        println!("  c          Enter commit message");
+        println!("  v          Mark file (Space/x then act on all marked files)");
+        println!(
+            "  d          Show hunks (Space: stage/unstage, V: pick lines, x: discard, a: show -diff file)"
+        );
+        println!(
+            "  K          Quick look: first changed lines of the file (j/k: next file, Enter: hunks)"
+        );
+        println!(
+            "  B          Review session: changes since it started (Space: approve, x: reject, e: end)"
+        );
+        println!("  x / X      Discard file / all changes (untracked files go to the trash)");
+        println!("  s          Stash changes");
+        println!("  i          Add untracked file to .gitignore");
+        println!(
+            "  n          Rename / move tracked file (marked files: into a directory) with git mv"
+        );
+        println!("  N          Intent-to-add untracked file (git add -N)");
+        println!("  u          Restore file deleted in the working tree");
+        println!("  W          WIP commit: stage everything, commit \"WIP: <time>\"");
+        println!("  U          Undo a WIP commit at HEAD back into the working tree");
+        println!("  g          Show/hide generated files (lockfiles, linguist-generated)");
+        println!("  H          Hide/unhide local changes (skip-worktree)");
+        println!("  S          Cycle file sort (path/status/size/modified)");
+        println!("  f          Show only untracked / modified / deleted files (Esc: all)");
+        println!("  F          Add changelog fragment (.changes/)");
+        println!("  D          Delete untracked file from disk");
+        println!("  c          Enter commit message (type/scope picker first if enabled)");
+        println!("  E          Write commit message in $GIT_EDITOR / $EDITOR");
+        println!("  S-Enter    New line in commit message (also Alt+Enter, Ctrl+J)");
+        println!("  Ctrl+T     Add Co-authored-by trailers (in commit message)");
+        println!("  C-Enter    Empty commit with the typed message (also Ctrl+E)");
+        println!("  Ctrl+D     Set the commit date (\"yesterday 17:00\", in commit message)");
+        println!("  Ctrl+S     Toggle Signed-off-by (in commit message)");
+        println!(
+            "  Ctrl+G     Fill the message from commit.suggest_command (staged diff on stdin)"
+        );
+        println!("  Ctrl+F     Pick which staged files to commit (in commit message)");
+        println!("  Ctrl+O     Commit only the marked/selected files (git commit -- <paths>)");
+        println!("  Ctrl+A     Commit all tracked changes (git commit -a)");
         println!("  P          Push to remote");
+        println!("  y          Copy file path to clipboard");
+        println!("  o          Show full output of the last operation ([pager] command if set)");
         println!("  r          Switch repository (for nested repos)");
         println!("  R          Refresh (full reload)");
         println!("  j/k/Up/Down Navigate files");
@@ -138,22 +362,71 @@ fn main() {
         println!("  q          Quit");
         println!();
         println!("Keybindings (Log tab):");
-        println!("  Enter      Copy diff command to clipboard");
+        println!("  Enter / l  Commit details: message, author, parents, files (Enter: file diff)");
+        println!("  d          Copy diff command to clipboard (in commit details)");
+        println!("  /          Search subjects, authors and hashes (n / N: next / previous)");
         println!("  j/k/Up/Down Navigate commits");
         println!("  e          Edit commit message (amend HEAD)");
+        println!("  u          Undo HEAD commit (reset --soft HEAD~1, changes stay staged)");
+        println!("  c          Check out the commit (detached HEAD; b in the dialog returns)");
+        println!(
+            "  v / Ctrl+R Revert the commit (staged with a Revert \"…\" message; conflicts show as UU)"
+        );
+        println!("  Space      Mark a commit as one end of a cherry-pick range or comparison");
+        println!("  d          Compare the marked and selected commits (git diff A..B)");
+        println!("  D          Cycle commit dates: relative / absolute / both");
+        println!(
+            "  C          Cherry-pick the commit, or the marked range, onto the current branch"
+        );
+        println!("  1 / 2      Jump to first / second parent (merges are marked ◆)");
+        println!("  p          Files vs parent 1 / 2 / combined diff (merge, in commit details)");
         println!("  t          Create/edit tag");
         println!("  T          Push all tags");
         println!("  x          Delete tag");
+        println!("  a          Tag details (tagger, date, annotation, target commit)");
+        println!("  g          All tags by version or date (s); Enter selects the tagged commit");
+        println!("  L          Reflog: check out (c) or reset the branch (r) to an earlier HEAD");
         println!("  P          Push to remote");
         println!("  p          Pull from remote");
+        println!("  f          Fetch from origin");
+        println!("  i          Incoming: commits and files pull would bring in (p: pull);");
+        println!("             also Enter on the ↓ row above the commits when behind");
+        println!("  y / Y      Copy short / full commit hash to clipboard");
+        println!("  A          Toggle all branches (every local and remote branch, not just HEAD)");
+        println!("  s          Copy \"<hash>  <subject>\" to clipboard");
+        println!("  o          Show full output of the last operation ([pager] command if set)");
         println!("  r          Switch repository (for nested repos)");
         println!("  R          Refresh (full reload)");
+        println!("  Tab        Switch to Stash tab");
+        println!("  q          Quit");
+        println!();
+        println!("Keybindings (Stash tab):");
+        println!("  Enter      Copy diff command to clipboard");
+        println!("  a          Apply stash");
+        println!("  p          Pop stash");
+        println!("  x          Drop stash");
+        println!("  Tab        Switch to Branches tab");
+        println!("  q          Quit");
+        println!();
+        println!("Keybindings (Branches tab):");
+        println!("  Enter      Check out branch");
+        println!("  n          Create a branch at HEAD and switch to it");
+        println!("  e          Rename branch");
+        println!("  x          Delete branch (asks first when not merged into HEAD)");
         println!("  Tab        Switch to Files tab");
         println!("  q          Quit");
         println!();
+        println!("Any tab:");
+        println!("  I          Switch identity ([[profiles]] in config.toml)");
+        println!("  Q          Start / stop recording a key macro");
+        println!("  @          Replay the recorded keys (stops at the first error)");
+        println!("  ^          Switch to the parent repository and back (nested repos)");
+        println!("  !          Remove a stale .git/index.lock (when the banner reports one)");
+        println!();
         println!("Mouse:");
         println!("  Click      Select item / Switch tab");
         println!("  Scroll     Navigate up/down");
+        println!("  M          Toggle mouse capture (off: select text in the terminal)");
         std::process::exit(0);
     }
 
@@ -169,8 +442,208 @@ fn main() {
     }
 }
 
-fn check_mode() -> Result<()> {
-    let repo = Repository::discover(".").context("Not a git repository")?;
+/// `siori --demo`: run the TUI on a sample repository in a temp directory
+fn demo_mode() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("siori-demo-{}", std::process::id()));
+    let result = create_demo_repo(&dir).and_then(|repo| {
+        std::env::set_current_dir(&repo)?;
+        run()
+    });
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+/// Branches, a merge, tags, a stash, an unpushed commit and staged/unstaged/untracked
+/// files, with a bare `origin` next to it so push and pull work
+fn create_demo_repo(dir: &std::path::Path) -> Result<PathBuf> {
+    let repo = dir.join("demo");
+    let origin = dir.join("origin.git");
+    std::fs::create_dir_all(&repo)?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    // Commits are spread over the last few days, four hours apart
+    let clock = std::cell::Cell::new(72);
+    let git = |hours_ago: u64, args: &[&str]| -> Result<()> {
+        let date = format!("@{} +0000", now - hours_ago * 3600);
+        let output = Command::new("git")
+            .current_dir(&repo)
+            .args(args)
+            .env("GIT_AUTHOR_NAME", "Demo User")
+            .env("GIT_AUTHOR_EMAIL", "demo@example.com")
+            .env("GIT_COMMITTER_NAME", "Demo User")
+            .env("GIT_COMMITTER_EMAIL", "demo@example.com")
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_DATE", &date)
+            .output()
+            .context("Failed to run git")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {}: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    };
+    let write = |path: &str, text: &str| -> Result<()> {
+        let path = repo.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, text).context("Failed to write demo file")
+    };
+    let commit = |message: &str, files: &[(&str, &str)]| -> Result<()> {
+        for (path, text) in files {
+            write(path, text)?;
+        }
+        git(clock.get(), &["add", "-A"])?;
+        git(clock.get(), &["commit", "-q", "-m", message])?;
+        clock.set(clock.get() - 4);
+        Ok(())
+    };
+
+    git(0, &["init", "-q", "-b", "main"])?;
+    git(0, &["config", "user.name", "Demo User"])?;
+    git(0, &["config", "user.email", "demo@example.com"])?;
+    // The user's global signing and hooks would prompt or fail on these scripted commits
+    git(0, &["config", "commit.gpgsign", "false"])?;
+    git(0, &["config", "tag.gpgsign", "false"])?;
+    git(0, &["config", "core.hooksPath", ""])?;
+    commit(
+        "Initial commit",
+        &[
+            ("README.md", "# demo\n\nA sample project for siori.\n"),
+            ("src/main.rs", "fn main() {\n    println!(\"hello\");\n}\n"),
+        ],
+    )?;
+    commit(
+        "Add greeting module",
+        &[(
+            "src/greet.rs",
+            "pub fn greet(name: &str) -> String {\n    format!(\"hello, {name}\")\n}\n",
+        )],
+    )?;
+    git(clock.get(), &["tag", "v0.1.0"])?;
+
+    git(clock.get(), &["checkout", "-q", "-b", "feature/login"])?;
+    commit("Add login form", &[("src/login.rs", "pub fn login() {}\n")])?;
+    commit(
+        "Validate passwords",
+        &[(
+            "src/login.rs",
+            "pub fn login() {}\n\npub fn valid(password: &str) -> bool {\n    password.len() >= 8\n}\n",
+        )],
+    )?;
+    git(clock.get(), &["checkout", "-q", "main"])?;
+    commit(
+        "Document usage",
+        &[(
+            "README.md",
+            "# demo\n\nA sample project for siori.\n\n## Usage\n\n    cargo run\n",
+        )],
+    )?;
+    git(
+        clock.get(),
+        &[
+            "merge",
+            "-q",
+            "--no-ff",
+            "feature/login",
+            "-m",
+            "Merge branch 'feature/login'",
+        ],
+    )?;
+    clock.set(clock.get() - 4);
+    git(
+        clock.get(),
+        &["tag", "-a", "v0.2.0", "-m", "Release v0.2.0"],
+    )?;
+
+    git(clock.get(), &["checkout", "-q", "-b", "experiment"])?;
+    commit(
+        "Try a faster greeting",
+        &[(
+            "src/greet.rs",
+            "pub fn greet(_: &str) -> &'static str {\n    \"hi\"\n}\n",
+        )],
+    )?;
+    git(clock.get(), &["checkout", "-q", "main"])?;
+
+    // Everything so far is on origin; the next commit is not
+    git(0, &["init", "-q", "--bare", &origin.to_string_lossy()])?;
+    git(0, &["remote", "add", "origin", &origin.to_string_lossy()])?;
+    git(0, &["push", "-q", "-u", "origin", "main", "v0.1.0"])?;
+    commit("Bump version", &[("VERSION", "0.2.1\n")])?;
+
+    // A stash, then the working tree: staged, unstaged and untracked changes
+    write(
+        "src/main.rs",
+        "fn main() {\n    println!(\"stashed\");\n}\n",
+    )?;
+    git(
+        clock.get(),
+        &["stash", "push", "-q", "-m", "Half-finished idea"],
+    )?;
+    write(
+        "src/greet.rs",
+        "pub fn greet(name: &str) -> String {\n    format!(\"hello, {name}!\")\n}\n",
+    )?;
+    git(0, &["add", "src/greet.rs"])?;
+    write(
+        "README.md",
+        "# demo\n\nA sample project for siori.\n\n## Usage\n\n    cargo run -- --name you\n",
+    )?;
+    write("notes.txt", "todo: try tagging with t in the Log tab\n")?;
+    Ok(repo)
+}
+
+/// Print how long each startup phase takes, for performance reports on large repos
+fn profile_startup() -> Result<()> {
+    let (app, phases) = app::App::profile_startup()?;
+    let total: Duration = phases.iter().map(|(_, d)| *d).sum();
+    println!("Repository: {}", app.repo_path.display());
+    println!(
+        "Files: {}  Commits loaded: {}  Stashes: {}",
+        app.files.len(),
+        app.commits.len(),
+        app.stashes.len()
+    );
+    println!();
+    for (phase, duration) in &phases {
+        println!(
+            "  {:<16} {:>9.1} ms",
+            phase,
+            duration.as_secs_f64() * 1000.0
+        );
+    }
+    println!("  {:<16} {:>9.1} ms", "total", total.as_secs_f64() * 1000.0);
+    if let Some((slowest, duration)) = phases.iter().max_by_key(|(_, d)| *d)
+        && !total.is_zero()
+    {
+        println!();
+        println!(
+            "Slowest: {} ({:.0}% of startup)",
+            slowest,
+            duration.as_secs_f64() / total.as_secs_f64() * 100.0
+        );
+    }
+    Ok(())
+}
+
+/// Prints the repository state; returns the unfinished merge/rebase and, with
+/// `remote`, the unpushed tags it found as (kind, message), which `--strict`
+/// fails on so release scripts can stop there. Only `remote` goes to the network
+fn check_mode(remote: bool) -> Result<Vec<(&'static str, String)>> {
+    let mut repo = Repository::discover(".").context("Not a git repository")?;
+    // Counted before anything borrows the repository
+    let mut stashes = 0;
+    repo.stash_foreach(|_, _, _| {
+        stashes += 1;
+        true
+    })?;
     let branch = match repo.head() {
         Ok(head) => head.shorthand().unwrap_or("HEAD").to_string(),
         Err(_) => "(no commits yet)".to_string(),
@@ -209,7 +682,62 @@ fn check_mode() -> Result<()> {
         0
     };
     println!("Recent commits: {}", commit_count);
-    Ok(())
+
+    println!("Stashes: {}", stashes);
+
+    let mut problems = Vec::new();
+    let operation = app::unfinished_operation(repo.state());
+    println!(
+        "In progress: {}",
+        operation.map_or("none", |(what, _)| what)
+    );
+    if let Some((what, abort)) = operation {
+        problems.push((
+            "unfinished-operation",
+            format!("{} in progress (git {} --continue or --abort)", what, abort),
+        ));
+    }
+
+    let local_tags: Vec<String> = repo
+        .tag_names(None)?
+        .iter()
+        .flatten()
+        .map(str::to_string)
+        .collect();
+    let workdir = repo.workdir().unwrap_or(repo.path()).to_path_buf();
+    let remote_tags = if !remote {
+        Err("not checked without --remote")
+    } else if repo.find_remote("origin").is_ok() {
+        app::App::fetch_remote_tags(&workdir).ok_or("origin unreachable")
+    } else {
+        Err("no origin remote")
+    };
+    match remote_tags {
+        Ok(remote_tags) => {
+            let unpushed: Vec<&String> = local_tags
+                .iter()
+                .filter(|t| !remote_tags.contains(*t))
+                .collect();
+            if unpushed.is_empty() {
+                println!("Unpushed tags: 0");
+            } else {
+                let names: Vec<&str> = unpushed.iter().map(|t| t.as_str()).collect();
+                println!("Unpushed tags: {} ({})", unpushed.len(), names.join(", "));
+                problems.push((
+                    "unpushed-tags",
+                    format!(
+                        "{} tag{} not pushed to origin: {}",
+                        unpushed.len(),
+                        if unpushed.len() == 1 { "" } else { "s" },
+                        names.join(", ")
+                    ),
+                ));
+            }
+        }
+        Err(why) => println!("Unpushed tags: unknown ({})", why),
+    }
+
+    Ok(problems)
 }
 
 fn diff_mode(args: &[String]) -> Result<()> {
@@ -248,10 +776,114 @@ fn diff_mode(args: &[String]) -> Result<()> {
 
         open_editor_diff(&repo_path, file_path, is_staged)
     } else {
-        // Commit mode: show diff for a specific commit
-        let commit_ref = filtered_args.first().map(|s| s.as_str()).unwrap_or("HEAD");
-        diff_viewer::run_commit(&repo_path, commit_ref)
+        // Commit mode: show diff for a specific commit, or a merge against one parent
+        let parent_idx = filtered_args.iter().position(|a| *a == "--parent");
+        let parent = match parent_idx {
+            Some(i) => Some(
+                filtered_args
+                    .get(i + 1)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| anyhow::anyhow!("Missing parent number after --parent"))?,
+            ),
+            None => None,
+        };
+        let commit_ref = filtered_args
+            .iter()
+            .enumerate()
+            .find(|(i, _)| parent_idx.is_none_or(|p| *i != p && *i != p + 1))
+            .map(|(_, s)| s.as_str())
+            .unwrap_or("HEAD");
+        let text = diff_viewer::commit_output(&repo_path, commit_ref, parent)?;
+        if let Some(argv) = config::Config::load().pager.argv() {
+            return diff_viewer::run_pager(&argv, &text);
+        }
+        let files = diff_viewer::parse_diff_files(&diff_viewer::strip_ansi(&text));
+        if files.is_empty() {
+            print!("{}", text);
+            return Ok(());
+        }
+        let title = match parent {
+            Some(n) => format!("{}^{} → {}", commit_ref, n, commit_ref),
+            None => commit_ref.to_string(),
+        };
+        run_diff_pager(diff_viewer::DiffPager::new(title, files))
+    }
+}
+
+/// `siori difftool <old> <new>`, e.g. `git difftool -x "siori difftool"`
+fn difftool_mode(args: &[String]) -> Result<()> {
+    let [old, new] = args else {
+        anyhow::bail!("Usage: siori difftool <old> <new>");
+    };
+    // --no-index exits with 1 when the files differ
+    let output = Command::new("git")
+        .args(["diff", "--no-index", "--no-color", "--", old, new])
+        .output()
+        .context("Failed to run git diff")?;
+    if output.status.code().is_some_and(|code| code > 1) {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
     }
+    let files = diff_viewer::parse_diff_files(&String::from_utf8_lossy(&output.stdout));
+    if files.is_empty() {
+        println!("No differences: {} {}", old, new);
+        return Ok(());
+    }
+    run_diff_pager(diff_viewer::DiffPager::new(
+        format!("{} → {}", old, new),
+        files,
+    ))
+}
+
+/// `siori pager`: diffs piped in by git open in the viewer; anything else
+/// (log, blame, ...) goes to `[pager] command`, or `less -R` as usual
+fn pager_mode() -> Result<()> {
+    use std::io::Read;
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let files = diff_viewer::parse_diff_files(&diff_viewer::strip_ansi(&input));
+    if files.is_empty() {
+        let argv = config::Config::load()
+            .pager
+            .argv()
+            .unwrap_or_else(|| vec!["less".to_string(), "-R".to_string()]);
+        if diff_viewer::run_pager(&argv, &input).is_err() {
+            print!("{}", input);
+        }
+        return Ok(());
+    }
+    run_diff_pager(diff_viewer::DiffPager::new("git diff".to_string(), files))
+}
+
+/// Event loop of the standalone viewer; keys come from the terminal even
+/// when stdin is a pipe
+fn run_diff_pager(mut pager: diff_viewer::DiffPager) -> Result<()> {
+    use crossterm::event::KeyCode;
+
+    enable_raw_mode()?;
+    stdout().execute(EnterAlternateScreen)?;
+    let mut terminal = ratatui::Terminal::new(ratatui::prelude::CrosstermBackend::new(stdout()))?;
+    let result = (|| -> Result<()> {
+        loop {
+            terminal.draw(|f| ui::render_diff_pager(f, &pager))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('j') | KeyCode::Down | KeyCode::Char(' ') => pager.next_hunk(),
+                    KeyCode::Char('k') | KeyCode::Up => pager.prev_hunk(),
+                    KeyCode::Char('J') | KeyCode::Tab => pager.next_file(),
+                    KeyCode::Char('K') | KeyCode::BackTab => pager.prev_file(),
+                    _ => {}
+                }
+            }
+        }
+    })();
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+    result
 }
 
 /// Open editor with diff highlights: changed lines shown in green, jump to first change.
@@ -1,17 +1,29 @@
 use crate::app::{
-    App, BranchSelectOp, FileEntry, FileStatus, HEAD_LABEL, InputMode, PendingDiscardTarget, Tab,
-    WorktreeInfo, remote_label,
+    AmendFile, App, BranchSelectOp, CommitDetailFile, CommitProblemKind, FileEntry, FileStatus,
+    HEAD_LABEL, HealthIssue, HiddenFlag, InputMode, MAX_MESSAGE_LINES, PendingDiscardTarget,
+    StatusFilter, Tab, WorktreeInfo, author_initials, format_age, format_relative_time,
+    remote_label, subject_length, summary_line,
+};
+use crate::config::{AuthorColumn, Config, DateFormat, FileSort, LogDecorations, get_color};
+use crate::diff_viewer::{
+    DiffPager, binary_delta_label, change_marker, format_size, mode_change_label,
 };
-use crate::config::{Config, get_color};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
 use std::sync::OnceLock;
 use unicode_width::UnicodeWidthStr;
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
 
+const TAB_TITLES: [(Tab, &str); 4] = [
+    (Tab::Files, "Files"),
+    (Tab::Log, "Log"),
+    (Tab::Stash, "Stash"),
+    (Tab::Branches, "Branches"),
+];
+
 fn config() -> &'static Config {
     CONFIG.get_or_init(Config::load)
 }
@@ -41,6 +53,9 @@ mod colors {
     pub fn magenta() -> Color {
         Color::Magenta
     }
+    pub fn cyan() -> Color {
+        Color::Cyan
+    }
     pub fn dim() -> Color {
         get_color(&config().colors.dim, Color::DarkGray)
     }
@@ -49,24 +64,28 @@ mod colors {
 pub fn ui(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
     let chunks = Layout::vertical([
-        Constraint::Length(2), // Tabs with underline
-        Constraint::Min(0),    // Content
-        Constraint::Length(3), // Hints
+        Constraint::Length(2),                       // Tabs with underline
+        Constraint::Length(app.health.len() as u16), // Health banner
+        Constraint::Min(0),                          // Content
+        Constraint::Length(3),                       // Hints
     ])
     .split(area);
 
     // Tabs with underline
     render_tabs(frame, app, chunks[0]);
+    render_health_banner(frame, app, chunks[1]);
 
     // Content
     match app.tab {
-        Tab::Files => render_files_tab(frame, app, chunks[1]),
-        Tab::Log => render_log_tab(frame, app, chunks[1]),
+        Tab::Files => render_files_tab(frame, app, chunks[2]),
+        Tab::Log => render_log_tab(frame, app, chunks[2]),
+        Tab::Stash => render_stash_tab(frame, app, chunks[2]),
+        Tab::Branches => render_branches_tab(frame, app, chunks[2]),
     }
 
     // Hints
     if config().ui.show_hints {
-        render_hints(frame, app, chunks[2]);
+        render_hints(frame, app, chunks[3]);
     }
 
     // Dialogs (overlays)
@@ -76,8 +95,29 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
         InputMode::TagInput => render_tag_dialog(frame, app),
         InputMode::VersionConfirm => render_version_confirm_dialog(frame, app),
         InputMode::UncommittedWarning => render_uncommitted_warning_dialog(frame, app),
-        InputMode::DiscardConfirm => render_discard_confirm_dialog(frame, app),
+        InputMode::DiscardConfirm
+            if !matches!(app.pending_discard, Some(PendingDiscardTarget::Hunk(_))) =>
+        {
+            render_discard_confirm_dialog(frame, app)
+        }
         InputMode::DeleteTagConfirm => render_delete_tag_confirm_dialog(frame, app),
+        InputMode::TagDetail => render_tag_detail(frame, app),
+        InputMode::TagsView => render_tags_view(frame, app),
+        InputMode::UndoCommitConfirm => render_undo_commit_dialog(frame, app),
+        InputMode::CheckoutConfirm => render_checkout_dialog(frame, app),
+        InputMode::RemoveLockConfirm => render_remove_lock_dialog(frame, app),
+        InputMode::LogSearch => render_log_search_dialog(frame, app),
+        InputMode::CommitDetail => render_commit_detail(frame, app),
+        InputMode::IncomingView => render_incoming_view(frame, app),
+        InputMode::CompareView => render_compare_view(frame, app),
+        InputMode::ReflogView => render_reflog_view(frame, app),
+        InputMode::ReflogResetConfirm => render_reflog_reset_dialog(frame, app),
+        InputMode::CommitFileDiff => {
+            if let Some(pager) = &app.commit_file_diff {
+                frame.render_widget(Clear, frame.area());
+                render_diff_pager(frame, pager);
+            }
+        }
         InputMode::DiffConfirm => render_diff_confirm_dialog(frame, app),
         InputMode::WorktreeTypeSelect => render_worktree_type_dialog(frame, app),
         InputMode::WorktreeNewBranch => render_worktree_new_branch_dialog(frame, app),
@@ -85,67 +125,184 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
         InputMode::WorktreeRemoveConfirm => render_worktree_remove_dialog(frame, app),
         InputMode::CherryPickInput => render_cherry_pick_dialog(frame, app),
         InputMode::BranchSelect => render_branch_select_dialog(frame, app),
+        InputMode::HunkView => render_hunk_view(frame, app),
+        InputMode::StashInput => render_stash_input_dialog(frame, app),
+        InputMode::GitignoreInput => render_gitignore_dialog(frame, app),
+        InputMode::MoveInput => render_move_dialog(frame, app),
+        InputMode::QuickLook => render_quick_look(frame, app),
+        InputMode::Review => render_review(frame, app),
+        InputMode::ChangelogInput => render_changelog_dialog(frame, app),
+        InputMode::AmendPreview => render_amend_preview_dialog(frame, app),
+        InputMode::CommitReview => render_commit_review(frame, app),
+        InputMode::CommitTypeSelect => render_commit_type_dialog(frame, app),
+        InputMode::CommitScopeInput => render_commit_scope_dialog(frame, app),
+        InputMode::CommitDateInput => render_commit_date_dialog(frame, app),
+        InputMode::CoAuthorSelect => render_co_author_dialog(frame, app),
+        InputMode::CommitFileSelect => render_commit_file_dialog(frame, app),
+        InputMode::ProfileSelect => render_profile_dialog(frame, app),
+        InputMode::CommitSizeWarning => render_commit_size_dialog(frame, app),
+        InputMode::CommitWarning => render_commit_warning_dialog(frame, app),
+        InputMode::StashDropConfirm => render_stash_drop_confirm_dialog(frame, app),
+        InputMode::BranchNameInput => render_branch_name_dialog(frame, app),
+        InputMode::BranchDeleteConfirm => render_branch_delete_confirm_dialog(frame, app),
+        InputMode::OutputView => render_output_view(frame, app),
+        InputMode::RecoveryPrompt => render_recovery_dialog(frame, app),
+        InputMode::DiscardConfirm
+            if matches!(app.pending_discard, Some(PendingDiscardTarget::Hunk(_))) =>
+        {
+            // Keep the hunk visible behind the confirmation
+            render_hunk_view(frame, app);
+            render_discard_confirm_dialog(frame, app);
+        }
         _ => {}
     }
 
-    // Processing overlay (highest priority)
-    if app.processing.is_active() {
-        render_processing_overlay(frame, app);
+    // Background operation status chip (non-blocking)
+    if app.processing.is_active() || app.failed_operation.is_some() {
+        // Includes queued operations and per-operation results of the batch
+        render_status_chip(frame, app, chunks[1]);
+    }
+
+    if config().ui.screen_reader {
+        plain_glyphs(frame.buffer_mut());
     }
 }
 
-fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
-    let base_dir = std::env::current_dir().unwrap_or_default();
-    let repo_name = repo_display_name(&app.repo_path, &base_dir);
+/// Screen-reader mode: swap glyphs that read as noise for plain ASCII.
+/// Borders become blank so only titles and text are read out.
+fn plain_glyphs(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        let mut chars = cell.symbol().chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        let plain = match c {
+            '\u{2500}'..='\u{257f}' => ' ',
+            '●' => '*',
+            '○' => 'o',
+            '✓' => '+',
+            '✗' => 'x',
+            '⚠' => '!',
+            '█' => '_',
+            '→' => '>',
+            '−' => '-',
+            _ => continue,
+        };
+        cell.set_char(plain);
+    }
+}
 
-    // Line 1: Tabs + repo name
-    let is_files = app.tab == Tab::Files;
-    let files_style = if is_files {
-        Style::default().fg(colors::fg_bright()).bold()
+/// Put the terminal cursor on the selected row so screen readers announce it
+fn place_reader_cursor(frame: &mut Frame, app: &App, area: Rect, row: usize) {
+    if config().ui.screen_reader && app.input_mode == InputMode::Normal {
+        let y = area.y + row as u16;
+        if y < area.bottom() {
+            // Skip the "> " highlight symbol
+            frame.set_cursor_position((area.x + 2, y));
+        }
+    }
+}
+
+fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    // The full workdir, so a repository found above the current directory
+    // (or switched to) is never mistaken for the one you are in
+    let repo_path = home_relative(&app.repo_path);
+    let repo_color = if app.repo_differs_from_cwd() {
+        colors::yellow()
     } else {
-        Style::default().fg(colors::dim())
+        colors::green()
     };
-    let log_style = if !is_files {
-        Style::default().fg(colors::fg_bright()).bold()
-    } else {
-        Style::default().fg(colors::dim())
+
+    // Line 1: Tabs + repo name
+    // Line 2: Underline under the active tab + branch info
+    let mut tab_spans = vec![Span::raw(" ")];
+    let mut underline = String::from(" ");
+    for (i, (tab, title)) in TAB_TITLES.iter().enumerate() {
+        if i > 0 {
+            tab_spans.push(Span::raw("   "));
+            underline.push_str("   ");
+        }
+        let is_active = app.tab == *tab;
+        let style = if is_active {
+            Style::default().fg(colors::fg_bright()).bold()
+        } else {
+            Style::default().fg(colors::dim())
+        };
+        // The underline is blanked in screen-reader mode, so bracket the active tab
+        let title = match (config().ui.screen_reader, is_active) {
+            (false, _) => title.to_string(),
+            (true, true) => format!("[{}]", title),
+            (true, false) => format!(" {} ", title),
+        };
+        underline.push_str(&if is_active { "━" } else { " " }.repeat(title.width()));
+        tab_spans.push(Span::styled(title, style));
+    }
+    // Keep the branch info position constant regardless of the active tab
+    let tabs_width = underline.width();
+    underline.push_str("    ");
+
+    let room = (area.width as usize).saturating_sub(tabs_width + 3);
+    tab_spans.push(Span::styled(
+        format!(
+            "{:>width$}",
+            format!("@ {}", truncate_left(&repo_path, room.saturating_sub(2))),
+            width = room
+        ),
+        Style::default().fg(repo_color).bold(),
+    ));
+    let tabs_line = Line::from(tab_spans);
+
+    let status = app.status_label();
+    let branch_info = match &app.detached_at {
+        Some(id) => format!("detached at {}  {}", id, status),
+        None => format!("on {}  {}", app.branch_name, status),
     };
 
-    let tabs_line = Line::from(vec![
-        Span::styled(" Files", files_style),
-        Span::raw("   "),
-        Span::styled("Log", log_style),
-        Span::styled(
-            format!(
-                "{:>width$}",
-                format!("@ {}", repo_name),
-                width = (area.width as usize).saturating_sub(15)
-            ),
-            Style::default().fg(colors::green()),
+    // How old ↑/↓ is; flagged once the last fetch passes ui.fetch_stale_minutes
+    let (freshness, freshness_color) = match app.fetch_freshness() {
+        Some((label, stale)) => (
+            format!("  {}", label),
+            if stale {
+                colors::yellow()
+            } else {
+                colors::dim()
+            },
         ),
-    ]);
+        None => (String::new(), colors::dim()),
+    };
 
-    // Line 2: Underline + branch info
-    // Fixed width: " Files" = 6 chars, "   " = 3 chars, "Log" = 3 chars = 12 total
-    // Use fixed-width strings so branch info position stays constant
-    let underline = if is_files {
-        " ━━━━━━         " // Files underline + padding (16 chars total)
+    let recording = if app.is_recording_macro() {
+        "● REC  "
     } else {
-        "         ━━━    " // Padding + Log underline + padding (16 chars total)
+        ""
+    };
+    let filter = if app.tab == Tab::Files && app.status_filter != StatusFilter::All {
+        format!("only {} (f)  ", app.status_filter.name())
+    } else {
+        String::new()
     };
-    let status = app.status_label();
-    let branch_info = format!("on {}  {}", app.branch_name, status);
 
     let underline_line = Line::from(vec![
         Span::styled(underline, Style::default().fg(colors::blue())),
         Span::styled(
             format!(
                 "{:>width$}",
-                branch_info,
-                width = (area.width as usize).saturating_sub(16)
+                filter,
+                width = (area.width as usize)
+                    .saturating_sub(
+                        tabs_width
+                            + 4
+                            + branch_info.width()
+                            + freshness.width()
+                            + recording.width()
+                    )
+                    .max(filter.width())
             ),
-            Style::default().fg(colors::dim()),
+            Style::default().fg(colors::yellow()).bold(),
         ),
+        Span::styled(recording, Style::default().fg(colors::red()).bold()),
+        Span::styled(branch_info, Style::default().fg(colors::dim())),
+        Span::styled(freshness, Style::default().fg(freshness_color)),
     ]);
 
     let paragraph = Paragraph::new(vec![tabs_line, underline_line]);
@@ -153,22 +310,30 @@ fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_files_tab(frame: &mut Frame, app: &mut App, area: Rect) {
+    // Multi-line messages grow the input box, up to a few lines
+    let message_lines = app
+        .commit_message
+        .split('\n')
+        .count()
+        .min(MAX_MESSAGE_LINES);
+    let input_height = message_lines as u16 + 2;
+
     // In INSERT mode, add extra line for IME composition
     let chunks = if app.input_mode == InputMode::Insert {
         Layout::vertical([
-            Constraint::Length(1), // Spacing
-            Constraint::Length(3), // Commit input
-            Constraint::Length(1), // IME composition line
-            Constraint::Length(1), // Spacing
-            Constraint::Min(0),    // Files
+            Constraint::Length(1),            // Spacing
+            Constraint::Length(input_height), // Commit input
+            Constraint::Length(1),            // IME composition line
+            Constraint::Length(1),            // Spacing
+            Constraint::Min(0),               // Files
         ])
         .split(area)
     } else {
         Layout::vertical([
-            Constraint::Length(1), // Spacing
-            Constraint::Length(3), // Commit input
-            Constraint::Length(1), // Spacing
-            Constraint::Min(0),    // Files
+            Constraint::Length(1),            // Spacing
+            Constraint::Length(input_height), // Commit input
+            Constraint::Length(1),            // Spacing
+            Constraint::Min(0),               // Files
         ])
         .split(area)
     };
@@ -182,32 +347,90 @@ fn render_files_tab(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Build display text for input box
     let inner_width = chunks[1].width.saturating_sub(2) as usize;
-    let input_text = build_input_display(
+    let input_text = build_message_display(
         &app.commit_message,
         app.cursor_pos,
         inner_width,
         app.input_mode,
     );
 
-    let input = Paragraph::new(input_text).style(input_style).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(if app.input_mode == InputMode::Insert {
-                colors::blue()
-            } else {
-                colors::dim()
-            }))
-            .title(if app.input_mode == InputMode::Insert {
-                if app.is_amending {
-                    " [AMEND] "
-                } else {
-                    " [INSERT] "
-                }
-            } else {
-                " c: commit "
-            }),
-    );
+    let has_message = !app.commit_message.is_empty();
+    // Subtle 50/72 column guide while typing, drawn after the text below
+    let guides: Vec<Option<usize>> = input_text
+        .iter()
+        .map(|line| {
+            message_guide_column(line)
+                .filter(|_| app.input_mode == InputMode::Insert)
+                .filter(|&column| column < inner_width)
+        })
+        .collect();
+    let input_lines: Vec<Line> = input_text
+        .iter()
+        .map(|line| match message_line_color(line) {
+            Some(color) if has_message => {
+                Line::from(Span::styled(line.text.clone(), Style::default().fg(color)))
+            }
+            _ => Line::from(line.text.clone()),
+        })
+        .collect();
+
+    let mut input_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if app.input_mode == InputMode::Insert {
+            colors::blue()
+        } else {
+            colors::dim()
+        }))
+        .title(if app.input_mode == InputMode::Insert {
+            let mode = if app.is_amending { "AMEND" } else { "INSERT" };
+            let mut title = format!(" [{}] ", mode);
+            if let Some((date, _)) = &app.commit_date {
+                title.push_str(&format!("dated {} ", date));
+            }
+            if app.signoff {
+                title.push_str("signed-off ");
+            }
+            if app.processing == crate::app::Processing::Generating {
+                title.push_str(&format!("{} generating… ", app.spinner_char()));
+            }
+            title
+        } else {
+            " c: commit ".to_string()
+        });
+    // Live subject counter against the 50-character guideline
+    if app.input_mode == InputMode::Insert {
+        let subject = subject_length(&app.commit_message);
+        let limits = &config().commit;
+        let color = if subject > limits.subject_max {
+            colors::red()
+        } else if subject > limits.subject_limit {
+            colors::yellow()
+        } else {
+            colors::dim()
+        };
+        input_block = input_block.title_bottom(
+            Line::from(Span::styled(
+                format!(" {}/{} ", subject, limits.subject_limit),
+                Style::default().fg(color),
+            ))
+            .right_aligned(),
+        );
+    }
+    let input = Paragraph::new(input_lines)
+        .style(input_style)
+        .block(input_block);
     frame.render_widget(input, chunks[1]);
+    for (row, guide) in guides.into_iter().enumerate() {
+        if let Some(column) = guide {
+            let position = (
+                chunks[1].x + 1 + column as u16,
+                chunks[1].y + 1 + row as u16,
+            );
+            if let Some(cell) = frame.buffer_mut().cell_mut(position) {
+                cell.set_bg(Color::Rgb(48, 48, 48));
+            }
+        }
+    }
 
     if app.input_mode == InputMode::Insert {
         // Render IME composition line: "  > " prompt for cursor positioning
@@ -224,31 +447,96 @@ fn render_files_tab(frame: &mut Frame, app: &mut App, area: Rect) {
     } else {
         3
     };
-    let staged: Vec<_> = app.files.iter().filter(|f| f.staged).collect();
-    let unstaged: Vec<_> = app.files.iter().filter(|f| !f.staged).collect();
+    // While amending, list what the amended commit will contain instead
+    if app.input_mode == InputMode::Insert && app.is_amending {
+        render_amend_contents(frame, app, chunks[files_chunk_idx]);
+        return;
+    }
+
+    // visual_list holds the display (sorted) order
+    let ordered: Vec<&FileEntry> = app.visual_list.iter().map(|&i| &app.files[i]).collect();
+    let staged: Vec<_> = ordered.iter().copied().filter(|f| f.staged).collect();
+    let unstaged: Vec<_> = ordered.iter().copied().filter(|f| !f.staged).collect();
 
     let mut items: Vec<ListItem> = Vec::new();
 
-    items.push(ListItem::new(Line::from(vec![
+    let mut staged_header = vec![
         Span::styled("STAGED ", Style::default().fg(colors::dim()).bold()),
         Span::styled(
             format!("({})", staged.len()),
             Style::default().fg(colors::green()),
         ),
-    ])));
+    ];
+    if !staged.is_empty() {
+        let (count, add, del) = app.staged_diff_totals();
+        staged_header.push(Span::styled(
+            format!(
+                "  +{} −{} across {} file{}",
+                add,
+                del,
+                count,
+                if count == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(colors::dim()),
+        ));
+    }
+    if let Some(label) = collapsed_generated_label(app, true) {
+        staged_header.push(Span::styled(label, Style::default().fg(colors::dim())));
+    }
+    items.push(ListItem::new(Line::from(staged_header)));
     for file in &staged {
-        items.push(create_file_item(file));
+        items.push(create_file_item(file, mark_column(app, file)));
     }
 
-    items.push(ListItem::new(Line::from(vec![
+    let hidden_count = unstaged.iter().filter(|f| f.hidden.is_some()).count();
+    let mut changes_header = vec![
         Span::styled("CHANGES ", Style::default().fg(colors::dim()).bold()),
         Span::styled(
-            format!("({})", unstaged.len()),
+            format!("({})", unstaged.len() - hidden_count),
             Style::default().fg(colors::yellow()),
         ),
-    ])));
+    ];
+    // Remaining work during a merge, counting down as markers are resolved
+    let conflicted: Vec<&&FileEntry> = unstaged
+        .iter()
+        .filter(|f| f.status == FileStatus::Conflicted)
+        .collect();
+    if !conflicted.is_empty() {
+        // A conflict without markers still counts as one to resolve
+        let regions: usize = conflicted.iter().map(|f| f.conflicts.unwrap_or(1)).sum();
+        changes_header.push(Span::styled(
+            format!(
+                "  {} conflict{} in {} file{}",
+                regions,
+                if regions == 1 { "" } else { "s" },
+                conflicted.len(),
+                if conflicted.len() == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(colors::magenta()),
+        ));
+    }
+    if hidden_count > 0 {
+        changes_header.push(Span::styled(
+            format!("  {} hidden", hidden_count),
+            Style::default().fg(colors::dim()),
+        ));
+    }
+    if let Some(label) = collapsed_generated_label(app, false) {
+        changes_header.push(Span::styled(label, Style::default().fg(colors::dim())));
+    }
+    if app.file_sort != FileSort::Path {
+        changes_header.push(Span::styled(
+            format!("  sorted by {}", app.file_sort.name()),
+            Style::default().fg(colors::dim()),
+        ));
+    }
+    items.push(ListItem::new(Line::from(changes_header)));
+    let directory_totals = app.changes_directory_totals();
+    if let Some(totals) = &directory_totals {
+        items.push(ListItem::new(directory_summary_line(totals)));
+    }
     for file in &unstaged {
-        items.push(create_file_item(file));
+        items.push(create_file_item(file, mark_column(app, file)));
     }
 
     let list = List::new(items)
@@ -258,37 +546,242 @@ fn render_files_tab(frame: &mut Frame, app: &mut App, area: Rect) {
     let mut adjusted_state = app.files_state.clone();
     if let Some(idx) = app.files_state.selected() {
         let staged_count = staged.len();
-        let adjusted_idx = if idx < staged_count { idx + 1 } else { idx + 2 };
+        let changes_rows = if directory_totals.is_some() { 2 } else { 1 };
+        let adjusted_idx = if idx < staged_count {
+            idx + 1
+        } else {
+            idx + 1 + changes_rows
+        };
         adjusted_state.select(Some(adjusted_idx));
     }
 
     frame.render_stateful_widget(list, chunks[files_chunk_idx], &mut adjusted_state);
+    if let Some(idx) = adjusted_state.selected() {
+        let row = idx.saturating_sub(adjusted_state.offset());
+        place_reader_cursor(frame, app, chunks[files_chunk_idx], row);
+    }
+}
+
+/// HEAD's files plus what is staged, shown under the AMEND input
+fn render_amend_contents(frame: &mut Frame, app: &App, area: Rect) {
+    let (add, del) = app
+        .amend_preview
+        .iter()
+        .filter_map(|f| f.stats)
+        .fold((0, 0), |(a, d), (fa, fd)| (a + fa, d + fd));
+    let mut lines = vec![Line::from(vec![
+        Span::styled("AMENDED COMMIT ", Style::default().fg(colors::dim()).bold()),
+        Span::styled(
+            format!("({})", app.amend_preview.len()),
+            Style::default().fg(colors::yellow()),
+        ),
+        Span::styled(
+            format!("  +{} −{}", add, del),
+            Style::default().fg(colors::dim()),
+        ),
+    ])];
+    lines.extend(app.amend_preview.iter().map(amend_file_line));
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// "  src/ +120 −4 · test/ +30 −0" under the CHANGES header
+fn directory_summary_line(totals: &[(String, usize, usize)]) -> Line<'static> {
+    let mut spans = vec![Span::raw("  ")];
+    for (i, (dir, add, del)) in totals.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" · ", Style::default().fg(colors::dim())));
+        }
+        spans.push(Span::styled(
+            format!("{} ", dir),
+            Style::default().fg(colors::fg()),
+        ));
+        spans.push(Span::styled(
+            format!("+{}", add),
+            Style::default().fg(colors::green()),
+        ));
+        spans.push(Span::styled(
+            format!(" −{}", del),
+            Style::default().fg(colors::red()),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// "  2 generated (g)" for a section whose generated files are collapsed
+fn collapsed_generated_label(app: &App, staged: bool) -> Option<String> {
+    if app.show_generated {
+        return None;
+    }
+    let count = app
+        .files
+        .iter()
+        .filter(|f| f.generated && f.staged == staged)
+        .count();
+    (count > 0).then(|| format!("  {} generated (g)", count))
+}
+
+/// Mark column, only shown while something is marked
+fn mark_column(app: &App, file: &FileEntry) -> Option<bool> {
+    (!app.marked_files.is_empty()).then(|| app.is_marked(file))
 }
 
-fn create_file_item(file: &FileEntry) -> ListItem<'static> {
+fn create_file_item(file: &FileEntry, marked: Option<bool>) -> ListItem<'static> {
+    if config().ui.screen_reader {
+        return create_file_item_text(file, marked);
+    }
+    let mark = match marked {
+        Some(true) => Span::styled("● ", Style::default().fg(colors::magenta())),
+        Some(false) => Span::raw("  "),
+        None => Span::raw(""),
+    };
+    // Hidden via update-index: dimmed, with the same letters as `git ls-files -v`
+    if let Some(flag) = file.hidden {
+        let flag_char = match flag {
+            HiddenFlag::SkipWorktree => "S",
+            HiddenFlag::AssumeUnchanged => "h",
+        };
+        let style = Style::default().fg(colors::dim());
+        return ListItem::new(Line::from(vec![
+            mark,
+            Span::styled(format!("{:>2} ", flag_char), style),
+            Span::styled(file.path.clone(), style),
+            Span::styled("  hidden", style),
+        ]));
+    }
+
+    // Untracked nested repository: Enter switches into it
+    if file.nested_repo {
+        return ListItem::new(Line::from(vec![
+            mark,
+            Span::styled(" ⎇ ", Style::default().fg(colors::blue())),
+            Span::styled(file.path.clone(), Style::default().fg(colors::blue())),
+            Span::styled("  git repo ⏎", Style::default().fg(colors::dim())),
+        ]));
+    }
+
     let (status_char, status_color) = match file.status {
+        // `git add -N`: tracked but nothing staged yet
+        FileStatus::Added if file.intent_to_add => ("+N", colors::blue()),
         FileStatus::Added => ("A", colors::green()),
         FileStatus::Modified => ("M", colors::yellow()),
         FileStatus::Deleted => ("D", colors::red()),
         FileStatus::Untracked => ("??", colors::red()),
+        FileStatus::Conflicted => ("UU", colors::magenta()),
     };
 
-    let diff_str = match file.diff_stats {
-        Some((add, del)) => format!("+{} -{}", add, del),
-        None => "new".to_string(),
+    let diff_str = match (file.diff_stats, file.mode_change, file.binary_size) {
+        // Line counts are meaningless for binary content
+        (_, _, Some((old, new))) => binary_delta_label(old, new),
+        // "+0 -0" says nothing for an executable-bit flip
+        (Some((0, 0)), Some((old, new)), None) => mode_change_label(old, new),
+        (Some((add, del)), Some((old, new)), None) => {
+            format!("+{} -{}  {}", add, del, mode_change_label(old, new))
+        }
+        (Some((add, del)), None, None) => format!("+{} -{}", add, del),
+        // Markers all gone: ready to stage
+        (None, _, None) if file.status == FileStatus::Conflicted => match file.conflicts {
+            Some(0) => "resolved, Space to stage".to_string(),
+            Some(1) => "1 conflict".to_string(),
+            Some(n) => format!("{} conflicts", n),
+            // Modify/delete or binary: nothing in the file says whether it's done
+            None => "conflict (no markers)".to_string(),
+        },
+        (None, _, None) => "new".to_string(),
     };
 
-    ListItem::new(Line::from(vec![
+    // Deleted files are struck through in both sections, generated files dimmed
+    let path_style = if file.generated {
+        Style::default().fg(colors::dim())
+    } else if file.status == FileStatus::Deleted {
+        Style::default()
+            .fg(colors::dim())
+            .add_modifier(Modifier::CROSSED_OUT)
+    } else {
+        Style::default().fg(colors::fg())
+    };
+    let mut spans = vec![
+        mark,
         Span::styled(
             format!("{:>2} ", status_char),
             Style::default().fg(status_color),
         ),
-        Span::styled(file.path.clone(), Style::default().fg(colors::fg())),
-        Span::styled(
-            format!("  {}", diff_str),
+        Span::styled(file.path.clone(), path_style),
+    ];
+    if let Some(target) = &file.symlink_target {
+        spans.push(Span::styled(
+            format!(" → {}", target),
+            Style::default().fg(colors::blue()),
+        ));
+    }
+    spans.push(Span::styled(
+        format!("  {}", diff_str),
+        Style::default().fg(colors::dim()),
+    ));
+    if file.generated {
+        spans.push(Span::styled(
+            "  generated",
             Style::default().fg(colors::dim()),
-        ),
-    ]))
+        ));
+    }
+    if !file.text_issues.is_empty() {
+        spans.push(Span::styled(" ⚠", Style::default().fg(colors::yellow())));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// Screen-reader variant: every state spelled out instead of letters and colors
+fn create_file_item_text(file: &FileEntry, marked: Option<bool>) -> ListItem<'static> {
+    let mut words: Vec<String> = Vec::new();
+    if marked == Some(true) {
+        words.push("marked".to_string());
+    }
+    words.push(
+        match file.hidden {
+            Some(HiddenFlag::SkipWorktree) => "hidden (skip-worktree)",
+            Some(HiddenFlag::AssumeUnchanged) => "hidden (assume-unchanged)",
+            None => match file.status {
+                FileStatus::Added if file.intent_to_add => "intent to add",
+                FileStatus::Added => "added",
+                FileStatus::Modified => "modified",
+                FileStatus::Deleted => "deleted",
+                FileStatus::Untracked if file.nested_repo => "nested repository",
+                FileStatus::Untracked => "untracked",
+                FileStatus::Conflicted => "conflicted",
+            },
+        }
+        .to_string(),
+    );
+    words.push(file.path.clone());
+    if let Some(target) = &file.symlink_target {
+        words.push(format!("symlink to {}", target));
+    }
+    if let Some((old, new)) = file.binary_size {
+        words.push(format!(
+            "binary, {} to {}",
+            format_size(old),
+            format_size(new)
+        ));
+    } else if let Some((add, del)) = file.diff_stats {
+        words.push(format!("{} added, {} removed", add, del));
+    } else if file.status == FileStatus::Conflicted {
+        words.push(match file.conflicts {
+            Some(n) => format!("{} conflicts left", n),
+            None => "conflict without markers".to_string(),
+        });
+    }
+    if let Some((old, new)) = file.mode_change {
+        words.push(format!("mode {}", mode_change_label(old, new)));
+    }
+    if file.generated {
+        words.push("generated".to_string());
+    }
+    if !file.text_issues.is_empty() {
+        words.push("warning: text issues".to_string());
+    }
+    ListItem::new(Line::from(Span::styled(
+        words.join(", "),
+        Style::default().fg(colors::fg()),
+    )))
 }
 
 fn render_log_tab(frame: &mut Frame, app: &mut App, area: Rect) {
@@ -298,14 +791,50 @@ fn render_log_tab(frame: &mut Frame, app: &mut App, area: Rect) {
     ])
     .split(area);
 
-    let ahead = app.ahead_behind.map(|(a, _)| a).unwrap_or(0);
+    let mut header = Vec::new();
+    // The ↓ row: k from the top commit (or a click) selects it, Enter reviews what pull brings in
+    if let Some(behind) = app.incoming_row() {
+        let label = format!(" ↓{} from {}  ⏎ review ", behind, app.upstream_name);
+        header.push(if app.on_incoming_row() {
+            Span::styled(
+                format!(">{}", label),
+                Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)),
+            )
+        } else {
+            Span::styled(format!(" {}", label), Style::default().fg(colors::yellow()))
+        });
+    }
+    if app.log_all_branches {
+        header.push(Span::styled(
+            " all branches (A: current only)",
+            Style::default().fg(colors::magenta()),
+        ));
+    }
+    if !header.is_empty() {
+        frame.render_widget(Paragraph::new(Line::from(header)), chunks[0]);
+    }
+    if app.on_incoming_row() {
+        place_reader_cursor(frame, app, chunks[0], 0);
+    }
+
+    // ui.author_column: as wide as the longest loaded name, within reason
+    let author_width = match config().ui.author_column {
+        AuthorColumn::Off => 0,
+        AuthorColumn::Initials => 2,
+        AuthorColumn::Name => app
+            .commits
+            .iter()
+            .map(|c| c.author.width())
+            .max()
+            .unwrap_or(0)
+            .min(16),
+    };
 
     let items: Vec<ListItem> = app
         .commits
         .iter()
-        .enumerate()
-        .map(|(i, commit)| {
-            let is_unpushed = i < ahead;
+        .map(|commit| {
+            let is_unpushed = commit.unpushed;
 
             // Color: unpushed=white, pushed=blue
             let color = if is_unpushed {
@@ -314,26 +843,107 @@ fn render_log_tab(frame: &mut Frame, app: &mut App, area: Rect) {
                 colors::blue()
             };
 
-            // Node symbol: pushed=●, unpushed=○
-            let node = if is_unpushed { "○" } else { "●" };
+            // Node symbol: pushed=●, unpushed=○; merges are diamonds
+            let node = match (config().ui.screen_reader, is_unpushed, commit.is_merge()) {
+                (true, true, true) => "unpushed merge:",
+                (true, true, false) => "unpushed:",
+                (true, false, true) => "merge:",
+                (true, false, false) => "",
+                (false, true, true) => "◇",
+                (false, true, false) => "○",
+                (false, false, true) => "◆",
+                (false, false, false) => "●",
+            };
+
+            // Screen readers get the node words without the box-drawing lanes
+            let graph = &commit.graph;
+            let (node_line, link_line) = if config().ui.screen_reader {
+                (format!("{} ", node), "│".to_string())
+            } else {
+                (
+                    format!("{}{}{} ", graph.before, node, graph.after),
+                    format!("{:<1$}", graph.links, graph.column * 2 + 1),
+                )
+            };
 
-            // Line 1: node + message + labels
-            let mut spans = vec![
-                Span::styled(format!("{} ", node), Style::default().fg(color)),
-                Span::styled(commit.message.clone(), Style::default().fg(colors::fg())),
-            ];
+            // Line 1: graph + node + message + labels
+            let query = app.log_search.as_deref().filter(|q| commit.matches(q));
+            let mut spans = vec![Span::styled(node_line, Style::default().fg(color))];
+            // Space: one end of a cherry-pick range
+            if app.log_mark == Some(commit.full_id) {
+                spans.push(Span::styled(
+                    if config().ui.screen_reader {
+                        "marked: "
+                    } else {
+                        "⚑ "
+                    },
+                    Style::default().fg(colors::magenta()).bold(),
+                ));
+            }
+            if author_width > 0 {
+                let author = match config().ui.author_column {
+                    AuthorColumn::Initials => author_initials(&commit.author),
+                    _ => truncate_chip_text(&commit.author, author_width),
+                };
+                let author = author.trim_end();
+                spans.push(Span::styled(
+                    format!(
+                        "{}{} ",
+                        author,
+                        " ".repeat(author_width.saturating_sub(author.width()))
+                    ),
+                    Style::default().fg(colors::dim()),
+                ));
+            }
+            spans.extend(highlight_matches(
+                &commit.message,
+                query,
+                Style::default().fg(colors::fg()),
+            ));
             if commit.is_head {
                 spans.push(Span::styled(
                     format!(" {}", HEAD_LABEL),
                     Style::default().fg(colors::green()).bold(),
                 ));
             }
-            for branch in &commit.remote_branches {
+            // Local branches: cyan, so they don't read as remote ones
+            for branch in &commit.local_branches {
+                spans.push(Span::styled(
+                    format!(" [{}]", branch),
+                    Style::default().fg(colors::cyan()),
+                ));
+            }
+            // Every label counts when other branches are on screen
+            let decorations = if app.log_all_branches {
+                LogDecorations::All
+            } else {
+                config().ui.log_decorations
+            };
+            let (shown, hidden) = match decorations {
+                LogDecorations::All => (commit.remote_branches.iter().collect(), 0),
+                LogDecorations::Upstream => {
+                    let shown: Vec<&String> = commit
+                        .remote_branches
+                        .iter()
+                        .filter(|b| **b == app.upstream_name)
+                        .collect();
+                    let hidden = commit.remote_branches.len() - shown.len();
+                    (shown, hidden)
+                }
+            };
+            for branch in shown {
                 spans.push(Span::styled(
                     format!(" {}", remote_label(branch)),
                     Style::default().fg(colors::blue()),
                 ));
             }
+            // The full list is in the detail pane (Enter)
+            if hidden > 0 {
+                spans.push(Span::styled(
+                    format!(" +{}", hidden),
+                    Style::default().fg(colors::dim()),
+                ));
+            }
             // Tags: pushed=magenta, unpushed=yellow
             for tag in &commit.tags {
                 let tag_color = if tag.pushed {
@@ -341,18 +951,127 @@ fn render_log_tab(frame: &mut Frame, app: &mut App, area: Rect) {
                 } else {
                     colors::yellow()
                 };
+                let local = if config().ui.screen_reader && !tag.pushed {
+                    " local"
+                } else {
+                    ""
+                };
                 spans.push(Span::styled(
-                    format!(" [{}]", tag.name),
+                    format!(" [{}{}]", tag.name, local),
                     Style::default().fg(tag_color),
                 ));
             }
 
-            // Line 2: graph line + hash + time
+            // Line 2: graph links + hash + time (+ parents of a merge)
+            let mut detail = vec![Span::styled(
+                format!("{} ", link_line),
+                Style::default().fg(color),
+            )];
+            match query {
+                Some(q) if commit.full_id.to_string().starts_with(&q.to_lowercase()) => detail
+                    .extend(highlight_matches(
+                        &commit.id,
+                        Some(q),
+                        Style::default().fg(color),
+                    )),
+                _ => detail.push(Span::styled(commit.id.clone(), Style::default().fg(color))),
+            }
+            let date = match app.date_format {
+                DateFormat::Relative => commit.time.clone(),
+                DateFormat::Absolute => commit.date.clone(),
+                DateFormat::Both => format!("{} ({})", commit.date, commit.time),
+            };
+            detail.push(Span::styled(
+                format!(" - {}", date),
+                Style::default().fg(color),
+            ));
+            // Filled in by a background thread; absent until computed
+            if let Some((files, add, del)) = app.commit_stats.get(&commit.full_id) {
+                detail.push(Span::styled(
+                    format!(" - {} file{}, ", files, if *files == 1 { "" } else { "s" }),
+                    Style::default().fg(colors::dim()),
+                ));
+                detail.push(Span::styled(
+                    format!("+{}", add),
+                    Style::default().fg(colors::green()),
+                ));
+                detail.push(Span::styled(
+                    format!(" -{}", del),
+                    Style::default().fg(colors::red()),
+                ));
+            }
+            // Name the author when that is what matched
+            if let Some(q) = query
+                && commit.author.to_lowercase().contains(&q.to_lowercase())
+            {
+                detail.push(Span::styled(" - by ", Style::default().fg(colors::dim())));
+                detail.extend(highlight_matches(
+                    &commit.author,
+                    Some(q),
+                    Style::default().fg(colors::dim()),
+                ));
+            }
+            if commit.is_merge() {
+                let parents: Vec<String> =
+                    commit.parents.iter().map(|p| format!("{:.7}", p)).collect();
+                detail.push(Span::styled(
+                    format!(" - merge of {}", parents.join(" + ")),
+                    Style::default().fg(colors::dim()),
+                ));
+            }
+            ListItem::new(vec![Line::from(spans), Line::from(detail)])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, chunks[1], &mut app.commits_state);
+    if let Some(idx) = app.commits_state.selected() {
+        // Two lines per commit
+        let row = idx.saturating_sub(app.commits_state.offset()) * 2;
+        place_reader_cursor(frame, app, chunks[1], row);
+    }
+}
+
+fn render_stash_tab(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Spacing
+        Constraint::Min(0),    // Stashes
+    ])
+    .split(area);
+
+    if app.stashes.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No stashes (s in Files tab to stash changes)",
+                Style::default().fg(colors::dim()),
+            )),
+            chunks[1],
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .stashes
+        .iter()
+        .map(|stash| {
+            let stats = match stash.diff_stats {
+                Some((files, add, del)) => format!("{} files  +{} -{}", files, add, del),
+                None => "-".to_string(),
+            };
             ListItem::new(vec![
-                Line::from(spans),
+                Line::from(vec![
+                    Span::styled(
+                        format!("stash@{{{}}} ", stash.index),
+                        Style::default().fg(colors::magenta()),
+                    ),
+                    Span::styled(stash.message.clone(), Style::default().fg(colors::fg())),
+                ]),
                 Line::from(Span::styled(
-                    format!("│ {} - {}", commit.id, commit.time),
-                    Style::default().fg(color),
+                    format!("│ {}", stats),
+                    Style::default().fg(colors::dim()),
                 )),
             ])
         })
@@ -362,12 +1081,118 @@ fn render_log_tab(frame: &mut Frame, app: &mut App, area: Rect) {
         .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
         .highlight_symbol("> ");
 
-    frame.render_stateful_widget(list, chunks[1], &mut app.commits_state);
+    frame.render_stateful_widget(list, chunks[1], &mut app.stash_state);
+    if let Some(idx) = app.stash_state.selected() {
+        let row = idx.saturating_sub(app.stash_state.offset()) * 2;
+        place_reader_cursor(frame, app, chunks[1], row);
+    }
 }
 
-fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
+fn render_branches_tab(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Spacing
+        Constraint::Min(0),    // Branches
+    ])
+    .split(area);
+
+    if app.branches.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No branches yet (n to create one)",
+                Style::default().fg(colors::dim()),
+            )),
+            chunks[1],
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .branches
+        .iter()
+        .map(|branch| {
+            let mut spans = vec![Span::styled(
+                branch.name.clone(),
+                if branch.is_head {
+                    Style::default().fg(colors::green()).bold()
+                } else {
+                    Style::default().fg(colors::fg())
+                },
+            )];
+            if branch.is_head {
+                spans.push(Span::styled(
+                    format!(" {}", HEAD_LABEL),
+                    Style::default().fg(colors::green()).bold(),
+                ));
+            }
+            match (&branch.upstream, branch.ahead_behind) {
+                (Some(upstream), Some((ahead, behind))) => {
+                    let sync = match (ahead, behind) {
+                        (0, 0) => "synced".to_string(),
+                        (ahead, 0) => format!("↑{}", ahead),
+                        (0, behind) => format!("↓{}", behind),
+                        (ahead, behind) => format!("↑{} ↓{}", ahead, behind),
+                    };
+                    spans.push(Span::styled(
+                        format!("  {}", sync),
+                        Style::default().fg(colors::yellow()),
+                    ));
+                    spans.push(Span::styled(
+                        format!(" {}", remote_label(upstream)),
+                        Style::default().fg(colors::blue()),
+                    ));
+                }
+                _ => spans.push(Span::styled(
+                    "  no upstream",
+                    Style::default().fg(colors::dim()),
+                )),
+            }
+            ListItem::new(vec![
+                Line::from(spans),
+                Line::from(Span::styled(
+                    format!("│ {} - {} - {}", branch.id, branch.subject, branch.time),
+                    Style::default().fg(colors::dim()),
+                )),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, chunks[1], &mut app.branches_state);
+    if let Some(idx) = app.branches_state.selected() {
+        let row = idx.saturating_sub(app.branches_state.offset()) * 2;
+        place_reader_cursor(frame, app, chunks[1], row);
+    }
+}
+
+/// `M` releases the mouse for terminal text selection, or takes it back
+fn mouse_hint(app: &App) -> (&'static str, &'static str) {
+    if app.mouse_capture {
+        ("M", "select text")
+    } else {
+        ("M", "mouse on")
+    }
+}
+
+fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
     let hints = match app.input_mode {
-        InputMode::Insert => vec![("Enter", "commit"), ("Esc", "cancel")],
+        InputMode::Insert if !app.marked_files.is_empty() => vec![
+            ("Enter", "commit"),
+            ("^O", "commit marked only"),
+            ("^T", "co-author"),
+            ("Esc", "cancel"),
+        ],
+        InputMode::Insert => vec![
+            ("Enter", "commit"),
+            ("^A", "commit -a"),
+            ("S-Enter/A-Enter", "new line"),
+            ("^T", "co-author"),
+            ("^D", "date"),
+            ("^G", "suggest"),
+            ("Esc", "cancel"),
+        ],
         InputMode::RepoSelect => vec![
             ("j/k", "move"),
             ("Enter", "select"),
@@ -376,7 +1201,11 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
         ],
         InputMode::RemoteUrl => vec![("Enter", "add"), ("Esc", "cancel")],
         InputMode::TagInput => vec![("Enter", "create tag"), ("Esc", "cancel")],
-        InputMode::VersionConfirm => vec![("Enter", "update & tag"), ("Esc", "cancel")],
+        InputMode::VersionConfirm => vec![
+            ("Enter", "release"),
+            ("p", "toggle push"),
+            ("Esc", "cancel"),
+        ],
         InputMode::UncommittedWarning => vec![("Enter", "continue"), ("Esc", "cancel")],
         InputMode::DiscardConfirm => vec![
             (
@@ -384,11 +1213,52 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
                 match &app.pending_discard {
                     Some(PendingDiscardTarget::Single(p)) => p.action.hint_label(),
                     Some(PendingDiscardTarget::All(_)) => "discard all",
+                    Some(PendingDiscardTarget::Hunk(_)) => "discard hunk",
                     None => "discard",
                 },
             ),
             ("Esc", "cancel"),
         ],
+        InputMode::UndoCommitConfirm => vec![("Enter", "undo"), ("Esc", "cancel")],
+        InputMode::LogSearch => vec![("Enter", "search"), ("Esc", "cancel")],
+        InputMode::CheckoutConfirm if app.checkout_return.is_some() => {
+            vec![
+                ("Enter", "checkout"),
+                ("b", "back to branch"),
+                ("Esc", "cancel"),
+            ]
+        }
+        InputMode::CheckoutConfirm => vec![("Enter", "checkout"), ("Esc", "cancel")],
+        InputMode::CommitDetail => vec![
+            ("j/k", "file"),
+            ("Enter", "diff"),
+            ("1/2", "parent"),
+            ("p", "diff vs parent"),
+            ("d", "diff command"),
+            ("y", "copy hash"),
+            ("Esc", "close"),
+        ],
+        InputMode::RemoveLockConfirm => vec![("Enter", "remove"), ("Esc", "cancel")],
+        InputMode::IncomingView => vec![
+            ("j/k", "file"),
+            ("Enter", "diff"),
+            ("p", "pull"),
+            ("Esc", "close"),
+        ],
+        InputMode::CompareView => vec![
+            ("j/k", "file"),
+            ("Enter", "diff"),
+            ("y", "copy command"),
+            ("Esc", "close"),
+        ],
+        InputMode::ReflogView => vec![
+            ("j/k", "move"),
+            ("c", "checkout"),
+            ("r", "reset branch"),
+            ("Esc", "close"),
+        ],
+        InputMode::ReflogResetConfirm => vec![("Enter", "reset"), ("Esc", "back")],
+        InputMode::CommitFileDiff => vec![("j/k", "hunk"), ("J/K", "file"), ("Esc", "back")],
         InputMode::DeleteTagConfirm => {
             vec![
                 ("Enter", "delete all"),
@@ -396,6 +1266,20 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
                 ("Esc", "cancel"),
             ]
         }
+        InputMode::TagDetail => vec![("Esc", "close")],
+        InputMode::TagsView => vec![
+            ("j/k", "move"),
+            ("Enter", "show in log"),
+            (
+                "s",
+                if app.tags_by_date {
+                    "sort by version"
+                } else {
+                    "sort by date"
+                },
+            ),
+            ("Esc", "close"),
+        ],
         InputMode::DiffConfirm => vec![("Enter", "copy"), ("Esc", "cancel")],
         InputMode::WorktreeTypeSelect => {
             vec![("j/k", "move"), ("Enter", "select"), ("Esc", "back")]
@@ -412,7 +1296,119 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
         InputMode::WorktreeRemoveConfirm => vec![("y", "remove"), ("Esc", "cancel")],
         InputMode::CherryPickInput => vec![("Enter", "cherry-pick"), ("Esc", "cancel")],
         InputMode::BranchSelect => vec![("j/k", "move"), ("Enter", "execute"), ("Esc", "cancel")],
+        InputMode::StashInput => vec![("Enter", "stash"), ("Esc", "cancel")],
+        InputMode::GitignoreInput => vec![("Enter", "add to .gitignore"), ("Esc", "cancel")],
+        InputMode::MoveInput => vec![("Enter", "git mv"), ("Esc", "cancel")],
+        InputMode::QuickLook => vec![
+            ("j/k", "next file"),
+            ("Space", "stage/unstage"),
+            ("⏎", "full diff"),
+            ("Esc", "close"),
+        ],
+        InputMode::Review => vec![
+            ("j/k", "file"),
+            ("J/K", "scroll"),
+            ("Space", "approve"),
+            ("x", "reject"),
+            ("u", "undo reject"),
+            ("e", "end review"),
+            ("Esc", "close"),
+        ],
+        InputMode::ChangelogInput => vec![("Enter", "add fragment"), ("Esc", "cancel")],
+        InputMode::AmendPreview => vec![("Enter", "edit message"), ("Esc", "cancel")],
+        InputMode::CommitTypeSelect => {
+            vec![("j/k", "type"), ("Enter", "scope"), ("Esc", "no prefix")]
+        }
+        InputMode::CommitScopeInput => {
+            vec![("Enter", "ok"), ("Tab", "next scope"), ("Esc", "back")]
+        }
+        InputMode::CommitDateInput => vec![("Enter", "set date"), ("Esc", "back")],
+        InputMode::ProfileSelect => vec![("j/k", "move"), ("Enter", "use"), ("Esc", "cancel")],
+        InputMode::CommitFileSelect => vec![
+            ("j/k", "move"),
+            ("Space", "toggle"),
+            ("Enter", "commit checked"),
+            ("Esc", "back"),
+        ],
+        InputMode::CoAuthorSelect => vec![
+            ("j/k", "move"),
+            ("Space", "toggle"),
+            ("Enter", "apply"),
+            ("Esc", "cancel"),
+        ],
+        InputMode::CommitSizeWarning => {
+            vec![("Enter", "commit anyway"), ("Esc", "back to split")]
+        }
+        InputMode::CommitReview => vec![
+            ("Enter", "commit"),
+            ("j/k", "scroll"),
+            ("Space", "page"),
+            ("Esc", "edit message"),
+        ],
+        InputMode::CommitWarning => vec![
+            ("j/k", "move"),
+            ("o", "open file"),
+            ("Enter", "commit anyway"),
+            ("Esc", "back"),
+        ],
+        InputMode::StashDropConfirm => vec![("Enter", "drop"), ("Esc", "cancel")],
+        InputMode::BranchNameInput if app.renaming_branch.is_some() => {
+            vec![("Enter", "rename"), ("Esc", "cancel")]
+        }
+        InputMode::BranchNameInput => vec![("Enter", "create & switch"), ("Esc", "cancel")],
+        InputMode::BranchDeleteConfirm => vec![("Enter", "delete"), ("Esc", "cancel")],
+        InputMode::OutputView => vec![
+            ("j/k", "scroll"),
+            (
+                "Esc",
+                if app.recovery_actions.is_empty() {
+                    "close"
+                } else {
+                    "fixes"
+                },
+            ),
+        ],
+        InputMode::RecoveryPrompt => vec![("o", "output"), ("Esc", "dismiss")],
+        InputMode::HunkView if app.diff_view.as_ref().is_some_and(|v| v.collapsed) => {
+            vec![("a", "show anyway"), ("Esc", "close")]
+        }
+        InputMode::HunkView
+            if app
+                .diff_view
+                .as_ref()
+                .is_some_and(|v| v.line_selection.is_some()) =>
+        {
+            let staged = app.diff_view.as_ref().is_some_and(|v| v.staged);
+            vec![
+                ("j/k", "extend"),
+                (
+                    "Space",
+                    if staged {
+                        "unstage lines"
+                    } else {
+                        "stage lines"
+                    },
+                ),
+                ("Esc", "hunk mode"),
+            ]
+        }
+        InputMode::HunkView => {
+            let staged = app.diff_view.as_ref().is_some_and(|v| v.staged);
+            vec![
+                ("j/k", "hunk"),
+                ("Space", if staged { "unstage hunk" } else { "stage hunk" }),
+                ("V", "lines"),
+                ("x", "discard hunk"),
+                ("Esc", "close"),
+            ]
+        }
         InputMode::Normal => match app.tab {
+            Tab::Files if !app.marked_files.is_empty() => vec![
+                ("v", "mark"),
+                ("Space", "stage/unstage marked"),
+                ("x", "discard marked"),
+                ("Esc", "clear marks"),
+            ],
             Tab::Files => {
                 let mut hints = vec![
                     ("⏎", "diff"),
@@ -420,34 +1416,131 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
                     ("a", "stage all"),
                     ("x", app.files_x_action_label()),
                     ("X", "discard all"),
+                    ("d", "hunks"),
+                    ("K", "peek"),
+                    (
+                        "B",
+                        if app.review.is_some() {
+                            "reviewing"
+                        } else {
+                            "review"
+                        },
+                    ),
+                    ("s", "stash"),
+                    ("i", "ignore"),
                     ("c", "commit"),
                     ("P", "push"),
+                    ("y", "copy path"),
                     ("C", "cherry-pick"),
                     ("m", "merge"),
                     ("b", "rebase"),
+                    ("f", "filter"),
                 ];
+                if app.selected_file().is_some_and(|f| f.nested_repo) {
+                    hints[0] = ("⏎", "open repo");
+                } else if app
+                    .selected_file()
+                    .is_some_and(|f| f.status == FileStatus::Untracked)
+                {
+                    hints.insert(5, ("D", "delete"));
+                    hints.insert(6, ("N", "intent-add"));
+                } else if app.selected_file().is_some_and(|f| f.intent_to_add) {
+                    hints.insert(5, ("N", "untrack"));
+                } else if app.selected_file().is_some_and(|f| f.hidden.is_some()) {
+                    hints.insert(5, ("H", "unhide"));
+                } else if app
+                    .selected_file()
+                    .is_some_and(|f| !f.staged && f.status == FileStatus::Deleted)
+                {
+                    hints.insert(5, ("u", "restore"));
+                } else if app
+                    .selected_file()
+                    .is_some_and(|f| !f.staged && f.status == FileStatus::Modified)
+                {
+                    hints.insert(5, ("H", "hide"));
+                }
+                if app.files.iter().any(|f| f.generated) {
+                    hints.push((
+                        "g",
+                        if app.show_generated {
+                            "hide generated"
+                        } else {
+                            "show generated"
+                        },
+                    ));
+                }
                 if app.available_repos.len() > 1 {
                     hints.push(("r", "repos"));
                 }
+                if app.last_output.is_some() {
+                    hints.push(("o", "output"));
+                }
+                hints.push(mouse_hint(app));
                 hints.push(("q", "quit"));
                 hints
             }
+            Tab::Log if app.on_incoming_row() => {
+                vec![
+                    ("⏎", "incoming"),
+                    ("j", "commits"),
+                    ("p", "pull"),
+                    ("q", "quit"),
+                ]
+            }
             Tab::Log => {
-                let mut hints = vec![
-                    ("⏎", "diff"),
+                let mut hints = vec![("⏎", "details"), ("/", "search")];
+                if app.log_search.is_some() {
+                    hints.push(("n/N", "match"));
+                }
+                hints.extend([
                     ("e", "amend"),
                     ("t", "tag"),
                     ("x", "del tag"),
+                    ("a", "tag info"),
+                    ("g", "tags"),
+                    ("L", "reflog"),
                     ("P", "push"),
                     ("p", "pull"),
-                    ("y", "copy"),
+                    ("y/s", "copy"),
+                    ("v", "revert"),
+                    ("Space", "mark range"),
+                    ("d", "compare"),
                     ("C", "cherry-pick"),
                     ("m", "merge"),
                     ("b", "rebase"),
-                ];
+                ]);
                 if app.available_repos.len() > 1 {
                     hints.push(("r", "repos"));
                 }
+                if app.last_output.is_some() {
+                    hints.push(("o", "output"));
+                }
+                hints.push(mouse_hint(app));
+                hints.push(("q", "quit"));
+                hints
+            }
+            Tab::Stash => {
+                let mut hints = vec![("⏎", "diff"), ("a", "apply"), ("p", "pop"), ("x", "drop")];
+                if app.last_output.is_some() {
+                    hints.push(("o", "output"));
+                }
+                hints.push(mouse_hint(app));
+                hints.push(("q", "quit"));
+                hints
+            }
+            Tab::Branches => {
+                let mut hints = vec![
+                    ("⏎", "checkout"),
+                    ("n", "new"),
+                    ("e", "rename"),
+                    ("x", "delete"),
+                    ("m", "merge"),
+                    ("b", "rebase"),
+                ];
+                if app.last_output.is_some() {
+                    hints.push(("o", "output"));
+                }
+                hints.push(mouse_hint(app));
                 hints.push(("q", "quit"));
                 hints
             }
@@ -456,10 +1549,15 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
 
     let mut spans: Vec<Span> = Vec::new();
     for (i, (key, action)) in hints.iter().enumerate() {
+        let key = if config().ui.screen_reader {
+            key.replace('⏎', "Enter")
+        } else {
+            key.to_string()
+        };
         if i > 0 {
             spans.push(Span::styled("  ", Style::default()));
         }
-        spans.push(Span::styled(*key, Style::default().fg(colors::blue())));
+        spans.push(Span::styled(key, Style::default().fg(colors::blue())));
         spans.push(Span::styled(
             format!(" {}", action),
             Style::default().fg(colors::dim()),
@@ -470,7 +1568,7 @@ fn render_hints(frame: &mut Frame, app: &App, area: Rect) {
         vec![
             Line::from(spans),
             Line::from(Span::styled(
-                msg.clone(),
+                summary_line(msg).to_string(),
                 Style::default().fg(if *is_error {
                     colors::red()
                 } else {
@@ -710,6 +1808,20 @@ fn render_tag_dialog(frame: &mut Frame, app: &App) {
         Style::default().fg(colors::fg_bright()),
     )));
 
+    // Preview when the branch has its own tag format
+    let version = app.tag_input.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    if warning.is_none() && app.tag_format() != app.repo_config.version.tag_format {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "→ {} (on {})",
+                crate::version::generate_tag_name(version, app.tag_format()),
+                app.branch_name
+            ),
+            Style::default().fg(colors::dim()),
+        )));
+    }
+
     frame.render_widget(Paragraph::new(lines), inner);
 
     // Cursor position
@@ -717,31 +1829,77 @@ fn render_tag_dialog(frame: &mut Frame, app: &App) {
     frame.set_cursor_position((inner.x + 5 + app.tag_input.width() as u16, cursor_y));
 }
 
-fn render_processing_overlay(frame: &mut Frame, app: &App) {
+/// Small status chip in the bottom-right corner of the content area.
+/// Shows the spinner while an operation runs, or the failure until the next key.
+fn render_status_chip(frame: &mut Frame, app: &App, area: Rect) {
     use crate::app::Processing;
 
-    let area = centered_rect(30, 3, frame.area());
-    frame.render_widget(Clear, area);
-
-    // Use green for tag push, blue for other operations
-    let border_color = match app.processing {
-        Processing::PushingTags => colors::green(),
-        _ => colors::blue(),
-    };
-
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(border_color));
-
-    let inner = block.inner(area);
-    frame.render_widget(block, area);
+    // One line per finished operation of the batch, then the running and queued ones
+    let mut lines: Vec<(String, Color)> = app
+        .operation_results
+        .iter()
+        .map(|(msg, is_error)| {
+            if *is_error {
+                (format!(" ✗ {} ", msg), colors::red())
+            } else {
+                (format!(" ✓ {} ", msg), colors::green())
+            }
+        })
+        .collect();
+    if app.processing.is_active() {
+        // Use green for tag push, blue for other operations
+        let bg = match app.processing {
+            Processing::PushingTags | Processing::Releasing => colors::green(),
+            _ => colors::blue(),
+        };
+        lines.push((
+            format!(" {} {} ", app.spinner_char(), app.processing.message()),
+            bg,
+        ));
+        for queued in &app.queued_operations {
+            lines.push((format!(" queued: {} ", queued.state.name()), colors::dim()));
+        }
+    } else if lines.len() <= 1
+        && let Some(error) = &app.failed_operation
+    {
+        lines = vec![(format!(" ✗ {} ", error), colors::red())];
+    }
 
-    let text = format!("{} {}", app.spinner_char(), app.processing.message());
-    let paragraph = Paragraph::new(text)
-        .style(Style::default().fg(colors::fg_bright()))
-        .alignment(Alignment::Center);
+    let max_width = area.width.saturating_sub(2) as usize;
+    let visible = lines.len().min(area.height as usize);
+    for (row, (text, bg)) in lines[lines.len() - visible..].iter().enumerate() {
+        let text = truncate_chip_text(text, max_width);
+        let width = text.width() as u16;
+        if width == 0 {
+            continue;
+        }
+        let chip = Rect::new(
+            area.x + area.width.saturating_sub(width + 1),
+            area.y + area.height - visible as u16 + row as u16,
+            width.min(area.width),
+            1,
+        );
+        frame.render_widget(Clear, chip);
+        frame.render_widget(
+            Paragraph::new(text).style(Style::default().bg(*bg).fg(Color::Rgb(0, 0, 0)).bold()),
+            chip,
+        );
+    }
+}
 
-    frame.render_widget(paragraph, inner);
+fn truncate_chip_text(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    for ch in text.chars() {
+        if truncated.width() + 2 > max_width {
+            break;
+        }
+        truncated.push(ch);
+    }
+    truncated.push_str("… ");
+    truncated
 }
 
 pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
@@ -750,8 +1908,104 @@ pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     Rect::new(x, y, width.min(area.width), height.min(area.height))
 }
 
+/// One visible row of the commit input box
+struct MessageLine {
+    text: String,
+    /// Line number in the message (0 = subject)
+    line: usize,
+    /// Characters in the full message line, for the 50/72 lint
+    chars: usize,
+    /// Shown from its first column, so the width guide lines up
+    unscrolled: bool,
+    /// Column of the drawn cursor `│`, which pushes later text right by one
+    cursor: Option<usize>,
+}
+
 /// Build display text for commit input box.
 /// Scrolls text to keep cursor position visible with ellipsis indicators.
+/// `build_input_display` per line of a multi-line message; the cursor is drawn
+/// only on its own line
+fn build_message_display(
+    text: &str,
+    cursor_pos: usize,
+    max_width: usize,
+    input_mode: InputMode,
+) -> Vec<MessageLine> {
+    let insert = input_mode == InputMode::Insert;
+    if !text.contains('\n') {
+        return vec![MessageLine {
+            text: build_input_display(text, cursor_pos, max_width, input_mode),
+            line: 0,
+            chars: text.chars().count(),
+            unscrolled: text.width() <= max_width,
+            cursor: insert.then(|| text[..cursor_pos].width()),
+        }];
+    }
+    let mut start = 0;
+    let mut cursor_line = None;
+    let mut lines = Vec::new();
+    for (i, line) in text.split('\n').enumerate() {
+        let end = start + line.len();
+        let has_cursor = cursor_line.is_none() && (start..=end).contains(&cursor_pos);
+        if has_cursor {
+            cursor_line = Some(i);
+        }
+        let display = if has_cursor && input_mode == InputMode::Insert {
+            build_input_display(line, cursor_pos - start, max_width, input_mode)
+        } else if line.is_empty() {
+            String::new()
+        } else {
+            build_input_display(line, 0, max_width, InputMode::Normal)
+        };
+        lines.push(MessageLine {
+            text: display,
+            line: i,
+            chars: line.chars().count(),
+            unscrolled: line.width() <= max_width,
+            cursor: (has_cursor && insert).then(|| line[..cursor_pos - start].width()),
+        });
+        start = end + 1;
+    }
+    let first = cursor_line
+        .unwrap_or(0)
+        .saturating_sub(MAX_MESSAGE_LINES - 1);
+    lines
+        .into_iter()
+        .skip(first)
+        .take(MAX_MESSAGE_LINES)
+        .collect()
+}
+
+/// Column of the 50/72 guide on a message line: the subject limit for the
+/// subject, the body width from the third line on
+fn message_guide_column(line: &MessageLine) -> Option<usize> {
+    if !line.unscrolled {
+        return None;
+    }
+    let limits = &config().commit;
+    let column = match line.line {
+        0 => limits.subject_limit,
+        1 => return None,
+        _ => limits.body_width,
+    };
+    Some(match line.cursor {
+        Some(cursor) if cursor <= column => column + 1,
+        _ => column,
+    })
+}
+
+/// Lint color for a message line: subject past 50 / 72, body past 72 (configurable)
+fn message_line_color(line: &MessageLine) -> Option<Color> {
+    let limits = &config().commit;
+    match line.line {
+        0 if line.chars > limits.subject_max => Some(colors::red()),
+        0 if line.chars > limits.subject_limit => Some(colors::yellow()),
+        1 => None,
+        n if n > 1 && line.chars > limits.body_width => Some(colors::yellow()),
+        _ => None,
+    }
+}
+
 fn build_input_display(
     text: &str,
     cursor_pos: usize,
@@ -875,39 +2129,184 @@ fn repo_display_name(path: &std::path::Path, base_dir: &std::path::Path) -> Stri
         })
 }
 
-fn render_version_confirm_dialog(frame: &mut Frame, app: &App) {
-    let Some(pending) = &app.pending_version_update else {
-        return;
-    };
+/// `~/src/app` for paths under $HOME, without the workdir's trailing slash
+fn home_relative(path: &std::path::Path) -> String {
+    let home = std::env::var_os("HOME").filter(|h| !h.is_empty());
+    // Component-wise, so /home/user2 isn't shortened against /home/user
+    match home.as_deref().and_then(|h| path.strip_prefix(h).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+    .trim_end_matches('/')
+    .to_string()
+}
+
+/// Keep the end of a path (the repository name) when it doesn't fit
+fn truncate_left(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let mut tail: Vec<char> = Vec::new();
+    let mut width = 1;
+    for c in text.chars().rev() {
+        width += unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        if width > max {
+            break;
+        }
+        tail.push(c);
+    }
+    format!("…{}", tail.iter().rev().collect::<String>())
+}
+
+fn render_amend_preview_dialog(frame: &mut Frame, app: &App) {
+    let staged = app.amend_preview.iter().filter(|f| f.staged).count();
+    let mut lines = vec![
+        if staged == 0 {
+            Line::from(Span::styled(
+                "Nothing staged: only the message changes",
+                Style::default().fg(colors::dim()),
+            ))
+        } else {
+            Line::from(Span::styled(
+                format!(
+                    "{} staged file{} will be folded into HEAD",
+                    staged,
+                    if staged == 1 { "" } else { "s" }
+                ),
+                Style::default().fg(colors::yellow()),
+            ))
+        },
+        Line::from(""),
+    ];
+
+    // Leave room for the border, the summary and the overflow line
+    let max_files = (frame.area().height as usize).saturating_sub(8).max(1);
+    for file in app.amend_preview.iter().take(max_files) {
+        lines.push(amend_file_line(file));
+    }
+    if app.amend_preview.len() > max_files {
+        lines.push(Line::from(Span::styled(
+            format!("… and {} more", app.amend_preview.len() - max_files),
+            Style::default().fg(colors::dim()),
+        )));
+    }
 
-    let height = 6 + pending.files.len() as u16;
-    let area = centered_rect(50, height.min(15), frame.area());
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(60, height, frame.area());
     frame.render_widget(Clear, area);
 
     let block = Block::default()
-        .title(" Version Update ")
+        .title(" Amend HEAD ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(colors::blue()));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
 
-    let mut lines = vec![
-        Line::from(format!(
-            "Version: {} → Tag: {}",
-            pending.new_version, pending.tag_name
-        )),
-        Line::from(""),
-        Line::from("Files to update:"),
+/// "M src/app.rs  +12 −3  HEAD + staged"
+fn amend_file_line(file: &AmendFile) -> Line<'static> {
+    let (note, color) = match (file.in_head, file.staged) {
+        (true, true) => ("HEAD + staged", colors::yellow()),
+        (false, true) => ("staged", colors::yellow()),
+        _ => ("HEAD", colors::dim()),
+    };
+    let mut spans = vec![
+        Span::styled(format!("{} ", file.status), Style::default().fg(color)),
+        Span::styled(file.path.clone(), Style::default().fg(colors::fg())),
     ];
+    if let Some((add, del)) = file.stats {
+        spans.push(Span::styled(
+            format!("  +{}", add),
+            Style::default().fg(colors::green()),
+        ));
+        spans.push(Span::styled(
+            format!(" −{}", del),
+            Style::default().fg(colors::red()),
+        ));
+    }
+    spans.push(Span::styled(
+        format!("  {}", note),
+        Style::default().fg(color),
+    ));
+    Line::from(spans)
+}
+
+fn render_version_confirm_dialog(frame: &mut Frame, app: &App) {
+    let Some(pending) = &app.pending_version_update else {
+        return;
+    };
 
+    // Every step of the release pipeline, confirmed once
+    let mut lines = vec![Line::from("1. Update files:")];
     for file in &pending.files {
         lines.push(Line::from(format!(
-            "  {} ({} → {})",
+            "   {} ({} → {})",
             file.path, file.current_version, pending.new_version
         )));
+        if let Some(command) = &file.post_update {
+            lines.push(Line::from(Span::styled(
+                format!("     then: {}", command),
+                Style::default().fg(colors::dim()),
+            )));
+        }
+    }
+    if !pending.fragments.is_empty() {
+        lines.push(Line::from(format!(
+            "   {}: {} fragment{} (removed from {})",
+            app.repo_config.changelog.file,
+            pending.fragments.len(),
+            if pending.fragments.len() == 1 {
+                ""
+            } else {
+                "s"
+            },
+            app.repo_config.changelog.dir
+        )));
+    }
+    lines.push(Line::from(format!(
+        "2. Commit: {}",
+        app.repo_config
+            .version
+            .commit_message
+            .replace("{version}", &pending.new_version)
+    )));
+    lines.push(Line::from(format!("3. Tag: {}", pending.tag_name)));
+    lines.push(Line::from(vec![
+        Span::raw("4. Push: "),
+        if pending.push {
+            Span::styled("commit + tag", Style::default().fg(colors::green()))
+        } else {
+            Span::styled("skip", Style::default().fg(colors::dim()))
+        },
+        Span::styled("  (p to toggle)", Style::default().fg(colors::dim())),
+    ]));
+    if !app.files.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "⚠ {} uncommitted change{} stay out of the release commit",
+                app.files.len(),
+                if app.files.len() == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(colors::yellow()),
+        )));
     }
 
+    let height = lines.len() as u16 + 2;
+    let area = centered_rect(60, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Release {} ", pending.new_version))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
     let paragraph = Paragraph::new(lines).style(Style::default().fg(colors::fg()));
     frame.render_widget(paragraph, inner);
 }
@@ -935,9 +2334,17 @@ fn render_uncommitted_warning_dialog(frame: &mut Frame, _app: &App) {
 fn render_discard_confirm_dialog(frame: &mut Frame, app: &App) {
     let (title, lines) = match &app.pending_discard {
         Some(PendingDiscardTarget::All(targets)) => (
-            " Discard All Changes ",
+            if app.marked_files.is_empty() {
+                " Discard All Changes "
+            } else {
+                " Discard Marked Changes "
+            },
             vec![
-                Line::from(format!("Discard all {} unstaged files?", targets.len())),
+                Line::from(if app.marked_files.is_empty() {
+                    format!("Discard all {} unstaged files?", targets.len())
+                } else {
+                    format!("Discard {} marked files?", targets.len())
+                }),
                 Line::from(""),
                 Line::from(Span::styled(
                     "This cannot be undone!",
@@ -945,10 +2352,25 @@ fn render_discard_confirm_dialog(frame: &mut Frame, app: &App) {
                 )),
             ],
         ),
-        Some(PendingDiscardTarget::Single(pending)) => (
-            pending.action.confirm_title(),
+        Some(PendingDiscardTarget::Hunk(pending)) => (
+            " Discard Hunk ",
             vec![
-                Line::from(pending.action.confirm_heading()),
+                Line::from(format!("Discard hunk in {}:", pending.path)),
+                Line::from(Span::styled(
+                    pending.header.as_str(),
+                    Style::default().fg(colors::yellow()),
+                )),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "This cannot be undone!",
+                    Style::default().fg(colors::red()),
+                )),
+            ],
+        ),
+        Some(PendingDiscardTarget::Single(pending)) => (
+            pending.action.confirm_title(),
+            vec![
+                Line::from(pending.action.confirm_heading()),
                 Line::from(Span::styled(
                     pending.path.as_str(),
                     Style::default().fg(colors::yellow()),
@@ -963,7 +2385,9 @@ fn render_discard_confirm_dialog(frame: &mut Frame, app: &App) {
         None => return,
     };
 
-    let area = centered_rect(45, 6, frame.area());
+    // Wide enough for a long path or the trash note
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+    let area = centered_rect(width.max(45), 6, frame.area());
     frame.render_widget(Clear, area);
 
     let block = Block::default()
@@ -1012,6 +2436,249 @@ fn render_delete_tag_confirm_dialog(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, inner);
 }
 
+fn render_undo_commit_dialog(frame: &mut Frame, app: &App) {
+    let Some((label, pushed)) = &app.pending_undo_commit else {
+        return;
+    };
+
+    let area = centered_rect(50, if *pushed { 7 } else { 6 }, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Undo Commit ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if *pushed {
+            colors::red()
+        } else {
+            colors::yellow()
+        }));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            label.as_str(),
+            Style::default().fg(colors::yellow()),
+        )),
+        Line::from("Its changes go back to STAGED"),
+    ];
+    if *pushed {
+        lines.push(Line::from(Span::styled(
+            "Already pushed: the remote keeps it until you force-push",
+            Style::default().fg(colors::red()),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter: undo  Esc: cancel",
+        Style::default().fg(colors::dim()),
+    )));
+
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(paragraph, inner);
+}
+
+/// One line per problem that will make git operations fail
+fn render_health_banner(frame: &mut Frame, app: &App, area: Rect) {
+    let lines: Vec<Line> = app
+        .health
+        .iter()
+        .map(|issue| {
+            let color = if issue.blocks_writes() {
+                colors::red()
+            } else {
+                colors::yellow()
+            };
+            Line::from(Span::styled(
+                format!(" ⚠ {}", issue.describe()),
+                Style::default().fg(color).bold(),
+            ))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+fn render_remove_lock_dialog(frame: &mut Frame, app: &App) {
+    let age = app.health.iter().find_map(|h| match h {
+        HealthIssue::IndexLock(age) => Some(*age),
+        _ => None,
+    });
+    let Some(age) = age else {
+        return;
+    };
+
+    let area = centered_rect(60, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Remove index.lock ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::red()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let created = match age {
+        Some(seconds) => format!("The lock is {}", format_age(seconds)),
+        None => "Left behind by a git process".to_string(),
+    };
+    let lines = vec![
+        Line::from(Span::styled(created, Style::default().fg(colors::yellow()))),
+        Line::from("Only remove it if no other git command is running"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter: remove  Esc: cancel",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_tag_detail(frame: &mut Frame, app: &App) {
+    if app.tag_details.is_empty() {
+        return;
+    }
+    let mut lines: Vec<Line> = Vec::new();
+    for tag in &app.tag_details {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        let (kind, color) = if tag.pushed {
+            ("pushed", colors::magenta())
+        } else {
+            ("local only", colors::yellow())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(tag.name.clone(), Style::default().fg(color).bold()),
+            Span::styled(format!("  {}", kind), Style::default().fg(colors::dim())),
+        ]));
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{:<9}", label), Style::default().fg(colors::dim())),
+                Span::styled(value, Style::default().fg(colors::fg())),
+            ])
+        };
+        lines.push(field("Commit", tag.target.clone()));
+        match &tag.tagger {
+            Some(tagger) => {
+                lines.push(field("Tagger", tagger.clone()));
+                lines.push(field("Date", tag.date.clone()));
+            }
+            None => lines.push(field("Date", format!("{} (lightweight tag)", tag.date))),
+        }
+        if let Some(annotation) = &tag.annotation {
+            lines.push(field("Message", annotation.clone()));
+        }
+    }
+
+    let full = frame.area();
+    let height = (lines.len() as u16 + 2).min(full.height);
+    let area = centered_rect(full.width.saturating_sub(4).min(80), height, full);
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Tags ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::magenta()));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Every tag: name, pushed state, tagged commit, date and annotation
+fn render_tags_view(frame: &mut Frame, app: &mut App) {
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(8).max(40),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let order = if app.tags_by_date { "date" } else { "version" };
+    let block = Block::default()
+        .title(format!(" Tags ({}, by {}) ", app.tags_view.len(), order))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::magenta()));
+
+    let name_width = app
+        .tags_view
+        .iter()
+        .map(|t| t.name.width())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = app
+        .tags_view
+        .iter()
+        .map(|tag| {
+            let (state, color) = if tag.pushed {
+                ("  ", colors::magenta())
+            } else {
+                ("● ", colors::yellow())
+            };
+            // The relative part of "2024-05-01 10:00 UTC (3 days ago)"
+            let age = tag
+                .date
+                .rsplit_once(" (")
+                .map_or(tag.date.as_str(), |(_, age)| age.trim_end_matches(')'));
+            let mut spans = vec![
+                Span::styled(state, Style::default().fg(colors::yellow())),
+                Span::styled(
+                    format!("{:<width$}  ", tag.name, width = name_width),
+                    Style::default().fg(color).bold(),
+                ),
+                Span::styled(format!("{:<14}", age), Style::default().fg(colors::dim())),
+                Span::styled(tag.target.clone(), Style::default().fg(colors::fg())),
+            ];
+            if let Some(annotation) = &tag.annotation {
+                spans.push(Span::styled(
+                    format!("  \"{}\"", annotation),
+                    Style::default().fg(colors::dim()),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, &mut app.tags_view_state);
+}
+
+fn render_checkout_dialog(frame: &mut Frame, app: &App) {
+    let Some((_, label)) = &app.pending_checkout else {
+        return;
+    };
+
+    let area = centered_rect(54, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Checkout Commit ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::yellow()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let hint = match &app.checkout_return {
+        Some(branch) => format!("Enter: checkout  b: back to {}  Esc: cancel", branch),
+        None => "Enter: checkout  Esc: cancel".to_string(),
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            label.as_str(),
+            Style::default().fg(colors::yellow()),
+        )),
+        Line::from("HEAD will be detached: new commits"),
+        Line::from("belong to no branch until you create one"),
+        Line::from(""),
+        Line::from(Span::styled(hint, Style::default().fg(colors::dim()))),
+    ];
+
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(paragraph, inner);
+}
+
 fn render_worktree_type_dialog(frame: &mut Frame, app: &App) {
     let area = centered_rect(45, 7, frame.area());
     frame.render_widget(Clear, area);
@@ -1216,6 +2883,12 @@ fn render_diff_confirm_dialog(frame: &mut Frame, app: &App) {
     let Some(cmd) = &app.pending_diff_command else {
         return;
     };
+    let merge = app.tab == Tab::Log
+        && app
+            .commits_state
+            .selected()
+            .and_then(|i| app.commits.get(i))
+            .is_some_and(|c| c.is_merge());
 
     let area = centered_rect(60, 5, frame.area());
     frame.render_widget(Clear, area);
@@ -1234,12 +2907,23 @@ fn render_diff_confirm_dialog(frame: &mut Frame, app: &App) {
             Style::default().fg(colors::fg_bright()),
         )),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("Enter", Style::default().fg(colors::blue())),
-            Span::styled(" copy  ", Style::default().fg(colors::dim())),
-            Span::styled("Esc", Style::default().fg(colors::blue())),
-            Span::styled(" cancel", Style::default().fg(colors::dim())),
-        ]),
+        Line::from(if merge {
+            vec![
+                Span::styled("Enter", Style::default().fg(colors::blue())),
+                Span::styled(" combined  ", Style::default().fg(colors::dim())),
+                Span::styled("1/2", Style::default().fg(colors::blue())),
+                Span::styled(" vs parent  ", Style::default().fg(colors::dim())),
+                Span::styled("Esc", Style::default().fg(colors::blue())),
+                Span::styled(" cancel", Style::default().fg(colors::dim())),
+            ]
+        } else {
+            vec![
+                Span::styled("Enter", Style::default().fg(colors::blue())),
+                Span::styled(" copy  ", Style::default().fg(colors::dim())),
+                Span::styled("Esc", Style::default().fg(colors::blue())),
+                Span::styled(" cancel", Style::default().fg(colors::dim())),
+            ]
+        }),
     ];
 
     let paragraph = Paragraph::new(lines);
@@ -1303,3 +2987,1535 @@ fn render_branch_select_dialog(frame: &mut Frame, app: &mut App) {
 
     frame.render_stateful_widget(list, inner, &mut app.branch_select_state);
 }
+
+fn render_hunk_view(frame: &mut Frame, app: &App) {
+    let Some(view) = &app.diff_view else {
+        return;
+    };
+
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(4),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let section = if view.staged { "staged" } else { "unstaged" };
+    let title = if view.hunks.is_empty() {
+        format!(" {} ({}) ", view.path, section)
+    } else if let Some((anchor, cursor)) = view.line_selection {
+        format!(
+            " {} ({}) - hunk {}/{}, {} line(s) selected ",
+            view.path,
+            section,
+            view.selected + 1,
+            view.hunks.len(),
+            anchor.abs_diff(cursor) + 1
+        )
+    } else {
+        format!(
+            " {} ({}) - hunk {}/{} ",
+            view.path,
+            section,
+            view.selected + 1,
+            view.hunks.len()
+        )
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Header: mode / symlink changes and line ending / encoding detection result
+    let mut header_lines: Vec<Line> = Vec::new();
+    if let Some((old, new)) = view.mode_change {
+        header_lines.push(Line::from(Span::styled(
+            format!("mode {}", mode_change_label(old, new)),
+            Style::default().fg(colors::blue()),
+        )));
+    }
+    if let Some((old, new)) = view.binary_size {
+        header_lines.push(Line::from(Span::styled(
+            format!(
+                "binary file changed: {} → {}",
+                format_size(old),
+                format_size(new)
+            ),
+            Style::default().fg(colors::blue()),
+        )));
+    }
+    if let Some(target) = &view.symlink_target {
+        header_lines.push(Line::from(Span::styled(
+            format!("symlink → {}", target),
+            Style::default().fg(colors::blue()),
+        )));
+    }
+    if let Some(attribute) = &view.diff_attribute {
+        let text = if view.collapsed {
+            format!(
+                "diff collapsed by .gitattributes ({}), a: show anyway",
+                attribute
+            )
+        } else {
+            format!("shown despite .gitattributes ({})", attribute)
+        };
+        header_lines.push(Line::from(Span::styled(
+            text,
+            Style::default().fg(colors::dim()),
+        )));
+    }
+    header_lines.extend(view.issues.iter().map(|issue| {
+        Line::from(Span::styled(
+            format!("⚠ {}", issue.label()),
+            Style::default().fg(colors::yellow()),
+        ))
+    }));
+    let chunks = Layout::vertical([
+        Constraint::Length(header_lines.len() as u16),
+        Constraint::Min(0),
+    ])
+    .split(inner);
+    frame.render_widget(Paragraph::new(header_lines), chunks[0]);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut scroll = 0u16;
+    for (i, hunk) in view.hunks.iter().enumerate() {
+        let is_selected = i == view.selected;
+        if is_selected {
+            scroll = lines.len() as u16;
+        }
+        let header_style = if is_selected {
+            Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0))
+        } else {
+            Style::default().fg(colors::blue())
+        };
+        lines.push(Line::from(Span::styled(hunk.header.clone(), header_style)));
+        let hunk_top = lines.len();
+        for (j, line) in hunk.lines.iter().enumerate() {
+            let color = match line.chars().next() {
+                Some('+') => colors::green(),
+                Some('-') => colors::red(),
+                _ => colors::fg(),
+            };
+            let style = if is_selected {
+                Style::default().fg(color)
+            } else {
+                Style::default().fg(colors::dim())
+            };
+            let style = match view.line_selection {
+                Some((anchor, cursor))
+                    if is_selected && (anchor.min(cursor)..=anchor.max(cursor)).contains(&j) =>
+                {
+                    style.bg(Color::DarkGray)
+                }
+                _ => style,
+            };
+            lines.push(Line::from(Span::styled(line.clone(), style)));
+        }
+        // Keep the line-mode cursor on screen in long hunks
+        if is_selected && let Some((_, cursor)) = view.line_selection {
+            let height = chunks[1].height as usize;
+            let cursor_row = hunk_top + cursor;
+            if cursor_row >= scroll as usize + height {
+                scroll = (cursor_row + 1 - height) as u16;
+            }
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), chunks[1]);
+}
+
+/// Standalone diff viewer for `siori pager` / `siori difftool`: the hunk view
+/// layout, with the file list in the title
+pub fn render_diff_pager(frame: &mut Frame, pager: &DiffPager) {
+    let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(frame.area());
+    let Some(file) = pager.files.get(pager.file) else {
+        return;
+    };
+    let block = Block::default()
+        .title(format!(
+            " {} - file {}/{}, hunk {}/{} ",
+            file.path,
+            pager.file + 1,
+            pager.files.len(),
+            pager.hunk + 1,
+            file.hunks.len()
+        ))
+        .title_bottom(Line::from(Span::styled(
+            format!(" {} ", pager.title),
+            Style::default().fg(colors::dim()),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut scroll = 0u16;
+    for (i, hunk) in file.hunks.iter().enumerate() {
+        let is_selected = i == pager.hunk;
+        if is_selected {
+            scroll = lines.len() as u16;
+        }
+        let header_style = if is_selected {
+            Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0))
+        } else {
+            Style::default().fg(colors::blue())
+        };
+        lines.push(Line::from(Span::styled(hunk.header.clone(), header_style)));
+        for line in &hunk.lines {
+            let color = match change_marker(hunk, line) {
+                Some('+') => colors::green(),
+                Some('-') => colors::red(),
+                _ => colors::fg(),
+            };
+            lines.push(Line::from(Span::styled(
+                line.clone(),
+                Style::default().fg(color),
+            )));
+        }
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(block).scroll((scroll, 0)),
+        chunks[0],
+    );
+
+    let hints = [("j/k", "hunk"), ("J/K", "file"), ("q", "quit")];
+    let spans: Vec<Span> = hints
+        .iter()
+        .flat_map(|(key, desc)| {
+            [
+                Span::styled(
+                    format!(" {} ", key),
+                    Style::default().fg(colors::fg_bright()),
+                ),
+                Span::styled(format!("{}  ", desc), Style::default().fg(colors::dim())),
+            ]
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[1]);
+}
+
+/// `text` split into spans with every case-insensitive occurrence of `query` highlighted
+fn highlight_matches(text: &str, query: Option<&str>, style: Style) -> Vec<Span<'static>> {
+    let Some(query) = query.filter(|q| !q.is_empty()) else {
+        return vec![Span::styled(text.to_string(), style)];
+    };
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let chars: Vec<char> = text.chars().collect();
+    let needle: Vec<char> = query.chars().map(lower).collect();
+    let highlight = style.bg(colors::yellow()).fg(Color::Rgb(0, 0, 0));
+
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars.len() - i >= needle.len()
+            && chars[i..i + needle.len()]
+                .iter()
+                .zip(&needle)
+                .all(|(c, n)| lower(*c) == *n)
+        {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), style));
+            }
+            spans.push(Span::styled(
+                chars[i..i + needle.len()].iter().collect::<String>(),
+                highlight,
+            ));
+            i += needle.len();
+        } else {
+            plain.push(chars[i]);
+            i += 1;
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, style));
+    }
+    spans
+}
+
+fn render_log_search_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Search Commits ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("/", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.log_search_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(
+            "subject, author or hash",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_stash_input_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Stash Changes ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Message: > ", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.stash_message_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(
+            "(optional)",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_stash_drop_confirm_dialog(frame: &mut Frame, app: &App) {
+    let Some(stash) = app.stash_state.selected().and_then(|i| app.stashes.get(i)) else {
+        return;
+    };
+
+    let area = centered_rect(45, 6, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Drop Stash ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::red()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(format!("Drop stash@{{{}}}?", stash.index)),
+        Line::from(Span::styled(
+            stash.message.as_str(),
+            Style::default().fg(colors::yellow()),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "This cannot be undone!",
+            Style::default().fg(colors::red()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), inner);
+}
+
+fn render_branch_name_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let (title, note) = match &app.renaming_branch {
+        Some(old) => (" Rename Branch ", format!("renames {}", old)),
+        None => (
+            " New Branch ",
+            "created at HEAD and checked out".to_string(),
+        ),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Name: > ", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.branch_name_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(note, Style::default().fg(colors::dim()))),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_branch_delete_confirm_dialog(frame: &mut Frame, app: &App) {
+    let Some(branch) = app
+        .branches_state
+        .selected()
+        .and_then(|i| app.branches.get(i))
+    else {
+        return;
+    };
+
+    let area = centered_rect(64, 6, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Delete Branch ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::red()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(format!("Delete {}?", branch.name)),
+        Line::from(Span::styled(
+            format!("{} {}", branch.id, branch.subject),
+            Style::default().fg(colors::yellow()),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Not fully merged: its commits will only be in the reflog",
+            Style::default().fg(colors::red()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), inner);
+}
+
+fn render_recovery_dialog(frame: &mut Frame, app: &App) {
+    let Some(output) = &app.last_output else {
+        return;
+    };
+
+    let height = app.recovery_actions.len() as u16 + 4;
+    let area = centered_rect(55, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" {} Failed ", output.title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::red()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            summary_line(&output.text).to_string(),
+            Style::default().fg(colors::red()),
+        )),
+        Line::from(""),
+    ];
+    for action in &app.recovery_actions {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {}  ", action.key()),
+                Style::default().fg(colors::blue()),
+            ),
+            Span::styled(action.label(), Style::default().fg(colors::fg())),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_commit_type_dialog(frame: &mut Frame, app: &mut App) {
+    let types = &app.repo_config.conventional.types;
+    let height = (types.len() + 2).min(15) as u16;
+    let area = centered_rect(30, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Commit Type ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let items: Vec<ListItem> = types
+        .iter()
+        .map(|t| {
+            ListItem::new(Line::from(Span::styled(
+                t.clone(),
+                Style::default().fg(colors::fg()),
+            )))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, inner, &mut app.commit_type_state);
+}
+
+fn render_commit_scope_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let kind = app
+        .commit_type_state
+        .selected()
+        .and_then(|i| app.repo_config.conventional.types.get(i))
+        .map_or("", |t| t.as_str());
+    let block = Block::default()
+        .title(format!(" Scope for {} (optional) ", kind))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let scopes = &app.repo_config.conventional.scopes;
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.commit_scope_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(
+            if scopes.is_empty() {
+                "Enter with no scope for \"type: \"".to_string()
+            } else {
+                scopes.join(" | ")
+            },
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_commit_date_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 4, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Commit date ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.commit_date_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(
+            "\"yesterday 17:00\", \"2024-05-01 09:30\"; empty for now",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_commit_size_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Large Commit ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::yellow()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let (files, add, del) = app.commit_totals();
+    let limits = &app.repo_config.commit;
+    let limit_text = match (limits.max_files, limits.max_lines) {
+        (Some(f), Some(l)) => format!("limit: {} files / {} lines", f, l),
+        (Some(f), None) => format!("limit: {} files", f),
+        (None, Some(l)) => format!("limit: {} lines", l),
+        (None, None) => String::new(),
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "{} file{}, +{} −{} lines",
+                files,
+                if files == 1 { "" } else { "s" },
+                add,
+                del
+            ),
+            Style::default().fg(colors::yellow()),
+        )),
+        Line::from(Span::styled(limit_text, Style::default().fg(colors::dim()))),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Consider splitting this into smaller commits.",
+            Style::default().fg(colors::fg()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), inner);
+}
+
+fn render_co_author_dialog(frame: &mut Frame, app: &mut App) {
+    let height = (app.co_author_candidates.len() + 2).min(15) as u16;
+    let area = centered_rect(60, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Co-authored-by ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let items: Vec<ListItem> = app
+        .co_author_candidates
+        .iter()
+        .enumerate()
+        .map(|(i, person)| {
+            let checked = app.co_author_marked.contains(&i);
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if checked { "[x] " } else { "[ ] " },
+                    Style::default().fg(if checked {
+                        colors::green()
+                    } else {
+                        colors::dim()
+                    }),
+                ),
+                Span::styled(person.clone(), Style::default().fg(colors::fg())),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, inner, &mut app.co_author_state);
+}
+
+/// Enter / `l` in the Log tab: the whole commit, then its files against the first parent
+fn render_commit_detail(frame: &mut Frame, app: &mut App) {
+    let Some(detail) = &app.commit_detail else {
+        return;
+    };
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(8).max(40),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Commit {} ", detail.id))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let label = |name: &'static str| Span::styled(name, Style::default().fg(colors::dim()));
+    let mut lines = vec![
+        Line::from(vec![
+            label("Author     "),
+            Span::styled(detail.author.clone(), Style::default().fg(colors::fg())),
+        ]),
+        Line::from(vec![
+            label("Date       "),
+            Span::styled(
+                detail.author_date.clone(),
+                Style::default().fg(colors::fg()),
+            ),
+        ]),
+    ];
+    // Rebased, amended or applied by someone else
+    if detail.committer != detail.author || detail.commit_date != detail.author_date {
+        lines.push(Line::from(vec![
+            label("Committer  "),
+            Span::styled(detail.committer.clone(), Style::default().fg(colors::fg())),
+        ]));
+        lines.push(Line::from(vec![
+            label("Committed  "),
+            Span::styled(
+                detail.commit_date.clone(),
+                Style::default().fg(colors::fg()),
+            ),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        label("Parents    "),
+        Span::styled(
+            if detail.parents.is_empty() {
+                "none (root commit)".to_string()
+            } else {
+                detail.parents.join(" + ")
+            },
+            Style::default().fg(if detail.parents.len() > 1 {
+                colors::magenta()
+            } else {
+                colors::yellow()
+            }),
+        ),
+    ]));
+    if !detail.refs.is_empty() {
+        lines.push(Line::from(vec![
+            label("Refs       "),
+            Span::styled(detail.refs.join(", "), Style::default().fg(colors::blue())),
+        ]));
+    }
+    lines.push(Line::from(""));
+
+    // The message gets at most half of the pane; the file list takes the rest
+    let max_message = (inner.height as usize / 2)
+        .saturating_sub(lines.len())
+        .max(1);
+    let message: Vec<&str> = detail.message.lines().collect();
+    for (i, text) in message.iter().take(max_message).enumerate() {
+        let style = if i == 0 {
+            Style::default().fg(colors::fg_bright()).bold()
+        } else {
+            Style::default().fg(colors::fg())
+        };
+        lines.push(Line::from(Span::styled(text.to_string(), style)));
+    }
+    if message.len() > max_message {
+        lines.push(Line::from(Span::styled(
+            format!("… {} more lines", message.len() - max_message),
+            Style::default().fg(colors::dim()),
+        )));
+    }
+    lines.push(Line::from(""));
+    let (added, removed) = detail
+        .files
+        .iter()
+        .filter_map(|f| f.stats)
+        .fold((0, 0), |(a, r), (add, del)| (a + add, r + del));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!(
+                "{} file{} changed",
+                detail.files.len(),
+                if detail.files.len() == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(colors::fg()),
+        ),
+        Span::styled(
+            format!("  +{}", added),
+            Style::default().fg(colors::green()),
+        ),
+        Span::styled(format!(" −{}", removed), Style::default().fg(colors::red())),
+        Span::styled(
+            match detail.diff_parent {
+                _ if detail.parents.len() < 2 => String::new(),
+                Some(n) => format!(
+                    "  vs parent {} ({}), p: next",
+                    n,
+                    detail.parents.get(n - 1).map_or("?", String::as_str)
+                ),
+                None => "  combined: differs from every parent, p: next".to_string(),
+            },
+            Style::default().fg(colors::dim()),
+        ),
+    ]));
+
+    let chunks =
+        Layout::vertical([Constraint::Length(lines.len() as u16), Constraint::Min(0)]).split(inner);
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let items = commit_file_items(&detail.files);
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, chunks[1], &mut app.commit_detail_state);
+}
+
+/// One row per changed file: status letter, path and +/− line counts
+fn commit_file_items(files: &[CommitDetailFile]) -> Vec<ListItem<'static>> {
+    files
+        .iter()
+        .map(|file| {
+            let color = match file.status {
+                'A' => colors::green(),
+                'D' => colors::red(),
+                _ => colors::yellow(),
+            };
+            let mut spans = vec![
+                Span::styled(format!("{} ", file.status), Style::default().fg(color)),
+                Span::styled(file.path.clone(), Style::default().fg(colors::fg())),
+            ];
+            match file.stats {
+                Some((add, del)) => {
+                    spans.push(Span::styled(
+                        format!("  +{}", add),
+                        Style::default().fg(colors::green()),
+                    ));
+                    spans.push(Span::styled(
+                        format!(" −{}", del),
+                        Style::default().fg(colors::red()),
+                    ));
+                }
+                None => spans.push(Span::styled("  binary", Style::default().fg(colors::dim()))),
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect()
+}
+
+/// What pulling would bring in: upstream commits missing from HEAD, then the
+/// combined diffstat since the merge base
+fn render_incoming_view(frame: &mut Frame, app: &mut App) {
+    let Some(incoming) = &app.incoming else {
+        return;
+    };
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(8).max(40),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Incoming from {} ", incoming.upstream))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Commits get at most half of the pane; the file list takes the rest
+    let max_commits = (inner.height as usize / 2).saturating_sub(2).max(1);
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} commit{} to pull",
+            incoming.commits.len(),
+            if incoming.commits.len() == 1 { "" } else { "s" }
+        ),
+        Style::default().fg(colors::fg_bright()).bold(),
+    ))];
+    for (id, subject, author, time) in incoming.commits.iter().take(max_commits) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", id), Style::default().fg(colors::yellow())),
+            Span::styled(subject.clone(), Style::default().fg(colors::fg())),
+            Span::styled(
+                format!("  {}, {}", author, time),
+                Style::default().fg(colors::dim()),
+            ),
+        ]));
+    }
+    if incoming.commits.len() > max_commits {
+        lines.push(Line::from(Span::styled(
+            format!("… {} more", incoming.commits.len() - max_commits),
+            Style::default().fg(colors::dim()),
+        )));
+    }
+    lines.push(Line::from(""));
+    let (added, removed) = incoming
+        .files
+        .iter()
+        .filter_map(|f| f.stats)
+        .fold((0, 0), |(a, r), (add, del)| (a + add, r + del));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!(
+                "{} file{} changed",
+                incoming.files.len(),
+                if incoming.files.len() == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(colors::fg()),
+        ),
+        Span::styled(
+            format!("  +{}", added),
+            Style::default().fg(colors::green()),
+        ),
+        Span::styled(format!(" −{}", removed), Style::default().fg(colors::red())),
+    ]));
+
+    let chunks =
+        Layout::vertical([Constraint::Length(lines.len() as u16), Constraint::Min(0)]).split(inner);
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let list = List::new(commit_file_items(&incoming.files))
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, chunks[1], &mut app.incoming_state);
+}
+
+/// Files changed between two commits of the log, with a +/− total
+fn render_compare_view(frame: &mut Frame, app: &mut App) {
+    let Some(comparison) = &app.comparison else {
+        return;
+    };
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(8).max(40),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Compare {} ", comparison.range()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let (added, removed) = comparison
+        .files
+        .iter()
+        .filter_map(|f| f.stats)
+        .fold((0, 0), |(a, r), (add, del)| (a + add, r + del));
+    let summary = Line::from(vec![
+        Span::styled(
+            format!(
+                "{} file{} changed",
+                comparison.files.len(),
+                if comparison.files.len() == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(colors::fg_bright()).bold(),
+        ),
+        Span::styled(
+            format!("  +{}", added),
+            Style::default().fg(colors::green()),
+        ),
+        Span::styled(format!(" −{}", removed), Style::default().fg(colors::red())),
+    ]);
+    let chunks = Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).split(inner);
+    frame.render_widget(Paragraph::new(summary), chunks[0]);
+
+    let list = List::new(commit_file_items(&comparison.files))
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, chunks[1], &mut app.comparison_state);
+}
+
+/// HEAD's reflog, newest first
+fn render_reflog_view(frame: &mut Frame, app: &mut App) {
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(8).max(40),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Reflog: HEAD ({} entries) ", app.reflog.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let items: Vec<ListItem> = app
+        .reflog
+        .iter()
+        .map(|entry| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<10}", entry.selector),
+                    Style::default().fg(colors::dim()),
+                ),
+                Span::styled(
+                    format!("{:.7} ", entry.id),
+                    Style::default().fg(colors::yellow()),
+                ),
+                Span::styled(entry.message.clone(), Style::default().fg(colors::fg())),
+                Span::styled(
+                    format!("  {}", entry.time),
+                    Style::default().fg(colors::dim()),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, &mut app.reflog_state);
+}
+
+fn render_reflog_reset_dialog(frame: &mut Frame, app: &App) {
+    let Some((_, label)) = &app.pending_reflog_reset else {
+        return;
+    };
+
+    let area = centered_rect(60, 8, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Reset Branch ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::red()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(Span::styled(
+            label.as_str(),
+            Style::default().fg(colors::yellow()),
+        )),
+        Line::from(format!(
+            "{} will point here (git reset --keep);",
+            app.branch_name
+        )),
+        Line::from("commits after it stay reachable from the reflog"),
+        Line::from("Uncommitted changes are kept, or the reset is refused"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter: reset  Esc: back",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(paragraph, inner);
+}
+
+/// Identity switcher: profiles from config.toml, tagged with the one in use
+/// and the one origin's host asks for
+fn render_profile_dialog(frame: &mut Frame, app: &mut App) {
+    let height = (app.profiles.len() + 2).min(15) as u16;
+    let area = centered_rect(60, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let (_, email) = app.current_identity();
+    let expected = app.expected_profile().map(|p| p.name.clone());
+    let block = Block::default()
+        .title(format!(
+            " Identity: {} ",
+            email.as_deref().unwrap_or("not set")
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let items: Vec<ListItem> = app
+        .profiles
+        .iter()
+        .map(|profile| {
+            let mut spans = vec![
+                Span::styled(
+                    format!("{} ", profile.name),
+                    Style::default().fg(colors::fg_bright()),
+                ),
+                Span::styled(
+                    format!("{} <{}>", profile.user_name, profile.email),
+                    Style::default().fg(colors::fg()),
+                ),
+            ];
+            if email.as_ref() == Some(&profile.email) {
+                spans.push(Span::styled(
+                    "  in use",
+                    Style::default().fg(colors::green()),
+                ));
+            }
+            if expected.as_ref() == Some(&profile.name) {
+                spans.push(Span::styled(
+                    "  origin",
+                    Style::default().fg(colors::yellow()),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, inner, &mut app.profile_state);
+}
+
+/// Staged files to pick from for a partial commit (Ctrl+F)
+fn render_commit_file_dialog(frame: &mut Frame, app: &mut App) {
+    let height = (app.commit_file_choices.len() + 2).min(15) as u16;
+    let area = centered_rect(60, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " Commit {} of {} staged ",
+            app.commit_file_marked.len(),
+            app.commit_file_choices.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let items: Vec<ListItem> = app
+        .commit_file_choices
+        .iter()
+        .enumerate()
+        .map(|(i, (path, unstaged))| {
+            let checked = app.commit_file_marked.contains(&i);
+            let mut spans = vec![
+                Span::styled(
+                    if checked { "[x] " } else { "[ ] " },
+                    Style::default().fg(if checked {
+                        colors::green()
+                    } else {
+                        colors::dim()
+                    }),
+                ),
+                Span::styled(path.clone(), Style::default().fg(colors::fg())),
+            ];
+            if *unstaged && checked {
+                spans.push(Span::styled(
+                    "  + unstaged changes",
+                    Style::default().fg(colors::yellow()),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, inner, &mut app.commit_file_state);
+}
+
+/// Full staged diff shown before committing (`[commit] review = true`)
+fn render_commit_review(frame: &mut Frame, app: &App) {
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(4),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let subject = app.commit_message.lines().next().unwrap_or("").trim();
+    let title = if app.is_amending {
+        format!(" Review amend: {} ", subject)
+    } else {
+        format!(" Review commit: {} ", subject)
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::green()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines: Vec<Line> = app
+        .commit_review
+        .iter()
+        .skip(app.commit_review_scroll)
+        .map(|line| {
+            let style = if line.starts_with("diff --git") {
+                Style::default().fg(colors::fg_bright()).bold()
+            } else if line.starts_with("@@") {
+                Style::default().fg(colors::blue())
+            } else if line.starts_with("+++") || line.starts_with("---") {
+                Style::default().fg(colors::dim())
+            } else if line.starts_with('+') {
+                Style::default().fg(colors::green())
+            } else if line.starts_with('-') {
+                Style::default().fg(colors::red())
+            } else {
+                Style::default().fg(colors::fg())
+            };
+            Line::from(Span::styled(line.clone(), style))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_output_view(frame: &mut Frame, app: &App) {
+    let Some(output) = &app.last_output else {
+        return;
+    };
+
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(4),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let (status, border) = if output.is_error {
+        ("failed", colors::red())
+    } else {
+        ("done", colors::green())
+    };
+    let block = Block::default()
+        .title(format!(" {} output ({}) ", output.title, status))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines: Vec<Line> = output
+        .text
+        .lines()
+        .enumerate()
+        .skip(app.output_scroll)
+        .map(|(i, line)| {
+            // Summary line first, raw git output below
+            let style = if i == 0 {
+                Style::default().fg(border).bold()
+            } else {
+                Style::default().fg(colors::fg())
+            };
+            Line::from(Span::styled(line.to_string(), style))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_gitignore_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Add to .gitignore ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Pattern: > ", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.gitignore_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(
+            "Edit to a glob (e.g. *.log) if needed",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_quick_look(frame: &mut Frame, app: &App) {
+    let full = frame.area();
+    let body_height = app.quick_look.as_ref().map_or(1, |q| q.lines.len().max(1)) as u16;
+    // Sits above the hints, leaving the file list (and its cursor) visible for j/k
+    let height = (body_height + 3).min(full.height.saturating_sub(4));
+    let centered = centered_rect(full.width.saturating_sub(8), height, full);
+    let area = Rect {
+        y: full.bottom().saturating_sub(height + 1),
+        ..centered
+    };
+    frame.render_widget(Clear, area);
+
+    let Some(quick_look) = &app.quick_look else {
+        let block = Block::default()
+            .title(" Quick Look ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(colors::blue()));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No file selected",
+                Style::default().fg(colors::dim()),
+            )),
+            inner,
+        );
+        return;
+    };
+
+    let section = if quick_look.staged {
+        "staged"
+    } else {
+        "unstaged"
+    };
+    let block = Block::default()
+        .title(format!(" {} ({}) ", quick_look.path, section))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines: Vec<Line> = quick_look
+        .lines
+        .iter()
+        .map(|line| {
+            let color = match line.chars().next() {
+                Some('+') => colors::green(),
+                Some('-') => colors::red(),
+                _ => colors::blue(),
+            };
+            Line::from(Span::styled(line.clone(), Style::default().fg(color)))
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No text changes to preview",
+            Style::default().fg(colors::dim()),
+        )));
+    }
+    let shown = quick_look
+        .lines
+        .iter()
+        .filter(|l| l.starts_with('+') || l.starts_with('-'))
+        .count();
+    let footer = if quick_look.changed > shown {
+        format!(
+            "{} of {} changed lines, Enter for the full diff",
+            shown, quick_look.changed
+        )
+    } else {
+        format!(
+            "{} changed line{}",
+            quick_look.changed,
+            if quick_look.changed == 1 { "" } else { "s" }
+        )
+    };
+    let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(footer, Style::default().fg(colors::dim()))),
+        chunks[1],
+    );
+}
+
+fn render_review(frame: &mut Frame, app: &App) {
+    let Some(session) = &app.review else {
+        return;
+    };
+    let full = frame.area();
+    let area = centered_rect(
+        full.width.saturating_sub(4),
+        full.height.saturating_sub(4),
+        full,
+    );
+    frame.render_widget(Clear, area);
+
+    let approved = session.files.len() - session.pending();
+    let block = Block::default()
+        .title(format!(
+            " Review: {} of {} approved, started {} ",
+            approved,
+            session.files.len(),
+            format_relative_time(session.started)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::magenta()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if session.files.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "Nothing changed since the review started",
+                Style::default().fg(colors::dim()),
+            )),
+            inner,
+        );
+        return;
+    }
+
+    let list_height = (session.files.len() as u16).min(inner.height / 3).max(1);
+    let chunks = Layout::vertical([
+        Constraint::Length(list_height),
+        Constraint::Length(1),
+        Constraint::Min(0),
+    ])
+    .split(inner);
+
+    let items: Vec<ListItem> = session
+        .files
+        .iter()
+        .map(|file| {
+            let (status, color) = match file.status {
+                FileStatus::Added => ("A", colors::green()),
+                FileStatus::Deleted => ("D", colors::red()),
+                _ => ("M", colors::yellow()),
+            };
+            let (mark, path_style) = if file.approved {
+                (
+                    Span::styled("✓ ", Style::default().fg(colors::green())),
+                    Style::default().fg(colors::dim()),
+                )
+            } else {
+                (Span::raw("  "), Style::default().fg(colors::fg()))
+            };
+            ListItem::new(Line::from(vec![
+                mark,
+                Span::styled(format!("{} ", status), Style::default().fg(color)),
+                Span::styled(file.path.clone(), path_style),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(session.selected));
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let separator = "─".repeat(chunks[1].width as usize);
+    frame.render_widget(
+        Paragraph::new(Span::styled(separator, Style::default().fg(colors::dim()))),
+        chunks[1],
+    );
+
+    let mut lines: Vec<Line> = session
+        .lines
+        .iter()
+        .skip(session.scroll)
+        .map(|line| {
+            let color = match line.chars().next() {
+                Some('+') => colors::green(),
+                Some('-') => colors::red(),
+                Some('@') => colors::blue(),
+                _ => colors::fg(),
+            };
+            Line::from(Span::styled(line.clone(), Style::default().fg(color)))
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No text changes to preview",
+            Style::default().fg(colors::dim()),
+        )));
+    }
+    frame.render_widget(Paragraph::new(lines), chunks[2]);
+}
+
+fn render_move_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let (title, label, note) = match app.move_sources.as_slice() {
+        [source] => (
+            " Rename / Move ".to_string(),
+            "New path: > ",
+            format!("from {}", source),
+        ),
+        sources => (
+            format!(" Move {} Files ", sources.len()),
+            "Into directory: > ",
+            "created if missing".to_string(),
+        ),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(label, Style::default().fg(colors::dim())),
+            Span::styled(&app.move_input, Style::default().fg(colors::fg_bright())),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(note, Style::default().fg(colors::dim()))),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_changelog_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 5, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Changelog Fragment ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::blue()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(colors::dim())),
+            Span::styled(
+                &app.changelog_input,
+                Style::default().fg(colors::fg_bright()),
+            ),
+            Span::styled("█", Style::default().fg(colors::fg_bright())),
+        ]),
+        Line::from(Span::styled(
+            "feature|bugfix|doc|removal|misc: text",
+            Style::default().fg(colors::dim()),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_commit_warning_dialog(frame: &mut Frame, app: &mut App) {
+    let height = (app.commit_problems.len() + 5).min(15) as u16;
+    let area = centered_rect(60, height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Check Staged Changes ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(colors::yellow()));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(2), // Message
+        Constraint::Min(0),    // Problems
+    ])
+    .split(inner);
+
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("{} suspicious hunks staged:", app.commit_problems.len()),
+            Style::default().fg(colors::yellow()),
+        )),
+        chunks[0],
+    );
+
+    let items: Vec<ListItem> = app
+        .commit_problems
+        .iter()
+        .map(|problem| {
+            let color = match problem.kind {
+                CommitProblemKind::ConflictMarker => colors::red(),
+                CommitProblemKind::TrailingWhitespaceOnly => colors::yellow(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(problem.path.clone(), Style::default().fg(colors::fg())),
+                Span::styled(
+                    format!("  hunk {}: ", problem.hunk_index + 1),
+                    Style::default().fg(colors::dim()),
+                ),
+                Span::styled(problem.kind.label(), Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Rgb(0, 0, 0)))
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, chunks[1], &mut app.commit_problems_state);
+}
@@ -1,4 +1,5 @@
 use regex::Regex;
+use std::cmp::Ordering;
 use std::path::Path;
 
 use crate::config::RepoConfig;
@@ -9,6 +10,10 @@ pub struct VersionFile {
     pub path: String,
     pub current_version: String,
     pub pattern: String,
+    /// Command to run after updating (additional_files only)
+    pub post_update: Option<String>,
+    /// Files the command regenerates
+    pub outputs: Vec<String>,
 }
 
 /// Auto-detect version files in the repository
@@ -33,6 +38,8 @@ pub fn detect_version_files(repo_path: &Path, config: &RepoConfig) -> Vec<Versio
                         path: filename.to_string(),
                         current_version: version,
                         pattern: pattern.to_string(),
+                        post_update: None,
+                        outputs: Vec::new(),
                     });
                 }
             }
@@ -49,6 +56,8 @@ pub fn detect_version_files(repo_path: &Path, config: &RepoConfig) -> Vec<Versio
                         path: file_config.path.clone(),
                         current_version: version,
                         pattern: file_config.pattern.clone(),
+                        post_update: file_config.post_update.clone(),
+                        outputs: file_config.outputs.clone(),
                     });
                 }
             }
@@ -63,6 +72,11 @@ pub fn generate_tag_name(version: &str, tag_format: &str) -> String {
     tag_format.replace("{version}", version)
 }
 
+/// Match a branch name against a pattern where `*` matches any run of characters
+pub fn branch_matches(pattern: &str, branch: &str) -> bool {
+    crate::config::glob_match(pattern, branch)
+}
+
 /// Check if input is a valid version format (e.g., 0.1.6, 1.0.0-beta.1)
 pub fn is_valid_version(input: &str) -> bool {
     Regex::new(r"^\d+\.\d+\.\d+")
@@ -70,6 +84,56 @@ pub fn is_valid_version(input: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Order tag names as versions: "v1.10.0" after "v1.9.0", and a pre-release
+/// ("1.0.0-rc.1") before its release; non-numeric parts compare as text
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |tag: &str| {
+        let tag = tag.trim_start_matches(|c: char| !c.is_ascii_digit());
+        match tag.split_once('-') {
+            Some((core, pre)) => (core.to_string(), Some(pre.to_string())),
+            None => (tag.to_string(), None),
+        }
+    };
+    let (a_core, a_pre) = split(a);
+    let (b_core, b_pre) = split(b);
+    natural_cmp(&a_core, &b_core)
+        .then_with(|| match (a_pre, b_pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => natural_cmp(&a, &b),
+        })
+        .then_with(|| a.cmp(b))
+}
+
+/// Compare digit runs by value and everything else character by character
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let chunks = |s: &str| -> Vec<(bool, String)> {
+        let mut chunks: Vec<(bool, String)> = Vec::new();
+        for c in s.chars() {
+            let digit = c.is_ascii_digit();
+            match chunks.last_mut() {
+                Some((d, chunk)) if *d == digit => chunk.push(c),
+                _ => chunks.push((digit, c.to_string())),
+            }
+        }
+        chunks
+    };
+    for (x, y) in chunks(a).iter().zip(chunks(b).iter()) {
+        let order = match (x, y) {
+            ((true, x), (true, y)) => {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            ((_, x), (_, y)) => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    chunks(a).len().cmp(&chunks(b).len())
+}
+
 /// Update version file content with new version
 pub fn update_version_content(content: &str, pattern: &str, new_version: &str) -> String {
     let old_pattern = pattern.replace("{version}", r"[0-9]+\.[0-9]+\.[0-9]+[a-zA-Z0-9\.\-]*");
@@ -81,6 +145,27 @@ pub fn update_version_content(content: &str, pattern: &str, new_version: &str) -
     }
 }
 
+/// Run a file's post-update command in the repository root
+pub fn run_post_update(
+    repo_path: &Path,
+    command: &str,
+    new_version: &str,
+) -> std::result::Result<(), String> {
+    let command = command.replace("{version}", new_version);
+    let output = std::process::Command::new("sh")
+        .current_dir(repo_path)
+        .args(["-c", &command])
+        .env("SIORI_VERSION", new_version)
+        .output()
+        .map_err(|e| format!("Failed to run `{}`: {e}", command))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("`{}` failed: {}", command, stderr.trim()))
+    }
+}
+
 // === Extractors ===
 
 fn extract_version(content: &str, filename: &str) -> Option<String> {
@@ -159,6 +244,45 @@ version = "0.1.5"
         assert_eq!(generate_tag_name("0.1.6", "{version}"), "0.1.6");
     }
 
+    #[test]
+    fn test_branch_tag_format() {
+        assert!(branch_matches("develop", "develop"));
+        assert!(!branch_matches("develop", "develop-2"));
+        assert!(branch_matches("release/*", "release/1.2"));
+        assert!(!branch_matches("release/*", "main"));
+
+        let config: RepoConfig = toml::from_str(
+            r#"
+[version]
+[[version.branch_tag_formats]]
+branch = "develop"
+format = "v{version}-beta"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.version.tag_format_for("develop"), "v{version}-beta");
+        assert_eq!(config.version.tag_format_for("main"), "v{version}");
+        assert_eq!(
+            generate_tag_name("1.2.0", config.version.tag_format_for("develop")),
+            "v1.2.0-beta"
+        );
+    }
+
+    #[test]
+    fn test_run_post_update() {
+        let dir = std::env::temp_dir().join(format!("siori-post-update-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        run_post_update(&dir, "echo {version}-$SIORI_VERSION > out.txt", "1.2.3").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("out.txt")).unwrap().trim(),
+            "1.2.3-1.2.3"
+        );
+        assert!(run_post_update(&dir, "exit 3", "1.2.3").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_is_valid_version() {
         assert!(is_valid_version("0.1.6"));
@@ -169,6 +293,18 @@ version = "0.1.5"
         assert!(!is_valid_version(""));
     }
 
+    #[test]
+    fn test_compare_versions() {
+        let mut tags = vec!["v1.10.0", "v1.2.0", "v1.9.0-rc.2", "v1.9.0", "v1.9.0-rc.10"];
+        tags.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(
+            tags,
+            ["v1.2.0", "v1.9.0-rc.2", "v1.9.0-rc.10", "v1.9.0", "v1.10.0"]
+        );
+        assert_eq!(compare_versions("0.3.0", "v0.3.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+    }
+
     #[test]
     fn test_update_version_content() {
         let content = r#"version = "0.1.5""#;
@@ -1,6 +1,8 @@
 use siori::app::{
-    FileEntry, FileStatus, PendingDiscard, PendingDiscardAction, execute_pending_discard_with,
+    FileEntry, FileStatus, HiddenFlag, PendingDiscard, PendingDiscardAction,
+    execute_pending_discard_with, split_marked_for_staging,
 };
+use std::collections::HashSet;
 use std::path::Path;
 
 fn file(path: &str, status: FileStatus, staged: bool) -> FileEntry {
@@ -9,6 +11,15 @@ fn file(path: &str, status: FileStatus, staged: bool) -> FileEntry {
         status,
         staged,
         diff_stats: None,
+        text_issues: Vec::new(),
+        intent_to_add: false,
+        hidden: None,
+        mode_change: None,
+        binary_size: None,
+        symlink_target: None,
+        nested_repo: false,
+        generated: false,
+        conflicts: None,
     }
 }
 
@@ -88,6 +99,7 @@ fn bulk_discard_counts_success_and_failure() {
                     Ok(())
                 }
             },
+            |_, _| Ok(String::new()),
             |_, _| Ok(()),
         );
         match result {
@@ -99,3 +111,28 @@ fn bulk_discard_counts_success_and_failure() {
     assert_eq!(success, 2);
     assert_eq!(failure, 1);
 }
+
+#[test]
+fn marked_files_split_into_stage_and_unstage() {
+    let mut hidden = file("local.toml", FileStatus::Modified, false);
+    hidden.hidden = Some(HiddenFlag::SkipWorktree);
+    let files = vec![
+        file("a.rs", FileStatus::Modified, true),
+        file("a.rs", FileStatus::Modified, false),
+        file("b.rs", FileStatus::Added, true),
+        file("new.txt", FileStatus::Untracked, false),
+        hidden,
+    ];
+    let marked: HashSet<(String, bool)> = [
+        ("a.rs".to_string(), false),
+        ("b.rs".to_string(), true),
+        ("new.txt".to_string(), false),
+        ("local.toml".to_string(), false),
+    ]
+    .into_iter()
+    .collect();
+
+    let (to_stage, to_unstage) = split_marked_for_staging(&files, &marked);
+    assert_eq!(to_stage, vec!["a.rs", "new.txt"]);
+    assert_eq!(to_unstage, vec!["b.rs"]);
+}
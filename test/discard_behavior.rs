@@ -1,5 +1,6 @@
 use siori::app::{
-    FileEntry, FileStatus, PendingDiscard, PendingDiscardAction, execute_pending_discard_with,
+    FileEntry, FileStatus, HiddenFlag, PendingDiscard, PendingDiscardAction,
+    execute_pending_discard_with,
 };
 use std::path::Path;
 
@@ -9,6 +10,15 @@ fn file(path: &str, status: FileStatus, staged: bool) -> FileEntry {
         status,
         staged,
         diff_stats: None,
+        text_issues: Vec::new(),
+        intent_to_add: false,
+        hidden: None,
+        mode_change: None,
+        binary_size: None,
+        symlink_target: None,
+        nested_repo: false,
+        generated: false,
+        conflicts: None,
     }
 }
 
@@ -52,6 +62,24 @@ fn staged_items_are_rejected_before_confirm() {
     assert_eq!(error, "Unstage file first (Space)");
 }
 
+#[test]
+fn intent_to_add_items_are_rejected_before_confirm() {
+    let mut entry = file("new.rs", FileStatus::Added, false);
+    entry.intent_to_add = true;
+    let error = PendingDiscard::for_file(&entry).expect_err("intent-to-add must be rejected");
+
+    assert_eq!(error, "Undo intent-to-add first (N)");
+}
+
+#[test]
+fn hidden_items_are_rejected_before_confirm() {
+    let mut entry = file("config/local.toml", FileStatus::Modified, false);
+    entry.hidden = Some(HiddenFlag::SkipWorktree);
+    let error = PendingDiscard::for_file(&entry).expect_err("hidden files must be rejected");
+
+    assert_eq!(error, "Unhide file first (H)");
+}
+
 #[test]
 fn restore_action_uses_restore_executor() {
     let pending = PendingDiscard {
@@ -72,8 +100,9 @@ fn restore_action_uses_restore_executor() {
         },
         |_, _| {
             trash_calls += 1;
-            Ok(())
+            Ok(String::new())
         },
+        |_, _| Ok(()),
     )
     .expect("restore should succeed");
 
@@ -102,12 +131,13 @@ fn trash_action_uses_trash_executor() {
             trash_calls += 1;
             assert_eq!(repo_path, Path::new("/repo"));
             assert_eq!(path, "notes.txt");
-            Ok(())
+            Ok(".git/siori/trash/1700000000".to_string())
         },
+        |_, _| Ok(()),
     )
     .expect("trash should succeed");
 
-    assert_eq!(result, "Moved to trash: notes.txt");
+    assert_eq!(result, "Moved to .git/siori/trash/1700000000: notes.txt");
     assert_eq!(restore_calls, 0);
     assert_eq!(trash_calls, 1);
 }
@@ -124,8 +154,34 @@ fn trash_failure_is_returned_without_fallback_delete() {
         &pending,
         |_, _| Ok(()),
         |_, _| Err("Move to trash failed: permission denied".to_string()),
+        |_, _| Ok(()),
     )
     .expect_err("trash failures should bubble up");
 
     assert_eq!(error, "Move to trash failed: permission denied");
 }
+
+#[test]
+fn delete_action_uses_delete_executor() {
+    let pending = PendingDiscard {
+        path: "build/".to_string(),
+        action: PendingDiscardAction::DeleteUntracked,
+    };
+    let mut delete_calls = 0;
+
+    let result = execute_pending_discard_with(
+        Path::new("/repo"),
+        &pending,
+        |_, _| Err("restore must not run".to_string()),
+        |_, _| Err("trash must not run".to_string()),
+        |_, path| {
+            delete_calls += 1;
+            assert_eq!(path, "build/");
+            Ok(())
+        },
+    )
+    .expect("delete should succeed");
+
+    assert_eq!(result, "Deleted: build/");
+    assert_eq!(delete_calls, 1);
+}